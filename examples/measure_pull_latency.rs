@@ -0,0 +1,55 @@
+/** Example program that measures how quickly a blocking pull returns after a sample was pushed,
+for each of the inlet's wait strategies (see lsl::WaitStrategy). Run it (in release mode) on the
+machine that is going to receive the data to decide whether spinning is worth its CPU cost. */
+use lsl::{ExPushable, Pullable}; // traits used by the outlet and inlet
+
+const SAMPLES: usize = 500;
+
+fn main() -> Result<(), lsl::Error> {
+    // we're sending a stream to ourselves, with a unique source id so that we find our own
+    let source_id = format!("latency-{}", std::process::id());
+    let info = lsl::StreamInfo::new(
+        "LatencyTest",
+        "Test",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Float32,
+        &source_id,
+    )?;
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360)?;
+    let mut inlet = lsl::InletBuilder::resolve("source_id", &source_id)
+        .timeout(5.0)
+        .open()?;
+
+    // the sender pushes a sample at irregular intervals, time-stamped with the push time, so that
+    // the receiver can compute the time that it took to receive it
+    let sender = std::thread::spawn(move || -> Result<(), lsl::Error> {
+        for k in 0..2 * SAMPLES {
+            std::thread::sleep(std::time::Duration::from_micros(
+                2000 + (k as u64 * 7919) % 3000,
+            ));
+            outlet.push_sample_ex(&vec![k as f32], lsl::local_clock(), true)?;
+        }
+        Ok(())
+    });
+
+    for &strategy in &[
+        lsl::WaitStrategy::Block,
+        lsl::WaitStrategy::SpinThenBlock(0.01),
+    ] {
+        inlet.set_wait_strategy(strategy);
+        let mut latencies = Vec::with_capacity(SAMPLES);
+        for _ in 0..SAMPLES {
+            let (_, ts): (Vec<f32>, _) = inlet.pull_sample(lsl::FOREVER)?;
+            latencies.push(lsl::local_clock() - ts);
+        }
+        latencies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        println!(
+            "{:?}: median {:.1} us, 99th percentile {:.1} us",
+            strategy,
+            latencies[SAMPLES / 2] * 1e6,
+            latencies[SAMPLES * 99 / 100] * 1e6
+        );
+    }
+    sender.join().unwrap()
+}
@@ -4,13 +4,11 @@ use lsl;
 use lsl::Pullable; // trait used by the inlet
 
 fn main() -> Result<(), lsl::Error> {
-    // first we're resolving a stream with content type EEG on the network
+    // first we're resolving a stream with content type EEG on the network and create an inlet to
+    // read from it, using the recommended default arguments for the max buffer length, chunk size,
+    // and whether LSL should attempt to recover the stream (see lsl::StreamInlet::new())
     println!("Resolving EEG stream...");
-    let res = lsl::resolve_byprop("type", "EEG", 1, lsl::FOREVER)?;
-
-    // next we're creating an inlet to read from it, using the recommended default arguments
-    // for the max buffer length, chunk size, and whether LSL should attempt to recover the stream
-    let inl = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    let inl = lsl::InletBuilder::resolve("type", "EEG").open()?;
 
     // now we're reading data in a loop and print it as we go
     println!("Reading data...");
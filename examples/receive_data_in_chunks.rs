@@ -4,12 +4,10 @@ use lsl;
 use lsl::Pullable; // trait that provides the pull_chunk method
 
 fn main() -> Result<(), lsl::Error> {
-    // first we're resolving a stream with content type EEG on the network
+    // first we're resolving a stream with content type EEG on the network and create an inlet to
+    // read from it, using default parameters
     println!("Resolving EEG stream...");
-    let res = lsl::resolve_byprop("type", "EEG", 1, lsl::FOREVER)?;
-
-    // next we create an inlet to read from the stream, using default parameters
-    let inl = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    let inl = lsl::InletBuilder::resolve("type", "EEG").open()?;
 
     println!("Reading data...");
     loop {
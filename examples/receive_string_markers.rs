@@ -3,12 +3,10 @@ use lsl;
 use lsl::Pullable; // trait that provides the pull_sample method
 
 fn main() -> Result<(), lsl::Error> {
-    // first we resolve a stream with content type Markers
+    // first we resolve a stream with content type Markers and create an inlet to read from it,
+    // using the default parameters
     println!("Resolving Marker stream...");
-    let res = lsl::resolve_byprop("type", "Markers", 1, lsl::FOREVER)?;
-
-    // next we create an inlet to read from the stream, using the default parameter
-    let inl = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    let inl = lsl::InletBuilder::resolve("type", "Markers").open()?;
 
     println!("Reading data...");
     loop {
@@ -18,7 +18,7 @@ fn main() -> Result<(), lsl::Error> {
     // conventions (see https://github.com/sccn/xdf/wiki/Meta-Data#stream-content-types), we
     // strongly advise to follow those for max interoperability. If you have other data and want to
     // contribute to standardizing LSL meta-data for it, PRs against that spec are always encouraged.
-    let mut channels = info.desc().append_child("channels");
+    let mut channels = info.desc_mut().append_child("channels");
     // here we're declaring some channel names for our 8 channels
     for c in &["C3", "C4", "Cz", "FPz", "POz", "CPz", "O1", "O2"] {
         channels.append_child("channel")
@@ -0,0 +1,165 @@
+/*!
+Derive macros for the `lsl` crate.
+
+These are re-exported by `lsl` when its `derive` feature is enabled; see the documentation of
+`lsl::SampleLayout` for how to use them.
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/**
+Derive `lsl::SampleLayout` and `lsl::ExPushable` (for `StreamOutlet`) for a struct whose fields
+are the channels of a sample.
+
+All fields must be of the same numeric type (one of the types implementing `lsl::LslSample`).
+The channel labels are the field names, unless overridden via `#[lsl(label = "...")]`.
+*/
+#[proc_macro_derive(LslPush, attributes(lsl))]
+pub fn derive_lsl_push(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_push(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/**
+Derive `lsl::LslPull` for a struct whose fields are the channels of a sample, which allows to
+pull samples into it via `StreamInlet::pull_as()`.
+
+All fields must be of the same numeric type (one of the types implementing `lsl::LslSample`).
+By default, the fields are filled from the channels in order. With `#[lsl(by_label)]` on the
+struct, each field is instead filled from the channel whose label (in the stream's meta-data)
+matches the field name, or the label given via `#[lsl(label = "...")]` on the field.
+*/
+#[proc_macro_derive(LslPull, attributes(lsl))]
+pub fn derive_lsl_pull(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_pull(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// a field of a struct that is mapped to a channel
+struct Channel {
+    ident: Ident,
+    label: String,
+}
+
+// The channels of a struct and their common value type.
+fn parse_layout(input: &DeriveInput) -> syn::Result<(Vec<Channel>, Type)> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generic structs are not supported",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "expected a struct with named fields",
+                ))
+            }
+        },
+        _ => return Err(syn::Error::new(input.ident.span(), "expected a struct")),
+    };
+    let type_name = |ty: &Type| quote!(#ty).to_string();
+    let ty = fields[0].ty.clone();
+    let mut channels = vec![];
+    for field in fields {
+        if type_name(&field.ty) != type_name(&ty) {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "all fields must have the same type",
+            ));
+        }
+        let ident = field.ident.clone().unwrap();
+        let mut label = ident.to_string();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("lsl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("label") {
+                    label = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported lsl attribute"))
+                }
+            })?;
+        }
+        channels.push(Channel { ident, label });
+    }
+    Ok((channels, ty))
+}
+
+// Implementation of SampleLayout for a struct.
+fn expand_layout(name: &Ident, channels: &[Channel], ty: &Type) -> TokenStream2 {
+    let labels = channels.iter().map(|c| &c.label);
+    quote! {
+        impl ::lsl::SampleLayout for #name {
+            type Value = #ty;
+            const LABELS: &'static [&'static str] = &[#(#labels),*];
+        }
+    }
+}
+
+fn expand_push(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (channels, ty) = parse_layout(input)?;
+    let layout = expand_layout(name, &channels, &ty);
+    let count = channels.len();
+    let fields = channels.iter().map(|c| &c.ident);
+    Ok(quote! {
+        #layout
+
+        impl ::lsl::ExPushable<#name> for ::lsl::StreamOutlet {
+            fn push_sample_ex(
+                &self,
+                data: &#name,
+                timestamp: f64,
+                pushthrough: bool,
+            ) -> ::std::result::Result<(), ::lsl::Error> {
+                let sample: [#ty; #count] = [#(data.#fields),*];
+                ::lsl::ExPushable::push_sample_ex(self, &sample, timestamp, pushthrough)
+            }
+        }
+    })
+}
+
+fn expand_pull(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (channels, ty) = parse_layout(input)?;
+    let mut by_label = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("lsl")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("by_label") {
+                by_label = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported lsl attribute"))
+            }
+        })?;
+    }
+    let labels = channels.iter().map(|c| &c.label);
+    let fields = channels.iter().map(|c| &c.ident);
+    let positions = 0..channels.len();
+    Ok(quote! {
+        impl ::lsl::LslPull for #name {
+            type Value = #ty;
+            const LABELS: &'static [&'static str] = &[#(#labels),*];
+            const BY_LABEL: bool = #by_label;
+
+            fn from_channels(values: &[#ty], indices: &[usize]) -> Self {
+                #name {
+                    #(#fields: values[indices[#positions]]),*
+                }
+            }
+        }
+    })
+}
@@ -1,11 +1,44 @@
 use std::env;
+use std::fs;
+
+// the oldest liblsl release whose sources can be built (e.g., Int64 streams on Windows need 1.14)
+const MIN_LIBLSL_VERSION: (u32, u32) = (1, 14);
 
 fn main() {
     // TODO: find out if liblsl already present on system and usable (if so, link to that instead)
     // println!("cargo:warning={}", "rebuilding...");
+    check_liblsl_version();
     build_liblsl();
 }
 
+// Check that the liblsl sources are at least of the minimum version, as declared in their cmake
+// project (e.g., `project(liblsl VERSION 1.14.0 ...)`)
+fn check_liblsl_version() {
+    let cmakelists = fs::read_to_string("liblsl/CMakeLists.txt")
+        .expect("liblsl sources not found (check out the lsl-sys/liblsl submodule)");
+    let version = cmakelists
+        .split("project")
+        .skip(1)
+        .filter_map(|call| call.split(')').next()?.split("VERSION").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .map(|version| {
+            let mut parts = version.split('.').map(|part| part.parse::<u32>());
+            (parts.next(), parts.next())
+        })
+        .find_map(|parts| match parts {
+            (Some(Ok(major)), Some(Ok(minor))) => Some((major, minor)),
+            _ => None,
+        })
+        .expect("could not determine the version of the liblsl sources");
+    if version < MIN_LIBLSL_VERSION {
+        panic!(
+            "liblsl {}.{} is too old, at least {}.{} is required (update the lsl-sys/liblsl \
+             submodule)",
+            version.0, version.1, MIN_LIBLSL_VERSION.0, MIN_LIBLSL_VERSION.1
+        );
+    }
+}
+
 // Build the liblsl library from source using cmake
 fn build_liblsl() {
     let target = env::var("TARGET").unwrap();
@@ -0,0 +1,277 @@
+/*!
+Async API for inlets and outlets (requires the `tokio`, `async-std` or `smol` feature).
+
+The native library only offers blocking operations, which must not be called directly from async
+tasks since they would stall a worker thread of the runtime. The types in this module run them
+on a pool of blocking threads instead, so that LSL consumers can be integrated into async
+applications (e.g., servers) without blocking the runtime. The API is the same for all runtimes;
+only the thread pool differs:
+
+* With the `tokio` feature, the runtime's blocking thread pool is used (via
+  `tokio::task::spawn_blocking()`), so the futures must be awaited within a tokio runtime.
+* With the `async-std` or `smol` feature, the thread pool of the `blocking` crate (which is also
+  used by these runtimes) is used; this works on any runtime, and takes precedence over the
+  `tokio` feature if both are enabled.
+
+```no_run
+use lsl::aio::{AsyncStreamInlet, AsyncStreamOutlet};
+use lsl::InletBuilder;
+
+async fn forward(outlet: AsyncStreamOutlet) -> Result<(), lsl::Error> {
+    let inlet = AsyncStreamInlet::new(InletBuilder::resolve("type", "EEG").open()?);
+    inlet.open_stream(5.0).await?;
+    outlet.wait_for_consumers(lsl::FOREVER).await?;
+    loop {
+        let (sample, ts) = inlet.pull_sample::<f32>(lsl::FOREVER).await?;
+        outlet.push_sample_ex(&sample, ts, true)?;
+    }
+}
+```
+*/
+
+#[cfg(feature = "tokio")]
+use crate::worker::{Handle, Task, Worker};
+#[cfg(feature = "tokio")]
+use crate::ContinuousResolver;
+use crate::{Error, ExPushable, Pullable, Pushable, Result, StreamInfo, StreamInlet, StreamOutlet};
+use std::sync::{Arc, Mutex};
+
+/**
+An inlet whose operations can be awaited.
+
+The operations are executed one at a time, in the order in which they were issued (e.g., an
+`info()` call waits until a pending `pull_sample()` has returned).
+*/
+#[derive(Debug, Clone)]
+pub struct AsyncStreamInlet {
+    inlet: Arc<Mutex<StreamInlet>>,
+}
+
+impl AsyncStreamInlet {
+    /// Wrap an inlet (e.g., one created via `StreamInlet::new()` or `InletBuilder`).
+    pub fn new(inlet: StreamInlet) -> AsyncStreamInlet {
+        AsyncStreamInlet {
+            inlet: Arc::new(Mutex::new(inlet)),
+        }
+    }
+
+    /// Pull the next sample (see `Pullable::pull_sample()`).
+    pub async fn pull_sample<T: Send + 'static>(&self, timeout: f64) -> Result<(Vec<T>, f64)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.run(move |inlet| inlet.pull_sample(timeout)).await
+    }
+
+    /// Pull a chunk of the currently buffered samples (see `Pullable::pull_chunk()`).
+    pub async fn pull_chunk<T: Send + 'static>(&self) -> Result<(Vec<Vec<T>>, Vec<f64>)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.run(|inlet| inlet.pull_chunk()).await
+    }
+
+    /// Retrieve the complete information of the stream (see `StreamInlet::info()`).
+    pub async fn info(&self, timeout: f64) -> Result<StreamInfo> {
+        self.run(move |inlet| inlet.info(timeout)).await
+    }
+
+    /// Subscribe to the data stream (see `StreamInlet::open_stream()`).
+    pub async fn open_stream(&self, timeout: f64) -> Result<()> {
+        self.run(move |inlet| inlet.open_stream(timeout)).await
+    }
+
+    /// Retrieve an estimate of the time correction offset (see `StreamInlet::time_correction()`).
+    pub async fn time_correction(&self, timeout: f64) -> Result<f64> {
+        self.run(move |inlet| inlet.time_correction(timeout)).await
+    }
+
+    /**
+    Retrieve an estimate of the time correction offset, along with the remote time and the
+    uncertainty of the estimate (see `StreamInlet::time_correction_ex()`).
+    */
+    pub async fn time_correction_ex(&self, timeout: f64) -> Result<(f64, f64, f64)> {
+        self.run(move |inlet| inlet.time_correction_ex(timeout))
+            .await
+    }
+
+    /**
+    Unwrap the inlet, e.g., to use it from a dedicated thread.
+
+    Returns `None` if the inlet is still shared with other clones of this wrapper.
+    */
+    pub fn into_inner(self) -> Option<StreamInlet> {
+        let inlet = Arc::try_unwrap(self.inlet).ok()?;
+        Some(inlet.into_inner().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    // Run a blocking operation on the inlet.
+    async fn run<R, F>(&self, f: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&StreamInlet) -> Result<R> + Send + 'static,
+    {
+        let inlet = self.inlet.clone();
+        unblock(move || {
+            let inlet = inlet.lock().map_err(|_| Error::Internal)?;
+            f(&inlet)
+        })
+        .await?
+    }
+}
+
+/**
+An outlet for use in async applications.
+
+Pushing data into an outlet never blocks (the data are buffered and sent by a background
+thread of the native library), so the push methods can be called directly from async tasks.
+Waiting for consumers, however, does block, and is therefore offered as an awaitable operation.
+*/
+#[derive(Debug, Clone)]
+pub struct AsyncStreamOutlet {
+    outlet: Arc<StreamOutlet>,
+}
+
+impl AsyncStreamOutlet {
+    /// Wrap an outlet.
+    pub fn new(outlet: StreamOutlet) -> AsyncStreamOutlet {
+        AsyncStreamOutlet {
+            outlet: Arc::new(outlet),
+        }
+    }
+
+    /// Push a sample (see `Pushable::push_sample()`).
+    pub fn push_sample<T>(&self, data: &T) -> Result<()>
+    where
+        StreamOutlet: Pushable<T>,
+    {
+        self.outlet.push_sample(data)
+    }
+
+    /// Push a chunk of samples (see `Pushable::push_chunk()`).
+    pub fn push_chunk<T>(&self, data: &[T]) -> Result<()>
+    where
+        StreamOutlet: Pushable<T>,
+    {
+        self.outlet.push_chunk(data)
+    }
+
+    /// Push a sample with a given time stamp (see `ExPushable::push_sample_ex()`).
+    pub fn push_sample_ex<T>(&self, data: &T, timestamp: f64, pushthrough: bool) -> Result<()>
+    where
+        StreamOutlet: ExPushable<T>,
+    {
+        self.outlet.push_sample_ex(data, timestamp, pushthrough)
+    }
+
+    /// Check whether consumers are currently registered (see `StreamOutlet::have_consumers()`).
+    pub fn have_consumers(&self) -> bool {
+        self.outlet.have_consumers()
+    }
+
+    /**
+    Wait until some consumer shows up (see `StreamOutlet::wait_for_consumers()`).
+
+    Returns true if a consumer showed up, or false if the timeout expired.
+    */
+    pub async fn wait_for_consumers(&self, timeout: f64) -> Result<bool> {
+        let outlet = self.outlet.clone();
+        unblock(move || outlet.wait_for_consumers(timeout)).await
+    }
+
+    /// The underlying outlet.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+// Run a blocking function on the runtime's blocking thread pool.
+#[cfg(not(any(feature = "async-std", feature = "smol")))]
+async fn unblock<R, F>(f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: FnOnce() -> R + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => Ok(result),
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        // the runtime is shutting down
+        Err(_) => Err(Error::Internal),
+    }
+}
+
+// Run a blocking function on the thread pool of the `blocking` crate.
+#[cfg(any(feature = "async-std", feature = "smol"))]
+async fn unblock<R, F>(f: F) -> Result<R>
+where
+    R: Send + 'static,
+    F: FnOnce() -> R + Send + 'static,
+{
+    Ok(blocking::unblock(f).await)
+}
+
+// the task that updates the sender of `ContinuousResolver::watch()`
+#[cfg(feature = "tokio")]
+struct WatchTask {
+    resolver: ContinuousResolver,
+    sender: Arc<tokio::sync::watch::Sender<Vec<StreamInfo>>>,
+    interval: f64,
+    uids: Vec<String>,
+}
+
+#[cfg(feature = "tokio")]
+impl Task for WatchTask {
+    fn run_once(&mut self, handle: &Handle) -> Result<()> {
+        // transient errors leave the last known set in place
+        if let Ok(mut streams) = self.resolver.results() {
+            streams.sort_by_key(|info| info.uid());
+            let current: Vec<_> = streams.iter().map(|info| info.uid()).collect();
+            if current != self.uids {
+                self.uids = current;
+                self.sender.send_replace(streams);
+            }
+        }
+        handle.wait(self.interval);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ContinuousResolver {
+    /**
+    Watch the set of visible streams (requires the `tokio` feature).
+
+    A background worker (see `lsl::worker`) queries the resolver every `interval` seconds, and
+    updates the returned receiver with the stream infos (ordered by uid) whenever streams have
+    appeared or disappeared, so that async code can await changes via `changed()` instead of
+    polling `results()`. The worker is stopped once all receivers have been dropped. This must be
+    called within a tokio runtime.
+
+    ```no_run
+    # async fn f() -> Result<(), lsl::Error> {
+    let mut streams = lsl::ContinuousResolver::new(5.0)?.watch(0.5);
+    while streams.changed().await.is_ok() {
+        let names: Vec<_> = streams.borrow().iter().map(|i| i.stream_name()).collect();
+        println!("visible streams: {:?}", names);
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn watch(self, interval: f64) -> tokio::sync::watch::Receiver<Vec<StreamInfo>> {
+        let (sender, receiver) = tokio::sync::watch::channel(vec![]);
+        let sender = Arc::new(sender);
+        let worker = Worker::spawn(WatchTask {
+            resolver: self,
+            sender: sender.clone(),
+            interval,
+            uids: vec![],
+        });
+        tokio::spawn(async move {
+            sender.closed().await;
+            // (stopping joins the worker's thread, which must not block the runtime)
+            tokio::task::spawn_blocking(move || worker.stop());
+        });
+        receiver
+    }
+}
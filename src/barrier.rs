@@ -0,0 +1,119 @@
+/*!
+Coordinated start/stop of multiple applications.
+
+Multi-machine experiments frequently need several producer applications to begin (or end) a
+phase at the same time, e.g., to start recording only once all devices are up. A `Barrier`
+implements this on top of LSL itself: each participant announces its arrival by serving a small
+marker stream whose name is derived from the session name, and waits until the announcements of
+all participants are visible on the network.
+
+```no_run
+use lsl::barrier::Barrier;
+
+fn main() -> Result<(), lsl::Error> {
+    // each of the 3 participating apps runs this
+    let start = Barrier::arm("session1/start", 3)?;
+    start.wait(60.0)?;
+    // ... all 3 apps proceed at (approximately) the same time
+    Ok(())
+}
+```
+
+Since the announcements are streams, a participant must keep its `Barrier` alive for a while
+after passing it, so that the slower participants can still see it; a convenient way is to keep
+it until the end of the phase that it guards (e.g., until the next barrier has been passed).
+Use a different session name for each barrier (e.g., "session1/start" and "session1/stop").
+*/
+
+use crate::{
+    local_clock, resolve_bypred, ChannelFormat, Error, Result, StreamInfo, StreamOutlet,
+    IRREGULAR_RATE,
+};
+
+/// Content type of the streams that announce the participants of a barrier.
+pub const STREAM_TYPE: &str = "Barrier";
+
+/// A barrier that is passed once a given number of participants have armed it.
+#[derive(Debug)]
+pub struct Barrier {
+    session: String,
+    participants: usize,
+    // the announcement of this participant; keeps being served while the barrier is alive
+    outlet: StreamOutlet,
+}
+
+impl Barrier {
+    /**
+    Arm a barrier, i.e., announce that this participant has arrived.
+
+    Arguments:
+    * `session`: Name of the barrier, which must be the same for all participants (and should
+      be unique on the network). Must not contain single quotes.
+    * `participants`: The total number of participants (including this one).
+    */
+    pub fn arm(session: &str, participants: usize) -> Result<Barrier> {
+        if session.contains('\'') || participants == 0 {
+            return Err(Error::BadArgument);
+        }
+        let info = StreamInfo::new(
+            &stream_name(session),
+            STREAM_TYPE,
+            1,
+            IRREGULAR_RATE,
+            ChannelFormat::String,
+            "",
+        )?;
+        Ok(Barrier {
+            session: session.to_string(),
+            participants,
+            outlet: StreamOutlet::new(&info, 0, 1)?,
+        })
+    }
+
+    /**
+    Wait until all participants have armed the barrier.
+
+    Arguments:
+    * `timeout`: The maximum time to wait, in seconds. You can use the value `lsl::FOREVER` to
+      have no timeout. If the timeout expires, an `Error::Timeout` is returned.
+    */
+    pub fn wait(&self, timeout: f64) -> Result<()> {
+        let pred = format!(
+            "name='{}' and type='{}'",
+            stream_name(&self.session),
+            STREAM_TYPE
+        );
+        let deadline = local_clock() + timeout;
+        loop {
+            let remaining = deadline - local_clock();
+            if remaining <= 0.0 {
+                return Err(Error::Timeout);
+            }
+            // each resolve call returns as soon as the minimum number of streams was seen
+            let arrived = resolve_bypred(&pred, self.participants as i32, remaining.min(5.0))?;
+            if arrived.len() >= self.participants {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Name of the barrier.
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// The total number of participants.
+    pub fn participants(&self) -> usize {
+        self.participants
+    }
+
+    /// The stream that announces this participant.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+// name of the streams that announce the participants of a given barrier
+fn stream_name(session: &str) -> String {
+    format!("barrier:{}", session)
+}
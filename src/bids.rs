@@ -0,0 +1,121 @@
+/*!
+Carrying BIDS entities in the meta-data of a stream.
+
+Labs that convert their recordings into the [BIDS](https://bids.neuroimaging.io) format need the
+task, subject, session and run of each recording, which are best declared by the sending program
+(e.g., the experiment script) rather than reconstructed from file names. A `BidsInfo` writes these
+entities into the meta-data of a stream, and reads them back on the receiving side (e.g., in the
+recorder or the conversion script):
+
+```no_run
+use lsl::bids::BidsInfo;
+use lsl::StreamInfo;
+
+fn main() -> Result<(), lsl::Error> {
+    let mut info = StreamInfo::template_markers("Experiment", "exp-1")?;
+    let bids = BidsInfo {
+        task: "nback".to_string(),
+        subject: "07".to_string(),
+        session: "pre".to_string(),
+        run: Some(2),
+    };
+    bids.describe(&mut info)?;
+    // ... on the receiving side (with the full info, e.g., from StreamInlet::info()):
+    let bids = BidsInfo::from_info(&info);
+    assert_eq!(bids.file_prefix(), "sub-07_ses-pre_task-nback_run-2");
+    Ok(())
+}
+```
+
+The subject is stored as `desc/subject/id` (as in `lsl::meta::Subject`), and the other entities
+as `desc/session/id`, `desc/session/task` and `desc/session/run`.
+*/
+
+use crate::meta::Section;
+use crate::{Error, Result, StreamInfo};
+
+/// The BIDS entities of a recording (see the module documentation).
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct BidsInfo {
+    /// The name of the task (the `task-` entity, e.g., "rest"); empty if not known.
+    pub task: String,
+    /// The subject label (the `sub-` entity, e.g., "07"); empty if not known.
+    pub subject: String,
+    /// The session label (the `ses-` entity, e.g., "pre"); empty if there are no sessions.
+    pub session: String,
+    /// The run index (the `run-` entity), if the task is repeated within the session.
+    pub run: Option<u32>,
+}
+
+impl BidsInfo {
+    /**
+    Read the entities from the meta-data of a stream.
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`). Entities that are
+    not declared (or, for the run, not a number) are left empty.
+    */
+    pub fn from_info(info: &StreamInfo) -> BidsInfo {
+        let desc = info.desc();
+        let session = Section::new(desc.clone(), "session");
+        BidsInfo {
+            task: session.get("task"),
+            subject: Section::new(desc, "subject").get("id"),
+            session: session.get("id"),
+            run: session.get("run").trim().parse().ok(),
+        }
+    }
+
+    /**
+    Write the entities into the meta-data of a stream (replacing previously declared ones).
+
+    Empty entities are removed. Returns an `Error::BadArgument` if a label contains other
+    characters than letters and digits, which BIDS does not allow.
+    */
+    pub fn describe(&self, info: &mut StreamInfo) -> Result<()> {
+        let labels = [&self.task, &self.subject, &self.session];
+        if labels.iter().any(|label| !is_label(label)) {
+            return Err(Error::BadArgument);
+        }
+        let desc = info.desc_mut();
+        let run = self.run.map(|run| run.to_string()).unwrap_or_default();
+        let fields = [
+            ("session", "task", &self.task),
+            ("session", "id", &self.session),
+            ("session", "run", &run),
+            ("subject", "id", &self.subject),
+        ];
+        for &(section, field, value) in fields.iter() {
+            let mut section = Section::new(desc.clone(), section);
+            match value.is_empty() {
+                true => section.remove(field),
+                false => section.set(field, value),
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    The entities in the order of BIDS file names (e.g., "sub-07_ses-pre_task-nback_run-2"),
+    omitting the empty ones.
+    */
+    pub fn file_prefix(&self) -> String {
+        let run = self.run.map(|run| run.to_string()).unwrap_or_default();
+        let entities = [
+            ("sub", &self.subject),
+            ("ses", &self.session),
+            ("task", &self.task),
+            ("run", &run),
+        ];
+        let parts: Vec<_> = entities
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| format!("{}-{}", key, value))
+            .collect();
+        parts.join("_")
+    }
+}
+
+// Whether a string is a valid BIDS label (or empty).
+fn is_label(label: &str) -> bool {
+    label.chars().all(|c| c.is_ascii_alphanumeric())
+}
@@ -0,0 +1,211 @@
+/*!
+Bridges between LSL streams and channels.
+
+`StreamInlet::into_channel()` forwards the samples of an inlet into a bounded channel, and a
+`ChannelOutlet` pushes the samples that are sent into a channel into an outlet, so that LSL
+streams fit into architectures in which threads communicate via channels. The channels of the
+standard library (`std::sync::mpsc`) are used, or those of the `crossbeam-channel` crate if the
+`crossbeam` feature is enabled. The background threads of both end when their channel is
+closed, so dropping the receiver of an inlet's channel closes the inlet (see `SampleReceiver`).
+
+```no_run
+use lsl::bridge::ChannelOutlet;
+use lsl::{InletBuilder, Sample, StreamInfo, StreamOutlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let samples = InletBuilder::resolve("type", "EEG").open()?.into_channel::<f32>(100);
+    let info = StreamInfo::new("Copy", "EEG", 8, 100.0, lsl::ChannelFormat::Float32, "")?;
+    let copy = ChannelOutlet::<f32>::new(StreamOutlet::new(&info, 0, 360)?, 100);
+    let sender = copy.sender();
+    for sample in samples.iter().take(1000) {
+        let sample = sample?;
+        let data = sample.data.iter().map(|x| x * 2.0).collect();
+        if sender.send(Sample { data, timestamp: sample.timestamp }).is_err() {
+            break;
+        }
+    }
+    drop(sender);
+    copy.finish()?;
+    Ok(())
+}
+```
+*/
+
+use crate::worker::{Handle, Task, Worker, MAX_BLOCK};
+use crate::{ExPushable, Pullable, Result, Sample, StreamInlet, StreamOutlet};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::thread;
+
+#[cfg(feature = "crossbeam")]
+pub use crossbeam_channel::{Receiver, Sender};
+#[cfg(not(feature = "crossbeam"))]
+pub use std::sync::mpsc::{Receiver, SyncSender as Sender};
+
+// Create a bounded channel (with a capacity of 0, sends wait until the sample is received).
+pub(crate) fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    #[cfg(feature = "crossbeam")]
+    return crossbeam_channel::bounded(capacity);
+    #[cfg(not(feature = "crossbeam"))]
+    return std::sync::mpsc::sync_channel(capacity);
+}
+
+/**
+The receiving end of the channel into which the samples of an inlet are forwarded, as returned
+by `StreamInlet::into_channel()`.
+
+It dereferences to the channel's `Receiver`, so samples are received via its methods (e.g.,
+`recv()`, `try_recv()` or `iter()`). The samples are pulled by a `Worker` on a background thread,
+which ends after a pull has failed (the error is received as the last item before the channel is
+closed). Dropping the receiver stops the worker and closes the inlet; this waits for the thread,
+which notices the stop within `lsl::worker::MAX_BLOCK` seconds, even if the stream is idle.
+*/
+#[derive(Debug)]
+pub struct SampleReceiver<T: Send + 'static>
+where
+    StreamInlet: Pullable<T>,
+{
+    // (declared first so that it is dropped before the worker, which may be waiting in a send)
+    receiver: Receiver<Result<Sample<T>>>,
+    _worker: Worker<ForwardTask<T>>,
+}
+
+impl<T: Send + 'static> SampleReceiver<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    pub(crate) fn new(inlet: StreamInlet, capacity: usize) -> SampleReceiver<T> {
+        let (sender, receiver) = channel(capacity);
+        let task = ForwardTask {
+            inlet,
+            sender: Some(sender),
+        };
+        SampleReceiver {
+            receiver,
+            _worker: Worker::spawn(task),
+        }
+    }
+}
+
+impl<T: Send + 'static> Deref for SampleReceiver<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    type Target = Receiver<Result<Sample<T>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<'a, T: Send + 'static> IntoIterator for &'a SampleReceiver<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    type Item = Result<Sample<T>>;
+    type IntoIter = <&'a Receiver<Result<Sample<T>>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.receiver.iter()
+    }
+}
+
+// the task that forwards the samples of an inlet into a channel (see `SampleReceiver`)
+#[derive(Debug)]
+struct ForwardTask<T> {
+    inlet: StreamInlet,
+    // (dropped after an error, so that the channel is closed even though the task lives on)
+    sender: Option<Sender<Result<Sample<T>>>>,
+}
+
+impl<T: Send + 'static> Task for ForwardTask<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    fn run_once(&mut self, handle: &Handle) -> Result<()> {
+        let sender = match &self.sender {
+            Some(sender) => sender,
+            None => {
+                handle.stop();
+                return Ok(());
+            }
+        };
+        match self.inlet.pull_sample(MAX_BLOCK) {
+            Ok((data, timestamp)) if timestamp != 0.0 => {
+                if sender.send(Ok(Sample { data, timestamp })).is_err() {
+                    // the receiver has been dropped
+                    handle.stop();
+                }
+            }
+            // (the worker checks whether it has been stopped before the next pull)
+            Ok(_) => {}
+            Err(e) => {
+                // (the error is the last item before the channel is closed)
+                let _ = sender.send(Err(e));
+                self.sender = None;
+                handle.stop();
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+An outlet wrapper that pushes the samples that are sent into a channel.
+
+The samples are pushed by a background thread with their time stamps (where a time stamp of 0.0
+stands for the current time), until all senders have been dropped or a push fails.
+*/
+#[derive(Debug)]
+pub struct ChannelOutlet<T> {
+    sender: Sender<Sample<T>>,
+    thread: thread::JoinHandle<Result<StreamOutlet>>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Send + 'static> ChannelOutlet<T>
+where
+    StreamOutlet: ExPushable<Vec<T>>,
+{
+    /**
+    Start pushing the samples that are sent into the channel into the given outlet.
+
+    Arguments:
+    * `outlet`: The outlet to push into.
+    * `capacity`: The maximum number of samples that are queued before sends block.
+    */
+    pub fn new(outlet: StreamOutlet, capacity: usize) -> ChannelOutlet<T> {
+        let (sender, receiver) = channel::<Sample<T>>(capacity);
+        let thread = thread::spawn(move || {
+            for sample in receiver.iter() {
+                outlet.push_sample_ex(&sample.data, sample.timestamp, true)?;
+            }
+            Ok(outlet)
+        });
+        ChannelOutlet {
+            sender,
+            thread,
+            _marker: PhantomData,
+        }
+    }
+
+    /// A sender for the samples to push (sending fails once the outlet has stopped).
+    pub fn sender(&self) -> Sender<Sample<T>> {
+        self.sender.clone()
+    }
+
+    /**
+    Wait until the samples sent so far have been pushed, which happens once all other senders
+    have been dropped, and return the outlet.
+
+    Returns the error of the first push that failed, if any (e.g., an `Error::LengthMismatch`
+    for a sample of the wrong length). If the background thread panicked, the panic is
+    propagated.
+    */
+    pub fn finish(self) -> Result<StreamOutlet> {
+        drop(self.sender);
+        self.thread
+            .join()
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+    }
+}
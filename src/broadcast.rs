@@ -0,0 +1,186 @@
+/*!
+Fan-out of the samples of one inlet to several consumers within the same process.
+
+Opening a separate inlet for each consumer of a stream (e.g., a display, a recorder and an online
+classifier) multiplies the network traffic. A `Broadcaster` instead reads the stream once and
+hands each sample to any number of `Subscriber`s, each of which has its own bounded queue. Each
+subscriber chooses what happens when it falls behind (see `DropPolicy`): slow consumers can
+either lose samples (and observe how many via `Subscriber::dropped()`), or hold up the broadcast.
+
+```no_run
+use lsl::broadcast::Broadcaster;
+use lsl::queue::DropPolicy;
+use lsl::worker::Worker;
+use lsl::InletBuilder;
+
+fn main() -> Result<(), lsl::Error> {
+    let inlet = InletBuilder::resolve("type", "EEG").timeout(5.0).open()?;
+    let broadcaster = Broadcaster::<f32>::new(inlet);
+    let display = broadcaster.hub().subscribe(100, DropPolicy::DropOldest);
+    let _worker = Worker::spawn(broadcaster);
+    while let Some(sample) = display.recv(lsl::FOREVER) {
+        println!("{:?} ({} samples dropped so far)", sample.data, display.dropped());
+    }
+    Ok(())
+}
+```
+*/
+
+use crate::queue::{DropPolicy, SampleQueue};
+use crate::worker::{Handle, Task, MAX_BLOCK};
+use crate::{Pullable, Result, Sample, StreamInlet};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+// the queues of the subscribers (weak, so that dropped subscribers are released)
+type Subscribers<T> = Vec<Weak<SampleQueue<Sample<T>>>>;
+
+/// Reads samples from an inlet and distributes them to subscribers (run it via a `Worker`).
+#[derive(Debug)]
+pub struct Broadcaster<T> {
+    inlet: StreamInlet,
+    hub: Hub<T>,
+}
+
+impl<T: Clone> Broadcaster<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    /// Create a new broadcaster for the samples of the given inlet.
+    pub fn new(inlet: StreamInlet) -> Broadcaster<T> {
+        Broadcaster {
+            inlet,
+            hub: Hub {
+                subscribers: Arc::new(Mutex::new(vec![])),
+            },
+        }
+    }
+
+    /**
+    Pull the samples that are available (waiting up to `timeout` seconds for the first one) and
+    hand them to the subscribers. Returns the number of samples that were pulled.
+    */
+    pub fn update_wait(&mut self, timeout: f64) -> Result<usize> {
+        let (data, timestamp) = self.inlet.pull_sample(timeout)?;
+        if timestamp == 0.0 {
+            return Ok(0);
+        }
+        self.hub.publish(Sample { data, timestamp });
+        let mut count = 1;
+        for sample in self.inlet.iter_available() {
+            self.hub.publish(sample?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// The hub via which consumers can subscribe (also after the broadcaster has been started).
+    pub fn hub(&self) -> Hub<T> {
+        self.hub.clone()
+    }
+
+    /// The inlet from which the samples are read.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+}
+
+impl<T: Clone + Send + 'static> Task for Broadcaster<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    fn run_once(&mut self, _handle: &Handle) -> Result<()> {
+        self.update_wait(MAX_BLOCK).map(|_| ())
+    }
+}
+
+/// A shareable handle for subscribing to a `Broadcaster`.
+#[derive(Debug)]
+pub struct Hub<T> {
+    subscribers: Arc<Mutex<Subscribers<T>>>,
+}
+
+// (derived Clone would needlessly require T: Clone)
+impl<T> Clone for Hub<T> {
+    fn clone(&self) -> Hub<T> {
+        Hub {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Hub<T> {
+    /**
+    Subscribe to the samples that are broadcast from now on.
+
+    Arguments:
+    * `capacity`: The maximum number of samples that are queued for this subscriber.
+    * `policy`: What happens when the queue is full. Note that with `DropPolicy::Block`, a slow
+      subscriber holds up the broadcaster, and thereby all other subscribers.
+    */
+    pub fn subscribe(&self, capacity: usize, policy: DropPolicy) -> Subscriber<T> {
+        let queue = Arc::new(SampleQueue::new(capacity, policy));
+        self.lock().push(Arc::downgrade(&queue));
+        Subscriber { queue }
+    }
+
+    /// The number of current subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().iter().filter(|s| s.strong_count() > 0).count()
+    }
+
+    // Hand a sample to all current subscribers (removing those that have gone away).
+    fn publish(&self, sample: Sample<T>) {
+        let queues: Vec<_> = {
+            let mut subscribers = self.lock();
+            subscribers.retain(|s| s.strong_count() > 0);
+            subscribers.iter().filter_map(|s| s.upgrade()).collect()
+        };
+        // the lock is not held here, since pushing may block
+        for queue in queues {
+            queue.push(sample.clone());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Subscribers<T>> {
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A consumer of the samples of a `Broadcaster`.
+#[derive(Debug)]
+pub struct Subscriber<T> {
+    queue: Arc<SampleQueue<Sample<T>>>,
+}
+
+impl<T> Subscriber<T> {
+    /**
+    Receive the next sample, waiting up to `timeout` seconds for one to arrive.
+
+    Returns `None` if no sample arrived in time.
+    */
+    pub fn recv(&self, timeout: f64) -> Option<Sample<T>> {
+        self.queue.pop(timeout)
+    }
+
+    /// Receive all samples that are currently queued.
+    pub fn recv_available(&self) -> Vec<Sample<T>> {
+        self.queue.drain()
+    }
+
+    /// The number of samples that were dropped for this subscriber because it fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped()
+    }
+
+    /// The number of samples that are currently queued for this subscriber.
+    pub fn backlog(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+impl<T> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        // releases the broadcaster if it is blocked on this subscriber's queue
+        self.queue.close();
+    }
+}
@@ -0,0 +1,266 @@
+/*!
+Outlets that never block the producer.
+
+Pushing into a `StreamOutlet` hands the data to the native library, which may take locks and
+touch the socket path; this is not acceptable in acquisition callbacks with hard real-time
+deadlines (e.g., audio callbacks or USB completion handlers). A `BufferedOutlet` instead accepts
+samples through a bounded lock-free queue (a single-producer, single-consumer ring buffer), from
+which a dedicated thread pushes them into the outlet in chunks. If the thread falls behind and the
+queue is full, new samples are dropped (and counted) rather than blocking the producer. Queueing a
+sample takes neither a lock nor a system call; in turn, the sender thread is not woken up by the
+producer, but checks the queue every millisecond.
+
+```no_run
+use lsl::buffered::BufferedOutlet;
+use lsl::{ChannelFormat, StreamInfo, StreamOutlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let info = StreamInfo::new("Audio", "Audio", 2, 48000.0, ChannelFormat::Float32, "mic-1")?;
+    let outlet = BufferedOutlet::<f32>::new(StreamOutlet::new(&info, 0, 10)?, 48000);
+    // ... in the audio callback, for each frame:
+    outlet.push(vec![0.1, -0.2], lsl::local_clock());
+    // ... at the end of the session
+    println!("{} samples were dropped", outlet.dropped());
+    outlet.finish()?;
+    Ok(())
+}
+```
+*/
+
+use crate::worker::{Handle, Task, Worker};
+use crate::{ExPushable, Result, StreamOutlet};
+use std::cell::{Cell, UnsafeCell};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// the interval, in seconds, at which the sender thread checks the queue while it is empty
+const DRAIN_INTERVAL: f64 = 0.001;
+
+// the maximum number of samples that the sender thread pushes as one chunk
+const MAX_CHUNK: usize = 1024;
+
+// a slot of the queue, holding the values and time stamp of a sample while it is queued
+type Slot<T> = UnsafeCell<MaybeUninit<(Vec<T>, f64)>>;
+
+// A bounded single-producer, single-consumer queue of samples. The producer only writes the slots
+// from `tail` up to `head + capacity`, and the consumer only reads those from `head` up to `tail`;
+// each side publishes its new index (with release ordering) only once it is done with the slots.
+struct Ring<T> {
+    // a power of two, so that the slot of an index is found by masking it
+    slots: Box<[Slot<T>]>,
+    capacity: usize,
+    // the number of samples that have been taken by the consumer, and queued by the producer
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    // set by the consumer when it has stopped after a failed push
+    closed: AtomicBool,
+}
+
+// the slots are handed over between the two sides via `head` and `tail` (see above)
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn new(capacity: usize) -> Ring<T> {
+        let slots = (0..capacity.next_power_of_two())
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+        Ring {
+            slots,
+            capacity,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    fn slot(&self, index: usize) -> *mut MaybeUninit<(Vec<T>, f64)> {
+        self.slots[index & (self.slots.len() - 1)].get()
+    }
+
+    // Queue a sample (only called by the producer); returns false if the queue is full or closed.
+    fn push(&self, sample: (Vec<T>, f64)) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) >= self.capacity || self.closed.load(Ordering::Relaxed) {
+            return false;
+        }
+        unsafe {
+            (*self.slot(tail)).write(sample);
+        }
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    // Take up to `max` queued samples (only called by the consumer), and return their number.
+    fn pop_into(&self, max: usize, data: &mut Vec<Vec<T>>, timestamps: &mut Vec<f64>) -> usize {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        let count = tail.wrapping_sub(head).min(max);
+        for k in 0..count {
+            let (values, timestamp) =
+                unsafe { (*self.slot(head.wrapping_add(k))).assume_init_read() };
+            data.push(values);
+            timestamps.push(timestamp);
+        }
+        self.head.store(head.wrapping_add(count), Ordering::Release);
+        count
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.get_mut(), *self.tail.get_mut());
+        let mut index = head;
+        while index != tail {
+            unsafe {
+                (*self.slot(index)).assume_init_drop();
+            }
+            index = index.wrapping_add(1);
+        }
+    }
+}
+
+// (the queued samples are not shown)
+impl<T> fmt::Debug for Ring<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ring")
+            .field("capacity", &self.capacity)
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .finish()
+    }
+}
+
+// the task of the sender thread: pushing the queued samples into the outlet
+#[derive(Debug)]
+struct SenderTask<T> {
+    ring: Arc<Ring<T>>,
+    outlet: StreamOutlet,
+    // reused between chunks
+    data: Vec<Vec<T>>,
+    timestamps: Vec<f64>,
+}
+
+impl<T> SenderTask<T>
+where
+    StreamOutlet: ExPushable<Vec<T>>,
+{
+    // Push the queued samples as one chunk, and return their number.
+    fn drain(&mut self) -> Result<usize> {
+        let count = self
+            .ring
+            .pop_into(MAX_CHUNK, &mut self.data, &mut self.timestamps);
+        if count > 0 {
+            let result = self
+                .outlet
+                .push_chunk_stamped_ex(&self.data, &self.timestamps, true);
+            self.data.clear();
+            self.timestamps.clear();
+            if result.is_err() {
+                self.ring.closed.store(true, Ordering::Relaxed);
+            }
+            result?;
+        }
+        Ok(count)
+    }
+}
+
+impl<T: Send + 'static> Task for SenderTask<T>
+where
+    StreamOutlet: ExPushable<Vec<T>>,
+{
+    fn run_once(&mut self, handle: &Handle) -> Result<()> {
+        if self.drain()? == 0 {
+            handle.wait(DRAIN_INTERVAL);
+        }
+        Ok(())
+    }
+}
+
+/**
+An outlet wrapper whose pushes never wait for the network (see the module documentation).
+
+The queue has a single producer, so the outlet can be moved to the producing thread, but not
+shared between threads.
+*/
+#[derive(Debug)]
+pub struct BufferedOutlet<T: Send + 'static>
+where
+    StreamOutlet: ExPushable<Vec<T>>,
+{
+    ring: Arc<Ring<T>>,
+    worker: Worker<SenderTask<T>>,
+    dropped: AtomicU64,
+    // not `Sync`, since concurrent pushes would break the queue
+    producer: PhantomData<Cell<()>>,
+}
+
+impl<T: Send + 'static> BufferedOutlet<T>
+where
+    StreamOutlet: ExPushable<Vec<T>>,
+{
+    /**
+    Start pushing the queued samples into the given outlet from a dedicated thread.
+
+    Arguments:
+    * `outlet`: The outlet to push into.
+    * `capacity`: The maximum number of queued samples; this should cover the longest expected
+      stall of the sender thread (e.g., a second's worth of samples).
+    */
+    pub fn new(outlet: StreamOutlet, capacity: usize) -> BufferedOutlet<T> {
+        let ring = Arc::new(Ring::new(capacity.max(1)));
+        let task = SenderTask {
+            ring: ring.clone(),
+            outlet,
+            data: vec![],
+            timestamps: vec![],
+        };
+        BufferedOutlet {
+            ring,
+            worker: Worker::spawn(task),
+            dropped: AtomicU64::new(0),
+            producer: PhantomData,
+        }
+    }
+
+    /**
+    Queue a sample for pushing, without blocking.
+
+    Arguments:
+    * `data`: The channel values of the sample.
+    * `timestamp`: The capture time of the sample, in agreement with `lsl::local_clock()` (note
+      that 0.0, which stands for the current time, would be resolved only when the sample is
+      pushed by the sender thread).
+
+    Returns false if the sample was dropped, because the queue is full or the sender thread has
+    stopped after a failed push (see `finish()`).
+    */
+    pub fn push(&self, data: Vec<T>, timestamp: f64) -> bool {
+        let queued = self.ring.push((data, timestamp));
+        if !queued {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        queued
+    }
+
+    /// The number of samples that have been dropped so far.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /**
+    Wait until all queued samples have been pushed, and return the outlet.
+
+    Returns the error of the first push that failed, if any (e.g., an `Error::LengthMismatch`
+    for a sample of the wrong length).
+    */
+    pub fn finish(self) -> Result<StreamOutlet> {
+        let (mut task, result) = self.worker.stop();
+        result?;
+        while task.drain()? > 0 {}
+        Ok(task.outlet)
+    }
+}
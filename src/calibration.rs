@@ -0,0 +1,183 @@
+/*!
+Latency calibration of stimulus markers against a measured analog signal.
+
+The time stamp of a stimulus marker records when the presentation software issued the stimulus,
+which is typically earlier than when it actually appeared on the screen or speaker, by a roughly
+constant (but unknown) latency. The usual way to measure this latency is to record the physical
+stimulus with a photodiode (or an audio loopback), and to compare the onsets in that signal with
+the marker time stamps. A `Calibrator` automates this procedure: it detects the onsets in the
+analog signal with a `ThresholdDetector`, pairs each marker with the next onset, and reports the
+constant offset along with a confidence interval.
+
+```no_run
+use lsl::calibration::Calibrator;
+use lsl::transforms::ThresholdDetector;
+use lsl::{ProcessingOption, StreamInlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let markers = lsl::resolve_byprop("type", "Markers", 1, 5.0)?;
+    let markers = StreamInlet::new(&markers[0], 360, 0, true)?;
+    let signal = lsl::resolve_byprop("type", "Photodiode", 1, 5.0)?;
+    let signal = StreamInlet::new(&signal[0], 360, 0, true)?;
+    // bring both streams into the local time domain
+    markers.set_postprocessing(&[ProcessingOption::ClockSync])?;
+    signal.set_postprocessing(&[ProcessingOption::ClockSync])?;
+    let detector = ThresholdDetector::new(vec![0], 0.5, 0.1, 0.2)?;
+    let mut calibrator = Calibrator::new(markers, signal, detector, 0.5);
+    for _ in 0..600 {
+        calibrator.update()?;
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let estimate = calibrator.estimate()?;
+    println!(
+        "latency: {:.1} ms (95% CI {:.1}-{:.1} ms, n={})",
+        estimate.offset * 1e3,
+        estimate.ci95.0 * 1e3,
+        estimate.ci95.1 * 1e3,
+        estimate.count
+    );
+    Ok(())
+}
+```
+*/
+
+use crate::transforms::{Edge, ThresholdDetector};
+use crate::{Error, Result, Sample, StreamInlet};
+
+/// The result of a latency calibration.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct LatencyEstimate {
+    /// The mean offset, in seconds, of the measured onsets relative to the markers.
+    pub offset: f64,
+    /// The standard deviation of the individual offsets (i.e., the jitter), in seconds.
+    pub std_dev: f64,
+    /// The 95% confidence interval of the mean offset, in seconds.
+    pub ci95: (f64, f64),
+    /// The number of markers that were paired with an onset.
+    pub count: usize,
+    /// The number of markers for which no onset was found within the maximum lag.
+    pub missed: usize,
+}
+
+/**
+Estimate the latency of a set of markers, given the onset times measured in an analog signal.
+
+Each marker is paired with the first onset that follows it within `max_lag` seconds (and that has
+not been paired with an earlier marker); markers without such an onset count as missed. Both
+slices must be sorted in ascending order.
+
+The confidence interval is based on the normal approximation (i.e., mean +/- 1.96 standard
+errors), which is adequate for the usual calibration runs of some dozens of trials or more.
+Returns an `Error::BadArgument` if fewer than 2 markers could be paired.
+*/
+pub fn estimate_latency(markers: &[f64], onsets: &[f64], max_lag: f64) -> Result<LatencyEstimate> {
+    let mut offsets = vec![];
+    let mut next = 0;
+    for &marker in markers {
+        while next < onsets.len() && onsets[next] < marker {
+            next += 1;
+        }
+        if next < onsets.len() && onsets[next] - marker <= max_lag {
+            offsets.push(onsets[next] - marker);
+            next += 1;
+        }
+    }
+    let n = offsets.len();
+    if n < 2 {
+        return Err(Error::BadArgument);
+    }
+    let mean = offsets.iter().sum::<f64>() / n as f64;
+    let var = offsets.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    let half_width = 1.96 * (var / n as f64).sqrt();
+    Ok(LatencyEstimate {
+        offset: mean,
+        std_dev: var.sqrt(),
+        ci95: (mean - half_width, mean + half_width),
+        count: n,
+        missed: markers.len() - n,
+    })
+}
+
+/**
+Collects marker time stamps and signal onsets from two inlets for a latency calibration.
+
+Both inlets should deliver time stamps in the same time domain, e.g., by enabling the
+`ProcessingOption::ClockSync` post-processing on both. The content of the markers is ignored,
+so the marker stream can have any channel format. Call `update()` periodically while the
+calibration stimuli are presented, and `estimate()` at the end.
+*/
+#[derive(Debug)]
+pub struct Calibrator {
+    markers: StreamInlet,
+    signal: StreamInlet,
+    detector: ThresholdDetector,
+    max_lag: f64,
+    marker_times: Vec<f64>,
+    onset_times: Vec<f64>,
+}
+
+impl Calibrator {
+    /**
+    Create a new calibrator.
+
+    Arguments:
+    * `markers`: The inlet of the marker stream.
+    * `signal`: The inlet of the measured analog signal (e.g., a photodiode).
+    * `detector`: The detector whose rising crossings are the onsets of the stimuli (its
+      refractory period should exceed the duration of a stimulus).
+    * `max_lag`: The maximum plausible latency, in seconds (should be less than the interval
+      between successive stimuli).
+    */
+    pub fn new(
+        markers: StreamInlet,
+        signal: StreamInlet,
+        detector: ThresholdDetector,
+        max_lag: f64,
+    ) -> Calibrator {
+        Calibrator {
+            markers,
+            signal,
+            detector,
+            max_lag,
+            marker_times: vec![],
+            onset_times: vec![],
+        }
+    }
+
+    /// Process the markers and signal data that have arrived since the last call.
+    pub fn update(&mut self) -> Result<()> {
+        while let Some((_, ts)) = self.markers.pull_sample_dynamic(0.0)? {
+            self.marker_times.push(ts);
+        }
+        let chunk: Vec<Sample<f64>> = self.signal.iter_available().collect::<Result<_>>()?;
+        let crossings = self.detector.detect(&chunk)?;
+        self.onset_times.extend(
+            crossings
+                .iter()
+                .filter(|c| c.edge == Edge::Rising)
+                .map(|c| c.timestamp),
+        );
+        Ok(())
+    }
+
+    /// Estimate the latency from the data collected so far (see `estimate_latency()`).
+    pub fn estimate(&self) -> Result<LatencyEstimate> {
+        estimate_latency(&self.marker_times, &self.onset_times, self.max_lag)
+    }
+
+    /// The time stamps of the markers collected so far.
+    pub fn marker_times(&self) -> &[f64] {
+        &self.marker_times
+    }
+
+    /// The times of the onsets detected in the signal so far.
+    pub fn onset_times(&self) -> &[f64] {
+        &self.onset_times
+    }
+
+    /// Discard the data collected so far, e.g., to start a new calibration run.
+    pub fn reset(&mut self) {
+        self.marker_times.clear();
+        self.onset_times.clear();
+    }
+}
@@ -0,0 +1,438 @@
+/*!
+Channel selection and reordering by label.
+
+The channel order of a stream depends on the configuration of the device (or of its driver), which
+can change between sessions. The `ReorderedInlet` adapter pulls samples from an inlet and
+rearranges their channels into a caller-specified order of channel labels, so that analysis code
+can rely on a fixed channel order. The labels are taken from the stream's meta-data, following
+the usual `<channels><channel><label>` convention.
+
+```no_run
+use lsl::channels::ReorderedInlet;
+use lsl::StreamInlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = StreamInlet::new(&res[0], 360, 0, true)?;
+    let inlet = ReorderedInlet::new(inlet, &["Fz", "Cz", "Pz"], 5.0)?;
+    let (sample, ts) = inlet.pull_sample::<f32>(lsl::FOREVER)?;
+    println!("Fz={}, Cz={}, Pz={} at {}", sample[0], sample[1], sample[2], ts);
+    Ok(())
+}
+```
+
+The channels of a stream can be declared once via the `lsl::channels!` macro, which yields a
+`ChannelList` constant; the same constant then provides both the meta-data for the outlet and the
+labels for label-keyed consumers such as the `ReorderedInlet`, so that the two cannot get out of
+sync. Duplicate labels are rejected at compile time:
+
+```no_run
+use lsl::channels::{ChannelList, ReorderedInlet};
+use lsl::{ChannelFormat, StreamInlet, StreamOutlet};
+
+const EEG: ChannelList<2> = lsl::channels!["C3" => microvolts, "C4" => microvolts];
+
+fn main() -> Result<(), lsl::Error> {
+    let info = EEG.stream_info("BioSemi", "EEG", 100.0, ChannelFormat::Float32, "bs-123")?;
+    let _outlet = StreamOutlet::new(&info, 0, 360)?;
+    // ... elsewhere:
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = StreamInlet::new(&res[0], 360, 0, true)?;
+    let _inlet = ReorderedInlet::new(inlet, &EEG.labels, 5.0)?;
+    Ok(())
+}
+```
+*/
+
+use crate::{ChannelFormat, Error, Pullable, Result, StreamInfo, StreamInlet, XMLElement};
+
+/**
+Declare a list of channel labels and their units as a `ChannelList`.
+
+Each entry has the form `"label" => unit`, where the unit is an identifier such as `microvolts`
+(see the module documentation for an example). The macro can be used in `const` items, in which
+case duplicate labels are a compile-time error.
+*/
+#[macro_export]
+macro_rules! channels {
+    ($($label:literal => $unit:ident),* $(,)?) => {
+        $crate::channels::ChannelList::new([$($label),*], [$(stringify!($unit)),*])
+    };
+}
+
+/**
+A fixed list of channel labels along with their units, usually created via `lsl::channels!`.
+
+The labels can be passed directly to the label-keyed APIs (e.g., `ReorderedInlet::new()`), and
+`describe()` writes the matching `<channels>` meta-data into a stream declaration.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ChannelList<const N: usize> {
+    /// The channel labels, in order.
+    pub labels: [&'static str; N],
+    /// The unit of each channel.
+    pub units: [&'static str; N],
+}
+
+impl<const N: usize> ChannelList<N> {
+    /**
+    Create a channel list from labels and units.
+
+    Panics if a label occurs more than once (which is a compile-time error in `const` items).
+    */
+    pub const fn new(labels: [&'static str; N], units: [&'static str; N]) -> ChannelList<N> {
+        let mut i = 0;
+        while i < N {
+            let mut j = i + 1;
+            while j < N {
+                if str_eq(labels[i], labels[j]) {
+                    panic!("duplicate channel label");
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        ChannelList { labels, units }
+    }
+
+    /// The number of channels.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Whether the list declares no channels.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /**
+    Append the channel declarations (as `<channels><channel><label/><unit/>`) to the `desc()`
+    field of a stream info, following the usual meta-data conventions.
+    */
+    pub fn describe(&self, info: &mut StreamInfo) {
+        let mut channels = info.desc_mut().append_child("channels");
+        for (label, unit) in self.labels.iter().zip(self.units.iter()) {
+            channels
+                .append_child("channel")
+                .append_child_value("label", label)
+                .append_child_value("unit", unit);
+        }
+    }
+
+    /**
+    Create the declaration of a stream with these channels, including their meta-data.
+
+    See `StreamInfo::new()` for the arguments.
+    */
+    pub fn stream_info(
+        &self,
+        name: &str,
+        type_: &str,
+        srate: f64,
+        channel_format: ChannelFormat,
+        source_id: &str,
+    ) -> Result<StreamInfo> {
+        let mut info = StreamInfo::new(name, type_, N as u32, srate, channel_format, source_id)?;
+        self.describe(&mut info);
+        Ok(info)
+    }
+}
+
+/**
+The declaration of a channel in the meta-data of a stream (see `StreamInfo::set_channels()`).
+
+Empty fields are not written to the meta-data.
+*/
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct ChannelDescriptor {
+    /// The label of the channel (e.g., "C3" for an EEG channel).
+    pub label: String,
+    /// The unit of the channel's values (e.g., "microvolts").
+    pub unit: String,
+    /// The type of the channel (e.g., "EEG" or "EOG" in a stream of type "EEG").
+    pub chan_type: String,
+    /// The expected value range of the channel (see `set_ranges()`), if declared.
+    pub range: Option<(f64, f64)>,
+    /// The 3D location of the sensor (see `set_locations()`), if declared.
+    pub location: Option<[f64; 3]>,
+}
+
+impl ChannelDescriptor {
+    /// Create a descriptor with a label and unit.
+    pub fn new(label: &str, unit: &str) -> ChannelDescriptor {
+        ChannelDescriptor {
+            label: label.to_string(),
+            unit: unit.to_string(),
+            ..ChannelDescriptor::default()
+        }
+    }
+}
+
+// String comparison that can be evaluated at compile time.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut k = 0;
+    while k < a.len() {
+        if a[k] != b[k] {
+            return false;
+        }
+        k += 1;
+    }
+    true
+}
+
+/**
+Read the channel labels declared in the meta-data of a stream.
+
+The info must have been fully retrieved (e.g., via `StreamInlet::info()`). Channels without a
+label yield an empty string; if the stream declares no channels at all, the result is empty.
+*/
+pub fn labels(info: &StreamInfo) -> Vec<String> {
+    info.channels().into_iter().map(|c| c.label).collect()
+}
+
+/**
+Declare the expected (nominal) value range of each channel in the meta-data of a stream.
+
+The ranges are written as `<range><min/><max/></range>` into the stream's `<channels><channel>`
+entries, in channel order; entries that do not exist yet are created, and previously declared
+ranges are replaced. Consumers can read them via `ranges()`, e.g., to detect amplifier saturation
+with a `lsl::transforms::ClippingDetector`.
+
+Returns an `Error::LengthMismatch` if the number of ranges differs from the channel count, and an
+`Error::BadArgument` if a range has its minimum above its maximum (or is NaN).
+*/
+pub fn set_ranges(info: &mut StreamInfo, ranges: &[(f64, f64)]) -> Result<()> {
+    if ranges.len() != info.channel_count() as usize {
+        return Err(Error::LengthMismatch {
+            expected: info.channel_count() as usize,
+            actual: ranges.len(),
+        });
+    }
+    if ranges
+        .iter()
+        .any(|&(min, max)| min > max || min.is_nan() || max.is_nan())
+    {
+        return Err(Error::BadArgument);
+    }
+    let mut desc = info.desc_mut();
+    let mut channels = desc.child("channels");
+    if channels.empty() {
+        channels = desc.append_child("channels");
+    }
+    let mut channel = channels.child("channel");
+    for &(min, max) in ranges {
+        if channel.empty() {
+            channel = channels.append_child("channel");
+        }
+        channel.remove_child_named("range");
+        channel
+            .append_child("range")
+            .append_child_value("min", &min.to_string())
+            .append_child_value("max", &max.to_string());
+        channel = channel.next_sibling_named("channel");
+    }
+    Ok(())
+}
+
+/**
+Read the expected value ranges declared in the meta-data of a stream (see `set_ranges()`).
+
+The info must have been fully retrieved (e.g., via `StreamInlet::info()`). There is one entry per
+declared channel, which is `None` if the channel has no (valid) range.
+*/
+pub fn ranges(info: &StreamInfo) -> Vec<Option<(f64, f64)>> {
+    info.channels().into_iter().map(|c| c.range).collect()
+}
+
+/**
+Declare the 3D locations of the sensors (e.g., EEG electrodes) in the meta-data of a stream.
+
+The locations are written as `<location><X/><Y/><Z/></location>` into the stream's
+`<channels><channel>` entries, in channel order, like the ranges in `set_ranges()`. The
+coordinate system is not prescribed; it is usually declared along with the fiducials (see
+`set_fiducials()`), whose locations are in the same coordinates.
+
+Returns an `Error::LengthMismatch` if the number of locations differs from the channel count.
+*/
+pub fn set_locations(info: &mut StreamInfo, locations: &[[f64; 3]]) -> Result<()> {
+    if locations.len() != info.channel_count() as usize {
+        return Err(Error::LengthMismatch {
+            expected: info.channel_count() as usize,
+            actual: locations.len(),
+        });
+    }
+    let mut desc = info.desc_mut();
+    let mut channels = desc.child("channels");
+    if channels.empty() {
+        channels = desc.append_child("channels");
+    }
+    let mut channel = channels.child("channel");
+    for &location in locations {
+        if channel.empty() {
+            channel = channels.append_child("channel");
+        }
+        write_location(&mut channel, location);
+        channel = channel.next_sibling_named("channel");
+    }
+    Ok(())
+}
+
+/**
+Read the 3D sensor locations declared in the meta-data of a stream (see `set_locations()`).
+
+The info must have been fully retrieved (e.g., via `StreamInlet::info()`). There is one entry per
+declared channel, which is `None` if the channel has no (complete) location.
+*/
+pub fn locations(info: &StreamInfo) -> Vec<Option<[f64; 3]>> {
+    info.channels().into_iter().map(|c| c.location).collect()
+}
+
+/// A landmark on the head (e.g., the nasion) whose location is declared in the meta-data.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Fiducial {
+    /// The name of the landmark (e.g., "Nasion", "LPA" or "RPA").
+    pub label: String,
+    /// The 3D location of the landmark, in the coordinates of the sensor locations.
+    pub location: [f64; 3],
+}
+
+/**
+Declare the fiducials in the meta-data of a stream (as `<fiducials><fiducial>` entries with a
+`<label>` and a `<location>`), replacing previously declared ones.
+*/
+pub fn set_fiducials(info: &mut StreamInfo, fiducials: &[Fiducial]) {
+    let mut desc = info.desc_mut();
+    desc.remove_child_named("fiducials");
+    let mut parent = desc.append_child("fiducials");
+    for fiducial in fiducials {
+        let mut element = parent.append_child("fiducial");
+        element.append_child_value("label", &fiducial.label);
+        write_location(&mut element, fiducial.location);
+    }
+}
+
+/**
+Read the fiducials declared in the meta-data of a stream (see `set_fiducials()`).
+
+The info must have been fully retrieved (e.g., via `StreamInlet::info()`). Fiducials without a
+(complete) location are skipped.
+*/
+pub fn fiducials(info: &StreamInfo) -> Vec<Fiducial> {
+    let mut result = vec![];
+    let mut element = info.desc().child("fiducials").child("fiducial");
+    while element.is_valid() && !element.empty() {
+        if let Some(location) = read_location(&element) {
+            result.push(Fiducial {
+                label: element.child_value_named("label"),
+                location,
+            });
+        }
+        element = element.next_sibling_named("fiducial");
+    }
+    result
+}
+
+// Write a `<location>` into an element, replacing a previous one.
+pub(crate) fn write_location(element: &mut XMLElement, [x, y, z]: [f64; 3]) {
+    element.remove_child_named("location");
+    element
+        .append_child("location")
+        .append_child_value("X", &x.to_string())
+        .append_child_value("Y", &y.to_string())
+        .append_child_value("Z", &z.to_string());
+}
+
+// Read the `<location>` of an element, if it has all three coordinates.
+pub(crate) fn read_location(element: &XMLElement) -> Option<[f64; 3]> {
+    let location = element.child("location");
+    let coordinate = |name| location.child_value_named(name).trim().parse::<f64>().ok();
+    Some([coordinate("X")?, coordinate("Y")?, coordinate("Z")?])
+}
+
+/**
+Compute, for each of the requested labels, the index of the corresponding channel.
+
+Returns an `Error::BadArgument` if any of the requested labels is not declared by the stream (see
+`labels()` to inspect the declared ones).
+*/
+pub fn indices_of(info: &StreamInfo, order: &[&str]) -> Result<Vec<usize>> {
+    let declared = labels(info);
+    order
+        .iter()
+        .map(|label| {
+            declared
+                .iter()
+                .position(|x| x == label)
+                .ok_or(Error::BadArgument)
+        })
+        .collect()
+}
+
+/**
+An inlet adapter that returns the channels of each sample in a given order of labels.
+
+The requested labels can also be a subset of the stream's channels, in which case only those
+channels are returned.
+*/
+#[derive(Debug)]
+pub struct ReorderedInlet {
+    inlet: StreamInlet,
+    indices: Vec<usize>,
+}
+
+impl ReorderedInlet {
+    /**
+    Wrap an inlet so that its channels are returned in the given order.
+
+    Arguments:
+    * `inlet`: The inlet to read from.
+    * `order`: The labels of the channels to return, in the desired order.
+    * `timeout`: The timeout for retrieving the stream's meta-data (see `StreamInlet::info()`).
+
+    Returns an `Error::BadArgument` if any of the labels is not declared by the stream.
+    */
+    pub fn new(inlet: StreamInlet, order: &[&str], timeout: f64) -> Result<ReorderedInlet> {
+        let indices = indices_of(&inlet.info(timeout)?, order)?;
+        Ok(ReorderedInlet { inlet, indices })
+    }
+
+    /// Pull the next sample, with its channels reordered (see `Pullable::pull_sample()`).
+    pub fn pull_sample<T: Clone>(&self, timeout: f64) -> Result<(Vec<T>, f64)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        let (sample, ts) = self.inlet.pull_sample(timeout)?;
+        if ts == 0.0 {
+            return Ok((sample, ts));
+        }
+        Ok((self.reorder(&sample), ts))
+    }
+
+    /// Pull a chunk of samples, with their channels reordered (see `Pullable::pull_chunk()`).
+    pub fn pull_chunk<T: Clone>(&self) -> Result<(Vec<Vec<T>>, Vec<f64>)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        let (samples, stamps) = self.inlet.pull_chunk()?;
+        Ok((samples.iter().map(|x| self.reorder(x)).collect(), stamps))
+    }
+
+    /// Rearrange the channels of a sample that was pulled from the underlying inlet.
+    pub fn reorder<T: Clone>(&self, sample: &[T]) -> Vec<T> {
+        self.indices.iter().map(|&k| sample[k].clone()).collect()
+    }
+
+    /// The index of the source channel for each of the returned channels.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The underlying inlet.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+}
@@ -0,0 +1,194 @@
+/*!
+Modeling the offset and drift between the clock of a remote stream and the local clock.
+
+The native library maps remote time stamps into the local clock by the most recent offset (see
+`StreamInlet::time_correction()`), which jumps with every new measurement and does not account
+for the drift between the clocks in the meantime. Offline synchronization (as done when loading
+XDF files) instead fits a line through all of the measurements of a recording. A `ClockSync`
+does the same online or offline: it collects the time-correction measurements of a stream, fits
+the offset and the drift to them (discarding measurements whose round-trip time was unusually
+long, as well as those that deviate from the fit), and maps remote time stamps through the model:
+
+```no_run
+use lsl::clocksync::ClockSync;
+use lsl::{Pullable, StreamInlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = StreamInlet::new(&res[0], 360, 0, true)?;
+    let mut sync = ClockSync::new(1000);
+    loop {
+        let (sample, ts): (Vec<f32>, _) = inlet.pull_sample(lsl::FOREVER)?;
+        sync.update(&inlet, 0.0)?;
+        println!("{:?} at {:?}", sample, sync.map_remote_to_local(ts));
+    }
+}
+```
+
+For offline use, the measurements can be taken from a recorded history (see
+`StreamInlet::time_correction_history()`) via `add()`.
+*/
+
+use crate::{Result, StreamInlet, TimeCorrection};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+// measurements whose round-trip time exceeds this multiple of the median are discarded
+const RTT_OUTLIER_FACTOR: f64 = 3.0;
+
+// measurements whose deviation from the first fit exceeds this multiple of the (normalized)
+// median absolute deviation are discarded before the final fit
+const RESIDUAL_OUTLIER_FACTOR: f64 = 3.0;
+
+// the factor that turns the median absolute deviation into an estimate of the standard
+// deviation, for normally distributed data
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// The offset and drift of a remote clock relative to the local clock (see `ClockSync`).
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct DriftModel {
+    /// The remote time, in seconds, at which `offset` holds (the center of the measurements).
+    pub reference: f64,
+    /// The offset, in seconds, to add to remote time stamps at the reference time to map them
+    /// into the local clock.
+    pub offset: f64,
+    /// The change of the offset per second of remote time (e.g., 1e-5 for 10 ppm).
+    pub drift: f64,
+    /// The number of measurements that the model was fitted to (after discarding outliers).
+    pub measurements: usize,
+}
+
+impl DriftModel {
+    /// Map a remote time stamp into the local clock.
+    pub fn map_remote_to_local(&self, ts: f64) -> f64 {
+        ts + self.offset + self.drift * (ts - self.reference)
+    }
+}
+
+/// A collection of the time-correction measurements of a stream, with a model of the offset and
+/// drift of its clock (see the module documentation).
+#[derive(Clone, Debug)]
+pub struct ClockSync {
+    measurements: VecDeque<TimeCorrection>,
+    capacity: usize,
+    model: Option<DriftModel>,
+}
+
+impl ClockSync {
+    /// Create a new, empty model that retains up to the given number of (most recent)
+    /// measurements.
+    pub fn new(capacity: usize) -> ClockSync {
+        ClockSync {
+            measurements: VecDeque::new(),
+            capacity: capacity.max(1),
+            model: None,
+        }
+    }
+
+    /**
+    Add the latest time-correction measurement of an inlet (see
+    `StreamInlet::time_correction_ex()`), and refit the model.
+
+    If the remote clock was reset since the last call (see `StreamInlet::was_clock_reset()`,
+    e.g., because the stream was recovered on another computer), the measurements made before
+    are discarded. A measurement that was already added is not added again. The timeout is that
+    of `time_correction_ex()`; passing 0.0 returns an `Error::Timeout` until the first
+    measurement is available.
+    */
+    pub fn update(&mut self, inlet: &StreamInlet, timeout: f64) -> Result<()> {
+        if inlet.was_clock_reset() {
+            self.reset();
+        }
+        let (offset, remote_time, rtt) = inlet.time_correction_ex(timeout)?;
+        let known = self.measurements.back().map(|m| m.remote_time);
+        if known != Some(remote_time) {
+            self.add(TimeCorrection {
+                remote_time,
+                offset,
+                rtt,
+            });
+        }
+        Ok(())
+    }
+
+    /// Add a measurement (e.g., from a recorded history), and refit the model.
+    pub fn add(&mut self, measurement: TimeCorrection) {
+        if self.measurements.len() == self.capacity {
+            self.measurements.pop_front();
+        }
+        self.measurements.push_back(measurement);
+        self.model = fit(self.measurements.iter().copied().collect());
+    }
+
+    /// Discard all measurements (e.g., after the remote clock was reset).
+    pub fn reset(&mut self) {
+        self.measurements.clear();
+        self.model = None;
+    }
+
+    /// The retained measurements, oldest first.
+    pub fn measurements(&self) -> Vec<TimeCorrection> {
+        self.measurements.iter().copied().collect()
+    }
+
+    /// The current model, or `None` if there are no measurements.
+    pub fn model(&self) -> Option<DriftModel> {
+        self.model
+    }
+
+    /// Map a remote time stamp into the local clock, or return `None` if there are no
+    /// measurements yet.
+    pub fn map_remote_to_local(&self, ts: f64) -> Option<f64> {
+        self.model.map(|model| model.map_remote_to_local(ts))
+    }
+}
+
+// Fit a model to the measurements, discarding those with unusually long round-trip times, and
+// then those that deviate from a first fit.
+fn fit(mut measurements: Vec<TimeCorrection>) -> Option<DriftModel> {
+    let max_rtt = median(measurements.iter().map(|m| m.rtt).collect())? * RTT_OUTLIER_FACTOR;
+    measurements.retain(|m| m.rtt <= max_rtt);
+    let model = fit_line(&measurements)?;
+    let residual = |m: &TimeCorrection| {
+        let local = model.map_remote_to_local(m.remote_time);
+        (local - m.remote_time - m.offset).abs()
+    };
+    let mad = median(measurements.iter().map(residual).collect())? * MAD_TO_SIGMA;
+    if mad > 0.0 {
+        measurements.retain(|m| residual(m) <= mad * RESIDUAL_OUTLIER_FACTOR);
+    }
+    fit_line(&measurements).or(Some(model))
+}
+
+// Fit the offsets of the measurements by least squares, as a line over the remote time.
+fn fit_line(measurements: &[TimeCorrection]) -> Option<DriftModel> {
+    if measurements.is_empty() {
+        return None;
+    }
+    let n = measurements.len() as f64;
+    let reference = measurements.iter().map(|m| m.remote_time).sum::<f64>() / n;
+    let offset = measurements.iter().map(|m| m.offset).sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for m in measurements {
+        let dt = m.remote_time - reference;
+        covariance += dt * (m.offset - offset);
+        variance += dt * dt;
+    }
+    // (without a spread of remote times, the drift cannot be determined)
+    let drift = match variance > 0.0 {
+        true => covariance / variance,
+        false => 0.0,
+    };
+    Some(DriftModel {
+        reference,
+        offset,
+        drift,
+        measurements: measurements.len(),
+    })
+}
+
+// The median of some values (the upper one of the middle two for an even number).
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    values.get(values.len() / 2).copied()
+}
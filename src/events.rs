@@ -0,0 +1,287 @@
+/*!
+A typed protocol for experiment events.
+
+Marker streams usually carry free-form strings, which leaves it to each application to invent
+(and parse) its own conventions. This module defines a small, versioned event payload with a
+name, a phase (instantaneous events, or the beginning and end of an interval), and any number of
+key-value fields, together with helpers to send and receive such events over a marker stream.
+
+Each event is encoded as a single string of the form `ev1|<phase>|<name>|<key>=<value>|...`,
+where `ev1` identifies the protocol version and the characters `|`, `=` and `%` in names, keys
+and values are percent-escaped.
+
+```no_run
+use lsl::events::{Event, EventOutlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let outlet = EventOutlet::new("MyExperiment", "exp-1234")?;
+    outlet.push(&Event::begin("trial").with("number", "1").with("condition", "congruent"))?;
+    Ok(())
+}
+```
+*/
+
+use crate::{
+    ChannelFormat, Error, ExPushable, Pullable, Result, StreamInfo, StreamInlet, StreamOutlet,
+    IRREGULAR_RATE,
+};
+use std::fmt;
+
+/// Version of the event protocol implemented by this module.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// prefix that identifies encoded events of this protocol version
+const PREFIX: &str = "ev1";
+
+/// The phase of an event.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Phase {
+    /// An instantaneous event (e.g., a button press).
+    Instant,
+    /// The beginning of an interval (e.g., the start of a trial).
+    Begin,
+    /// The end of an interval that was started by a `Begin` event of the same name.
+    End,
+}
+
+/// An experiment event, consisting of a name, a phase and key-value fields.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Event {
+    /// Name of the event (e.g., "trial" or "stimulus").
+    pub name: String,
+    /// Phase of the event.
+    pub phase: Phase,
+    /// Additional fields of the event, in order.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Event {
+    /// Create a new instantaneous event with the given name and no fields.
+    pub fn new(name: &str) -> Event {
+        Event {
+            name: name.to_string(),
+            phase: Phase::Instant,
+            fields: vec![],
+        }
+    }
+
+    /// Create an event that marks the beginning of an interval.
+    pub fn begin(name: &str) -> Event {
+        Event {
+            phase: Phase::Begin,
+            ..Event::new(name)
+        }
+    }
+
+    /// Create an event that marks the end of an interval.
+    pub fn end(name: &str) -> Event {
+        Event {
+            phase: Phase::End,
+            ..Event::new(name)
+        }
+    }
+
+    /// Add a field to the event and return it (for chaining).
+    pub fn with(mut self, key: &str, value: &str) -> Event {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Get the value of the first field with the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Encode the event into its string representation.
+    pub fn encode(&self) -> String {
+        let mut result = format!("{}|{}|{}", PREFIX, self.phase, escape(&self.name));
+        for (key, value) in &self.fields {
+            result.push_str(&format!("|{}={}", escape(key), escape(value)));
+        }
+        result
+    }
+
+    /**
+    Decode an event from its string representation.
+
+    Returns an `Error::BadArgument` if the string is not a well-formed event of this protocol
+    version (e.g., a free-form marker).
+    */
+    pub fn decode(s: &str) -> Result<Event> {
+        let mut parts = s.split('|');
+        if parts.next() != Some(PREFIX) {
+            return Err(Error::BadArgument);
+        }
+        let phase = match parts.next() {
+            Some("instant") => Phase::Instant,
+            Some("begin") => Phase::Begin,
+            Some("end") => Phase::End,
+            _ => return Err(Error::BadArgument),
+        };
+        let name = unescape(parts.next().ok_or(Error::BadArgument)?)?;
+        let mut fields = vec![];
+        for part in parts {
+            let mut kv = part.splitn(2, '=');
+            let key = unescape(kv.next().unwrap_or(""))?;
+            let value = unescape(kv.next().ok_or(Error::BadArgument)?)?;
+            fields.push((key, value));
+        }
+        Ok(Event {
+            name,
+            phase,
+            fields,
+        })
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Phase::Instant => "instant",
+            Phase::Begin => "begin",
+            Phase::End => "end",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+/**
+Create the declaration of an event stream.
+
+This is an irregular-rate, single-channel marker stream whose meta-data declare the event
+protocol (as `<event_protocol><version>` in the `desc()` field).
+*/
+pub fn stream_info(name: &str, source_id: &str) -> Result<StreamInfo> {
+    let mut info = StreamInfo::new(
+        name,
+        "Markers",
+        1,
+        IRREGULAR_RATE,
+        ChannelFormat::String,
+        source_id,
+    )?;
+    info.desc_mut()
+        .append_child("event_protocol")
+        .append_child_value("version", &PROTOCOL_VERSION.to_string());
+    Ok(info)
+}
+
+/// An outlet that sends typed events (see module documentation).
+#[derive(Debug)]
+pub struct EventOutlet {
+    outlet: StreamOutlet,
+}
+
+impl EventOutlet {
+    /**
+    Create a new event stream and make it discoverable.
+
+    Arguments:
+    * `name`: Name of the stream (e.g., the name of the experiment).
+    * `source_id`: Unique identifier of the source (see `StreamInfo::new()`), or an empty str.
+    */
+    pub fn new(name: &str, source_id: &str) -> Result<EventOutlet> {
+        let info = stream_info(name, source_id)?;
+        Ok(EventOutlet {
+            outlet: StreamOutlet::new(&info, 0, 360)?,
+        })
+    }
+
+    /// Send an event, time-stamped with the current time.
+    pub fn push(&self, event: &Event) -> Result<()> {
+        self.push_at(event, 0.0)
+    }
+
+    /// Send an event with a given time stamp (in agreement with `lsl::local_clock()`).
+    pub fn push_at(&self, event: &Event, timestamp: f64) -> Result<()> {
+        self.outlet
+            .push_sample_ex(&vec![event.encode()], timestamp, true)
+    }
+
+    /// The underlying outlet.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+/// An inlet that receives typed events (see module documentation).
+#[derive(Debug)]
+pub struct EventInlet {
+    inlet: StreamInlet,
+}
+
+impl EventInlet {
+    /**
+    Open an event stream from a resolved stream info.
+
+    Arguments:
+    * `info`: A resolved stream info object (as coming from one of the resolver functions).
+    * `max_buflen`: The maximum amount of data to buffer (see `StreamInlet::new()`).
+    */
+    pub fn new(info: &StreamInfo, max_buflen: i32) -> Result<EventInlet> {
+        Ok(EventInlet {
+            inlet: StreamInlet::new(info, max_buflen, 0, true)?,
+        })
+    }
+
+    /**
+    Receive the next event.
+
+    Returns the event and its time stamp, or `None` if no new event was available within the
+    given timeout. Markers that are not well-formed events yield an `Error::BadArgument`.
+    */
+    pub fn pull(&self, timeout: f64) -> Result<Option<(Event, f64)>> {
+        let (sample, ts): (Vec<String>, f64) = self.inlet.pull_sample(timeout)?;
+        match sample.first() {
+            Some(marker) if ts != 0.0 => Ok(Some((Event::decode(marker)?, ts))),
+            _ => Ok(None),
+        }
+    }
+
+    /// The underlying inlet.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+}
+
+// Escape the characters that have a special meaning in the encoding.
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '%' => result.push_str("%25"),
+            '|' => result.push_str("%7C"),
+            '=' => result.push_str("%3D"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// Reverse the escaping done by escape().
+fn unescape(s: &str) -> Result<String> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('%') {
+        result.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 3).ok_or(Error::BadArgument)?;
+        result.push(match code {
+            "25" => '%',
+            "7C" => '|',
+            "3D" => '=',
+            _ => return Err(Error::BadArgument),
+        });
+        rest = &rest[pos + 3..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -0,0 +1,229 @@
+/*!
+Fragmentation of large blob samples.
+
+Very large samples (e.g., multi-megabyte video frames) stall the transport, since each sample is
+transmitted as a unit. The `Fragmenter` splits such blobs into numbered fragments that are pushed
+as successive samples of a single-channel string-formatted stream, and the `Assembler` on the
+receiving end puts them back together.
+
+Each fragment is a sample whose only channel holds a 12-byte header (the message number, the
+index of the fragment and the total number of fragments, each as a little-endian `u32`) followed
+by the payload bytes. The scheme is declared in the stream's meta-data (see `declare()`), so that
+consumers can recognize fragmented streams.
+*/
+
+use crate::{
+    local_clock, ChannelFormat, Error, ExPushable, Pullable, Result, StreamInfo, StreamInlet,
+    StreamOutlet,
+};
+
+/// Name of the fragmentation scheme, as written to the stream's meta-data.
+pub const SCHEME: &str = "lsl-fragment-v1";
+
+// size of the header that precedes the payload of each fragment
+const HEADER_BYTES: usize = 12;
+
+/**
+Declare in the meta-data of a stream that its samples are fragmented according to this module.
+
+This appends a `<fragmentation>` element with the `scheme` and `max_fragment_bytes` to the
+stream's `desc()` field.
+*/
+pub fn declare(info: &mut StreamInfo, max_fragment_bytes: usize) {
+    info.desc_mut()
+        .append_child("fragmentation")
+        .append_child_value("scheme", SCHEME)
+        .append_child_value("max_fragment_bytes", &max_fragment_bytes.to_string());
+}
+
+/// Check whether the meta-data of a (fully retrieved) stream declare this fragmentation scheme.
+pub fn is_declared(info: &StreamInfo) -> bool {
+    info.desc()
+        .child("fragmentation")
+        .child_value_named("scheme")
+        == SCHEME
+}
+
+/**
+Pushes large blobs into an outlet as a sequence of fragments.
+
+The outlet must be a single-channel stream of format `ChannelFormat::String`.
+*/
+#[derive(Debug)]
+pub struct Fragmenter {
+    outlet: StreamOutlet,
+    max_payload: usize,
+    next_message: u32,
+}
+
+impl Fragmenter {
+    /**
+    Create a new fragmenter that pushes into the given outlet.
+
+    Arguments:
+    * `outlet`: A single-channel outlet of format `ChannelFormat::String`.
+    * `max_fragment_bytes`: The maximum size of each fragment (including its 12-byte header).
+    */
+    pub fn new(outlet: StreamOutlet, max_fragment_bytes: usize) -> Result<Fragmenter> {
+        let info = outlet.info()?;
+        if info.channel_format() != ChannelFormat::String
+            || info.channel_count() != 1
+            || max_fragment_bytes <= HEADER_BYTES
+        {
+            return Err(Error::BadArgument);
+        }
+        Ok(Fragmenter {
+            outlet,
+            max_payload: max_fragment_bytes - HEADER_BYTES,
+            next_message: 0,
+        })
+    }
+
+    /**
+    Push a blob as a sequence of fragments.
+
+    Arguments:
+    * `blob`: The data to push.
+    * `timestamp`: Optionally the capture time of the blob, in agreement with `local_clock()`;
+      if passed as 0.0, the current time is used. All fragments carry this time stamp.
+    * `pushthrough`: Whether to push the last fragment through to the receivers.
+    */
+    pub fn push(&mut self, blob: &[u8], timestamp: f64, pushthrough: bool) -> Result<()> {
+        let timestamp = if timestamp == 0.0 {
+            local_clock()
+        } else {
+            timestamp
+        };
+        let fragments = encode(self.next_message, blob, self.max_payload);
+        self.next_message = self.next_message.wrapping_add(1);
+        let last = fragments.len() - 1;
+        for (k, fragment) in fragments.iter().enumerate() {
+            let sample = vec![&fragment[..]];
+            self.outlet
+                .push_sample_ex(&sample, timestamp, pushthrough && k == last)?;
+        }
+        Ok(())
+    }
+
+    /// The outlet into which fragments are pushed.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+/**
+Reassembles blobs from a stream of fragments produced by a `Fragmenter`.
+
+If fragments of a blob go missing (e.g., because the inlet's buffer overflowed), the incomplete
+blob is discarded.
+*/
+#[derive(Debug)]
+pub struct Assembler {
+    inlet: StreamInlet,
+    message: Option<u32>,
+    parts: Vec<Vec<u8>>,
+    expected: u32,
+}
+
+impl Assembler {
+    /// Create a new assembler that reads from the given inlet.
+    pub fn new(inlet: StreamInlet) -> Assembler {
+        Assembler {
+            inlet,
+            message: None,
+            parts: vec![],
+            expected: 0,
+        }
+    }
+
+    /**
+    Pull the next complete blob.
+
+    Arguments:
+    * `timeout`: The timeout for this operation, if any. If you use 0.0, only fragments that are
+      already buffered are processed. You can also use `lsl::FOREVER` to have no timeout.
+
+    Returns the blob and its time stamp, or `None` if no blob was completed within the timeout.
+    */
+    pub fn pull(&mut self, timeout: f64) -> Result<Option<(Vec<u8>, f64)>> {
+        let deadline = local_clock() + timeout;
+        loop {
+            let remaining = (deadline - local_clock()).max(0.0);
+            let (sample, ts): (Vec<Vec<u8>>, f64) = self.inlet.pull_sample(remaining)?;
+            if ts == 0.0 {
+                return Ok(None);
+            }
+            if let Some(fragment) = sample.first() {
+                if let Some(blob) = self.add(fragment) {
+                    return Ok(Some((blob, ts)));
+                }
+            }
+        }
+    }
+
+    /// The inlet from which fragments are read.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+
+    // Add a fragment; returns the blob if it is complete.
+    fn add(&mut self, fragment: &[u8]) -> Option<Vec<u8>> {
+        let (message, index, count) = decode_header(fragment)?;
+        if self.message != Some(message) || index as usize != self.parts.len() {
+            // a new blob starts here, any incomplete one is dropped
+            self.parts.clear();
+            self.message = Some(message);
+            self.expected = count;
+            if index != 0 {
+                return None;
+            }
+        }
+        self.parts.push(fragment[HEADER_BYTES..].to_vec());
+        if self.parts.len() as u32 == self.expected {
+            self.message = None;
+            Some(self.parts.drain(..).flatten().collect())
+        } else {
+            None
+        }
+    }
+}
+
+// Split a blob into fragments (each with header), of which there is at least one.
+fn encode(message: u32, blob: &[u8], max_payload: usize) -> Vec<Vec<u8>> {
+    let chunks: Vec<&[u8]> = if blob.is_empty() {
+        vec![blob]
+    } else {
+        blob.chunks(max_payload).collect()
+    };
+    let count = chunks.len() as u32;
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(index, payload)| {
+            let mut fragment = Vec::with_capacity(HEADER_BYTES + payload.len());
+            fragment.extend_from_slice(&message.to_le_bytes());
+            fragment.extend_from_slice(&(index as u32).to_le_bytes());
+            fragment.extend_from_slice(&count.to_le_bytes());
+            fragment.extend_from_slice(payload);
+            fragment
+        })
+        .collect()
+}
+
+// Parse the (message, index, count) header of a fragment, if well-formed.
+fn decode_header(fragment: &[u8]) -> Option<(u32, u32, u32)> {
+    if fragment.len() < HEADER_BYTES {
+        return None;
+    }
+    let field = |k: usize| {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&fragment[4 * k..4 * k + 4]);
+        u32::from_le_bytes(bytes)
+    };
+    let (message, index, count) = (field(0), field(1), field(2));
+    if index < count {
+        Some((message, index, count))
+    } else {
+        None
+    }
+}
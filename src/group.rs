@@ -0,0 +1,305 @@
+/*!
+Keeping inlets open to all streams that match a query.
+
+Recorders and dashboards typically read from every stream that matches some condition (e.g., all
+EEG streams in the lab), and have to cope with streams that come and go while they are running.
+A `StreamGroup` does this bookkeeping: it continuously resolves the matching streams, opens an
+inlet for each stream that appears, closes the inlet of each stream that disappears (or fails),
+and delivers the samples of all of its inlets through a single pull interface, each tagged with
+the UID of its stream (as in `lsl::inlets::MultiReader`).
+
+```no_run
+use lsl::group::{GroupOptions, StreamGroup};
+use lsl::query::Query;
+
+fn main() -> Result<(), lsl::Error> {
+    let group = StreamGroup::new(Query::new().type_is("EEG"), GroupOptions::default())?;
+    loop {
+        if let Some(sample) = group.pull(1.0)? {
+            let name = group.info(&sample.uid).map(|info| info.stream_name());
+            println!("{:?}: {:?} at {}", name, sample.value, sample.timestamp);
+        }
+    }
+}
+```
+*/
+
+use crate::inlets::{InletSet, TaggedSample};
+use crate::worker::{Handle, Task, Worker};
+use crate::{local_clock, ContinuousResolver, Error, Result, StreamInfo, StreamInlet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+// the interval, in seconds, at which a group checks its resolver for streams that have appeared
+// or disappeared
+const REFRESH: f64 = 0.1;
+
+/// The settings of a `StreamGroup`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct GroupOptions {
+    /// The time, in seconds, after which a stream that is no longer seen on the network is removed
+    /// from the group (see `ContinuousResolver::new()`); defaults to 5.0.
+    pub forget_after: f64,
+    /// The maximum amount of data that each inlet buffers (see `StreamInlet::new()`); defaults to
+    /// 360.
+    pub max_buflen: i32,
+    /// Whether the inlets try to recover lost streams (see `StreamInlet::new()`); defaults to
+    /// true. Without recovery, the inlet of a lost stream is closed right away.
+    pub recover: bool,
+    /// The number of samples that are held for the consumer; while they are not pulled, the
+    /// inlets keep buffering. Defaults to 1000.
+    pub capacity: usize,
+}
+
+impl Default for GroupOptions {
+    fn default() -> GroupOptions {
+        GroupOptions {
+            forget_after: 5.0,
+            max_buflen: 360,
+            recover: true,
+            capacity: 1000,
+        }
+    }
+}
+
+// state that is shared between a group and its background thread
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    // signaled when samples have been queued, or when the background thread has failed
+    available: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    queue: VecDeque<TaggedSample>,
+    // the streams that the group currently has inlets for, by UID
+    members: HashMap<Arc<str>, StreamInfo>,
+    // the error that ended the background thread
+    error: Option<Error>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// the task of a `StreamGroup`: keeping the inlets in line with the resolver, and reading them
+#[derive(Debug)]
+struct GroupTask {
+    resolver: ContinuousResolver,
+    options: GroupOptions,
+    inlets: InletSet,
+    // the id of each stream's inlet in the set, by UID, and vice versa
+    ids: HashMap<Arc<str>, usize>,
+    uids: HashMap<usize, Arc<str>>,
+    // the UIDs of the streams whose inlets have failed, which are not reopened while the resolver
+    // still reports them
+    failed: HashSet<Arc<str>>,
+    next_refresh: f64,
+    shared: Arc<Shared>,
+}
+
+impl GroupTask {
+    fn refresh(&mut self) -> Result<()> {
+        let current: HashMap<Arc<str>, StreamInfo> = self
+            .resolver
+            .results()?
+            .into_iter()
+            .map(|info| (info.uid().into(), info))
+            .collect();
+        self.failed.retain(|uid| current.contains_key(uid));
+        let vanished: Vec<_> = self
+            .ids
+            .keys()
+            .filter(|uid| !current.contains_key(*uid))
+            .cloned()
+            .collect();
+        for uid in vanished {
+            self.close(uid);
+        }
+        for (uid, info) in current {
+            if self.ids.contains_key(&uid) || self.failed.contains(&uid) {
+                continue;
+            }
+            let options = &self.options;
+            match StreamInlet::new(&info, options.max_buflen, 0, options.recover) {
+                Ok(inlet) => {
+                    let id = self.inlets.add(inlet);
+                    self.ids.insert(uid.clone(), id);
+                    self.uids.insert(id, uid.clone());
+                    self.shared.lock().members.insert(uid, info);
+                }
+                Err(_) => {
+                    self.failed.insert(uid);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, uid: Arc<str>) {
+        if let Some(id) = self.ids.remove(&uid) {
+            self.inlets.remove(id);
+            self.uids.remove(&id);
+        }
+        self.shared.lock().members.remove(&uid);
+        self.failed.insert(uid);
+    }
+
+    fn step(&mut self, handle: &Handle) -> Result<()> {
+        if local_clock() >= self.next_refresh {
+            self.refresh()?;
+            self.next_refresh = local_clock() + REFRESH;
+        }
+        let room = self.options.capacity.max(1);
+        let room = room.saturating_sub(self.shared.lock().queue.len());
+        if self.inlets.is_empty() || room == 0 {
+            // wait for streams to appear, or for the consumer to catch up
+            handle.wait(REFRESH);
+            return Ok(());
+        }
+        // (waiting no longer than until the next refresh)
+        let timeout = (self.next_refresh - local_clock()).max(0.0);
+        let mut failed = vec![];
+        let received: Vec<_> = self
+            .inlets
+            .poll_each(timeout, room, &mut failed)
+            .into_iter()
+            .map(|(id, value, timestamp)| TaggedSample {
+                uid: self.uids[&id].clone(),
+                value,
+                timestamp,
+            })
+            .collect();
+        for (id, _) in failed {
+            let uid = self.uids[&id].clone();
+            self.close(uid);
+        }
+        if !received.is_empty() {
+            self.shared.lock().queue.extend(received);
+            self.shared.available.notify_all();
+        }
+        Ok(())
+    }
+}
+
+impl Task for GroupTask {
+    fn run_once(&mut self, handle: &Handle) -> Result<()> {
+        let result = self.step(handle);
+        if let Err(e) = &result {
+            let mut state = self.shared.lock();
+            state.error = Some(e.clone());
+            state.members.clear();
+            self.shared.available.notify_all();
+        }
+        result
+    }
+}
+
+/**
+A set of inlets that is kept open to all streams that match a query (see the module docs).
+
+The group is maintained by a background thread (see `lsl::worker`), which checks for streams
+that have appeared or disappeared several times per second. A stream that is lost is removed
+from the group once the resolver no longer reports it (after `GroupOptions::forget_after`
+seconds), or right away if its inlet fails; it is added again when it reappears. Dropping the
+group stops the thread and closes the inlets.
+*/
+#[derive(Debug)]
+pub struct StreamGroup {
+    worker: Worker<GroupTask>,
+    shared: Arc<Shared>,
+}
+
+impl StreamGroup {
+    /**
+    Start keeping a group of the streams that match a query.
+
+    Arguments:
+    * `query`: The predicate that the streams must match, as a `lsl::query::Query` or a string
+      (see `resolve_bypred()`).
+    * `options`: The settings of the group (see `GroupOptions`).
+
+    Note that the resolver needs some time (typically well below a second) after the group's
+    creation to discover the streams that are already present.
+    */
+    pub fn new(query: impl AsRef<str>, options: GroupOptions) -> Result<StreamGroup> {
+        let shared = Arc::new(Shared::default());
+        let task = GroupTask {
+            resolver: ContinuousResolver::new_with_pred(query, options.forget_after)?,
+            options,
+            inlets: InletSet::new(),
+            ids: HashMap::new(),
+            uids: HashMap::new(),
+            failed: HashSet::new(),
+            next_refresh: 0.0,
+            shared: shared.clone(),
+        };
+        Ok(StreamGroup {
+            worker: Worker::spawn(task),
+            shared,
+        })
+    }
+
+    /**
+    Receive the next sample of any of the streams in the group.
+
+    Returns the sample, tagged with the UID of its stream, or `None` if no sample arrived within
+    the given timeout (in seconds). Returns an error if the group has failed (i.e., its resolver
+    has failed), after the samples that were received before have been pulled.
+    */
+    pub fn pull(&self, timeout: f64) -> Result<Option<TaggedSample>> {
+        let deadline = local_clock() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(sample) = state.queue.pop_front() {
+                self.worker.handle().wake();
+                return Ok(Some(sample));
+            }
+            if let Some(e) = &state.error {
+                return Err(e.clone());
+            }
+            let remaining = deadline - local_clock();
+            if remaining <= 0.0 {
+                return Ok(None);
+            }
+            state = self
+                .shared
+                .available
+                .wait_timeout(state, Duration::from_secs_f64(remaining))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Receive all samples that are currently available (see `pull()`).
+    pub fn pull_available(&self) -> Result<Vec<TaggedSample>> {
+        let mut state = self.shared.lock();
+        if let (true, Some(e)) = (state.queue.is_empty(), &state.error) {
+            return Err(e.clone());
+        }
+        self.worker.handle().wake();
+        Ok(state.queue.drain(..).collect())
+    }
+
+    /// The streams that the group currently has inlets for.
+    pub fn streams(&self) -> Vec<StreamInfo> {
+        self.shared.lock().members.values().cloned().collect()
+    }
+
+    /// The stream with the given UID (e.g., of a `TaggedSample`), if it is in the group.
+    pub fn info(&self, uid: &str) -> Option<StreamInfo> {
+        self.shared.lock().members.get(uid).cloned()
+    }
+
+    /**
+    Stop the background thread, close the inlets, and return the error with which the group
+    failed, if any.
+    */
+    pub fn stop(self) -> Result<()> {
+        self.worker.stop().1
+    }
+}
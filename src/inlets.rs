@@ -0,0 +1,809 @@
+/*!
+Servicing several inlets from a few threads, sharing one inlet between threads, and keeping an
+inlet connected.
+
+Applications that read from several streams (e.g., a marker stream and a few data streams) would
+otherwise need one thread per inlet, since each pull call blocks on one inlet only. An `InletSet`
+instead waits on all of its inlets at once, and returns the samples of whichever inlets have
+data, tagged with the id that was assigned to the inlet when it was added. The samples are
+returned as `SampleValue`s, so that the inlets can have different channel formats.
+
+```no_run
+use lsl::inlets::InletSet;
+use lsl::InletBuilder;
+
+fn main() -> Result<(), lsl::Error> {
+    let mut set = InletSet::new();
+    let markers = set.add(InletBuilder::resolve("type", "Markers").open()?);
+    let _eeg = set.add(InletBuilder::resolve("type", "EEG").open()?);
+    loop {
+        for (id, value, ts) in set.poll(1.0)? {
+            if id == markers {
+                println!("marker {:?} at {}", value, ts);
+            }
+        }
+    }
+}
+```
+
+For rigs with many streams (e.g., a recorder with dozens of inlets), a `MultiReader` runs such
+sets on a small pool of worker threads, and delivers the samples of all inlets to a single
+callback or channel, each tagged with the UID of its stream:
+
+```no_run
+use lsl::inlets::MultiReader;
+use lsl::StreamInlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let inlets = lsl::resolve_streams(2.0)?
+        .iter()
+        .map(|info| StreamInlet::new(info, 360, 0, true))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (_reader, samples) = MultiReader::with_channel(inlets, 4, 1000);
+    for sample in samples.iter() {
+        println!("{}: {:?} at {}", sample.uid, sample.value, sample.timestamp);
+    }
+    Ok(())
+}
+```
+
+Conversely, a `SyncInlet` lets several threads pull from the same stream (e.g., a pool of workers
+that each process the next available sample). It enables the native library's thread-safe
+post-processing (`ProcessingOption::Threadsafe`) when it is created, and can therefore be shared
+by reference (e.g., in an `Arc`), whereas a plain `StreamInlet` can only be moved between threads.
+The thread safety costs some CPU time on each pull, so it is only worth it if the inlet is in
+fact shared; otherwise, a `StreamInlet` owned by a single thread is the better choice.
+
+```no_run
+use lsl::inlets::SyncInlet;
+use std::sync::Arc;
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = Arc::new(SyncInlet::new(&res[0], 360, 0, true)?);
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let inlet = inlet.clone();
+            std::thread::spawn(move || loop {
+                let (sample, ts) = inlet.pull_sample::<f32>(lsl::FOREVER).unwrap();
+                println!("{:?} at {}", sample, ts);
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    Ok(())
+}
+```
+
+Finally, a `ResilientInlet` survives the restart of a sending program whose stream has no source
+id, which a plain inlet cannot recover from: it finds the restarted stream by its name, type,
+format and meta-data, opens a new inlet to it, and reports the gap to the caller.
+
+```no_run
+use lsl::inlets::ResilientInlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop(lsl::ResolveProp::Name, "Tracker", 1, 5.0)?;
+    let mut inlet = ResilientInlet::new(&res[0], 360, 0, 5.0)?;
+    loop {
+        let (sample, ts): (Vec<f32>, _) = inlet.pull_sample(1.0)?;
+        for resync in inlet.take_resyncs() {
+            println!("stream was lost for {} s", resync.reacquired_at - resync.lost_at);
+        }
+        if ts != 0.0 {
+            println!("{:?}", sample);
+        }
+    }
+}
+```
+*/
+
+use crate::bridge::{self, Receiver, Sender};
+use crate::query::Query;
+use crate::worker::{Handle, Task, Worker, MAX_BLOCK};
+use crate::{
+    local_clock, ChannelFormat, ContinuousResolver, Error, ProcessingOption, Pullable, Result,
+    SampleValue, StreamInfo, StreamInlet,
+};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+// the range of the time slice, in seconds, for which a waiting poll blocks on each inlet in turn:
+// the slice doubles while no data arrive, so that an idle set wakes up rarely, and starts over
+// at the minimum once data arrive
+const MIN_SLICE: f64 = 0.001;
+const MAX_SLICE: f64 = 0.008;
+
+/**
+A set of inlets that can be polled for new samples together.
+
+Since the native library cannot wait on several inlets at once, a waiting poll blocks on each
+inlet in turn for a short time slice, which grows (up to 8 ms) while the set is idle; the first
+sample after an idle period can therefore be delayed by up to that slice per inlet. A set with
+a single inlet simply blocks on it.
+*/
+#[derive(Debug, Default)]
+pub struct InletSet {
+    // indexed by id (removed inlets leave a gap, so that ids stay valid)
+    inlets: Vec<Option<StreamInlet>>,
+    // the current time slice of a waiting poll (0 until the first wait)
+    slice: Cell<f64>,
+}
+
+impl InletSet {
+    /// Create an empty set.
+    pub fn new() -> InletSet {
+        InletSet::default()
+    }
+
+    /// Add an inlet to the set, and return the id by which its samples are tagged.
+    pub fn add(&mut self, inlet: StreamInlet) -> usize {
+        self.inlets.push(Some(inlet));
+        self.inlets.len() - 1
+    }
+
+    /// Remove the inlet with the given id from the set, and return it.
+    pub fn remove(&mut self, id: usize) -> Option<StreamInlet> {
+        self.inlets.get_mut(id).and_then(Option::take)
+    }
+
+    /// The inlet with the given id, if it is (still) in the set.
+    pub fn inlet(&self, id: usize) -> Option<&StreamInlet> {
+        self.inlets.get(id).and_then(Option::as_ref)
+    }
+
+    /// The number of inlets in the set.
+    pub fn len(&self) -> usize {
+        self.inlets.iter().flatten().count()
+    }
+
+    /// Whether the set contains no inlets.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+    Receive the samples that are available on any of the inlets, waiting up to `timeout` seconds
+    for the first one to arrive.
+
+    Returns the samples as `(id, value, timestamp)` tuples, in the order of the inlets (and in
+    temporal order per inlet), or an empty vector if no sample arrived in time. An error of any
+    of the inlets (e.g., `Error::StreamLost`) is returned immediately; samples that were already
+    received in the same call are then lost.
+    */
+    pub fn poll(&self, timeout: f64) -> Result<Vec<(usize, SampleValue, f64)>> {
+        let mut failed = vec![];
+        let result = self.poll_each(timeout, usize::MAX, &mut failed);
+        match failed.into_iter().next() {
+            Some((_, e)) => Err(e),
+            None => Ok(result),
+        }
+    }
+
+    /**
+    Like `poll()`, but receive at most `max` samples, and instead of failing, note the inlets that
+    have failed (with their error) in `failed`; they are not pulled from for the rest of the call,
+    and should be removed by the caller.
+    */
+    pub(crate) fn poll_each(
+        &self,
+        timeout: f64,
+        max: usize,
+        failed: &mut Vec<(usize, Error)>,
+    ) -> Vec<(usize, SampleValue, f64)> {
+        let deadline = local_clock() + timeout;
+        let mut result = vec![];
+        loop {
+            for (id, inlet) in self.iter() {
+                if failed.iter().any(|&(failed_id, _)| failed_id == id) {
+                    continue;
+                }
+                while result.len() < max {
+                    match inlet.pull_sample_dynamic(0.0) {
+                        Ok(Some((value, ts))) => result.push((id, value, ts)),
+                        Ok(None) => break,
+                        Err(e) => {
+                            failed.push((id, e));
+                            break;
+                        }
+                    }
+                }
+            }
+            let remaining = deadline - local_clock();
+            let waiting = self.len() - failed.len();
+            if !result.is_empty() || !failed.is_empty() || remaining <= 0.0 || waiting == 0 {
+                if !result.is_empty() {
+                    self.slice.set(MIN_SLICE);
+                }
+                return result;
+            }
+            // wait on each inlet in turn for a short time (the native library returns as soon as
+            // data arrive), then collect whatever else has arrived in the meantime
+            let slice = match waiting {
+                1 => remaining,
+                _ => self.slice.get().max(MIN_SLICE).min(remaining),
+            };
+            for (id, inlet) in self.iter() {
+                match inlet.pull_sample_dynamic(slice) {
+                    Ok(Some((value, ts))) => {
+                        result.push((id, value, ts));
+                        break;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        failed.push((id, e));
+                        break;
+                    }
+                }
+            }
+            if result.is_empty() {
+                self.slice.set((slice * 2.0).min(MAX_SLICE));
+            }
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, &StreamInlet)> {
+        self.inlets
+            .iter()
+            .enumerate()
+            .filter_map(|(id, inlet)| inlet.as_ref().map(|inlet| (id, inlet)))
+    }
+}
+
+/**
+A sample delivered by a `MultiReader` (or a `lsl::group::StreamGroup`), tagged with the UID of
+the stream it came from.
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct TaggedSample {
+    /// The UID of the stream (see `StreamInfo::uid()`).
+    pub uid: Arc<str>,
+    /// The values of the sample.
+    pub value: SampleValue,
+    /// The time stamp of the sample.
+    pub timestamp: f64,
+}
+
+// a consumer callback, shared by the threads of a `MultiReader`
+type Callback = dyn FnMut(TaggedSample) + Send;
+
+// where the threads of a `MultiReader` deliver their samples
+#[derive(Clone)]
+enum Sink {
+    Callback(Arc<Mutex<Callback>>),
+    Channel(Sender<TaggedSample>),
+}
+
+// (closures are not `Debug`)
+impl fmt::Debug for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sink::Callback(_) => f.write_str("Callback"),
+            Sink::Channel(sender) => f.debug_tuple("Channel").field(sender).finish(),
+        }
+    }
+}
+
+/// An inlet that a `MultiReader` has closed because it failed.
+#[derive(PartialEq, Clone, Debug)]
+pub struct InletFailure {
+    /// The UID of the stream (see `StreamInfo::uid()`).
+    pub uid: Arc<str>,
+    /// The error of the inlet (e.g., `Error::StreamLost`).
+    pub error: Error,
+}
+
+// the failures of the inlets of a `MultiReader`, shared by its threads
+type Failures = Arc<Mutex<Vec<InletFailure>>>;
+
+// the task of one thread of a `MultiReader`: polling its share of the inlets
+#[derive(Debug)]
+struct ReaderTask {
+    set: InletSet,
+    uids: Vec<Arc<str>>,
+    sink: Sink,
+    failures: Failures,
+}
+
+impl Task for ReaderTask {
+    fn run_once(&mut self, handle: &Handle) -> Result<()> {
+        let mut failed = vec![];
+        let samples = self.set.poll_each(MAX_BLOCK, usize::MAX, &mut failed);
+        for (id, error) in failed {
+            self.set.remove(id);
+            let uid = self.uids[id].clone();
+            let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+            failures.push(InletFailure { uid, error });
+        }
+        if self.set.is_empty() {
+            handle.stop();
+        }
+        for (id, value, timestamp) in samples {
+            let sample = TaggedSample {
+                uid: self.uids[id].clone(),
+                value,
+                timestamp,
+            };
+            match &self.sink {
+                Sink::Callback(callback) => {
+                    let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+                    callback(sample);
+                }
+                Sink::Channel(sender) => {
+                    // the receiver has gone away: there is nobody left to read for
+                    if sender.send(sample).is_err() {
+                        handle.stop();
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+Reads from many inlets on a small pool of threads (see the module documentation).
+
+The inlets are distributed evenly over the threads, each of which waits on all of its inlets at
+once (like an `InletSet`). An inlet that fails (e.g., with `Error::StreamLost` if it does not
+recover) is closed, while the others are still read; the failures can be retrieved via
+`take_failures()`. A thread ends once all of its inlets have failed, which can be observed via
+`running()`. Dropping the reader stops all threads (within `lsl::worker::MAX_BLOCK` seconds) and
+closes the inlets.
+*/
+#[derive(Debug)]
+pub struct MultiReader {
+    workers: Vec<Worker<ReaderTask>>,
+    failures: Failures,
+}
+
+impl MultiReader {
+    /**
+    Start reading, and hand each sample to a callback.
+
+    Arguments:
+    * `inlets`: The inlets to read from.
+    * `threads`: The number of threads to use (at least 1, and at most one per inlet).
+    * `callback`: Called with each sample. The calls are serialized, but come from different
+      threads; a slow callback holds up all of them (and the inlets keep buffering meanwhile).
+    */
+    pub fn with_callback<F>(inlets: Vec<StreamInlet>, threads: usize, callback: F) -> MultiReader
+    where
+        F: FnMut(TaggedSample) + Send + 'static,
+    {
+        let callback = Arc::new(Mutex::new(callback));
+        MultiReader::start(inlets, threads, Sink::Callback(callback))
+    }
+
+    /**
+    Start reading, and send the samples into a bounded channel (see `lsl::bridge`).
+
+    Arguments:
+    * `inlets`: The inlets to read from.
+    * `threads`: The number of threads to use (at least 1, and at most one per inlet).
+    * `capacity`: The capacity of the channel; when it is full, the threads wait for the
+      consumer (and the inlets keep buffering meanwhile).
+
+    Returns the reader and the receiving end of the channel. The threads stop once the receiver
+    has been dropped.
+    */
+    pub fn with_channel(
+        inlets: Vec<StreamInlet>,
+        threads: usize,
+        capacity: usize,
+    ) -> (MultiReader, Receiver<TaggedSample>) {
+        let (sender, receiver) = bridge::channel(capacity);
+        let reader = MultiReader::start(inlets, threads, Sink::Channel(sender));
+        (reader, receiver)
+    }
+
+    fn start(inlets: Vec<StreamInlet>, threads: usize, sink: Sink) -> MultiReader {
+        let threads = threads.max(1).min(inlets.len());
+        let failures = Failures::default();
+        let mut tasks: Vec<_> = (0..threads)
+            .map(|_| ReaderTask {
+                set: InletSet::new(),
+                uids: vec![],
+                sink: sink.clone(),
+                failures: failures.clone(),
+            })
+            .collect();
+        for (k, inlet) in inlets.into_iter().enumerate() {
+            let task = &mut tasks[k % threads];
+            task.uids.push(inlet.core_info().uid().into());
+            task.set.add(inlet);
+        }
+        MultiReader {
+            workers: tasks.into_iter().map(Worker::spawn).collect(),
+            failures,
+        }
+    }
+
+    /// The number of threads that are still reading (i.e., that have inlets left).
+    pub fn running(&self) -> usize {
+        self.workers.iter().filter(|w| !w.is_finished()).count()
+    }
+
+    /// Take the failures of inlets that have occurred since the last call, oldest first.
+    pub fn take_failures(&self) -> Vec<InletFailure> {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        failures.drain(..).collect()
+    }
+
+    /**
+    Stop all threads and wait until they have finished.
+
+    Returns the inlets that have not failed (in no particular order), along with the first error
+    that ended a thread, if any.
+    */
+    pub fn stop(self) -> (Vec<StreamInlet>, Result<()>) {
+        let mut inlets = vec![];
+        let mut result = Ok(());
+        for worker in self.workers {
+            let (mut task, outcome) = worker.stop();
+            for id in 0..task.uids.len() {
+                inlets.extend(task.set.remove(id));
+            }
+            result = result.and(outcome);
+        }
+        (inlets, result)
+    }
+}
+
+/**
+An inlet whose pull methods can be called from several threads at once (see the module docs).
+
+Only the methods that are safe to call concurrently are exposed; in particular, the
+post-processing options can only be changed in a way that keeps them thread-safe.
+*/
+#[derive(Debug)]
+pub struct SyncInlet {
+    inlet: StreamInlet,
+}
+
+impl SyncInlet {
+    /**
+    Open a new inlet with thread-safe post-processing (see `StreamInlet::new()` for the
+    arguments).
+    */
+    pub fn new(
+        info: &StreamInfo,
+        max_buflen: i32,
+        max_chunklen: i32,
+        recover: bool,
+    ) -> Result<SyncInlet> {
+        let inlet = StreamInlet::new(info, max_buflen, max_chunklen, recover)?;
+        SyncInlet::from_inlet(inlet, &[])
+    }
+
+    /**
+    Make an existing inlet shareable between threads.
+
+    The inlet's post-processing is replaced by the given options, along with
+    `ProcessingOption::Threadsafe` (see `set_postprocessing()`).
+    */
+    pub fn from_inlet(inlet: StreamInlet, options: &[ProcessingOption]) -> Result<SyncInlet> {
+        let inlet = SyncInlet { inlet };
+        inlet.set_postprocessing(options)?;
+        Ok(inlet)
+    }
+
+    /**
+    Set the post-processing options (see `StreamInlet::set_postprocessing()`).
+
+    `ProcessingOption::Threadsafe` is always added to the given options.
+    */
+    pub fn set_postprocessing(&self, options: &[ProcessingOption]) -> Result<()> {
+        let mut options = options.to_vec();
+        options.push(ProcessingOption::Threadsafe);
+        self.inlet.set_postprocessing(&options)
+    }
+
+    /// Pull the next sample (see `Pullable::pull_sample()`).
+    pub fn pull_sample<T>(&self, timeout: f64) -> Result<(Vec<T>, f64)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.inlet.pull_sample(timeout)
+    }
+
+    /// Pull the samples that are currently available (see `Pullable::pull_chunk()`).
+    pub fn pull_chunk<T>(&self) -> Result<(Vec<Vec<T>>, Vec<f64>)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.inlet.pull_chunk()
+    }
+
+    /// Pull the next sample in any channel format (see `StreamInlet::pull_sample_dynamic()`).
+    pub fn pull_sample_dynamic(&self, timeout: f64) -> Result<Option<(SampleValue, f64)>> {
+        self.inlet.pull_sample_dynamic(timeout)
+    }
+
+    /// The number of samples that are available for immediate pickup.
+    pub fn samples_available(&self) -> u32 {
+        self.inlet.samples_available()
+    }
+
+    /// Retrieve the complete stream declaration (see `StreamInlet::info()`).
+    pub fn info(&self, timeout: f64) -> Result<StreamInfo> {
+        self.inlet.info(timeout)
+    }
+
+    /// Retrieve an estimate of the time-correction offset (see `StreamInlet::time_correction()`).
+    pub fn time_correction(&self, timeout: f64) -> Result<f64> {
+        self.inlet.time_correction(timeout)
+    }
+
+    /**
+    Return the underlying inlet (which keeps the thread-safe post-processing until it is
+    changed).
+    */
+    pub fn into_inner(self) -> StreamInlet {
+        self.inlet
+    }
+}
+
+// concurrent pulls are safe in the native library once `ProcessingOption::Threadsafe` is set
+// (which `SyncInlet` guarantees), and the state of the Rust wrapper that is reachable via `&self`
+// is either immutable or behind locks
+unsafe impl Sync for SyncInlet {}
+
+// the time, in seconds, that a `ResilientInlet` waits for the meta-data of a candidate stream
+const CANDIDATE_TIMEOUT: f64 = 1.0;
+
+// the interval, in seconds, at which a `ResilientInlet` checks for candidates while it waits
+const REACQUIRE_POLL: f64 = 0.05;
+
+// the properties by which a `ResilientInlet` recognizes its stream after a restart of the source
+// (whose UID, and possibly host, are new)
+#[derive(PartialEq, Debug)]
+struct Fingerprint {
+    name: String,
+    type_: String,
+    channel_count: i32,
+    channel_format: ChannelFormat,
+    nominal_srate: f64,
+    // the meta-data, as XML
+    desc: String,
+}
+
+impl Fingerprint {
+    // (requires the full stream info, including the meta-data)
+    fn of(info: &StreamInfo) -> Result<Fingerprint> {
+        let xml = info.to_xml()?;
+        Ok(Fingerprint {
+            name: info.stream_name(),
+            type_: info.stream_type(),
+            channel_count: info.channel_count(),
+            channel_format: info.channel_format(),
+            nominal_srate: info.nominal_srate(),
+            desc: xml[xml.find("<desc").unwrap_or(xml.len())..].to_string(),
+        })
+    }
+
+    fn query(&self) -> Result<Query> {
+        Query::new()
+            .name_is(&self.name)
+            .type_is(&self.type_)
+            .channel_count(self.channel_count as u32)
+            .nominal_srate(self.nominal_srate)
+    }
+}
+
+/// The reacquisition of a lost stream by a `ResilientInlet`.
+#[derive(Clone, Debug)]
+pub struct Resync {
+    /// The stream that was lost.
+    pub lost: StreamInfo,
+    /// The stream that replaces it (with the same name, type, format and meta-data).
+    pub found: StreamInfo,
+    /// When the loss was detected (in agreement with `lsl::local_clock()`).
+    pub lost_at: f64,
+    /// When the new inlet was opened; the samples in between are missing.
+    pub reacquired_at: f64,
+}
+
+/**
+An inlet that reopens its stream after the stream was lost for good.
+
+A `StreamInlet` recovers from the loss of a stream only if the stream has a source id (see
+`StreamInfo::new()`), and fails with `Error::StreamLost` otherwise, e.g., when the sending
+program was restarted. A `ResilientInlet` then looks for a stream with the same name, type,
+channel count, format, sampling rate and meta-data, and transparently opens a new inlet to it.
+Each such reacquisition is recorded as a `Resync`, which the caller can collect via
+`take_resyncs()` (e.g., to mark the gap in a recording, or to reset any filter state).
+
+While the stream is lost, the pull methods behave as if no data were available; in particular,
+`pull_chunk()` returns right away and checks for a replacement on each call. Checking a
+candidate stream involves fetching its meta-data, so that a pull call can take up to about a
+second longer than its timeout while a candidate is found.
+*/
+#[derive(Debug)]
+pub struct ResilientInlet {
+    inlet: StreamInlet,
+    // the (full) declaration of the current stream
+    info: StreamInfo,
+    fingerprint: Fingerprint,
+    max_buflen: i32,
+    max_chunklen: i32,
+    postprocessing: Vec<ProcessingOption>,
+    // the state while the stream is lost
+    lost: Option<Lost>,
+    resyncs: Vec<Resync>,
+}
+
+// a lost stream that a `ResilientInlet` is looking for
+#[derive(Debug)]
+struct Lost {
+    at: f64,
+    resolver: ContinuousResolver,
+    // the UIDs of the streams that have been ruled out (including the lost stream itself)
+    rejected: HashSet<String>,
+}
+
+impl ResilientInlet {
+    /**
+    Open a resilient inlet.
+
+    Arguments:
+    * `info`: A resolved stream info object (as coming from one of the resolver functions).
+    * `max_buflen`: The maximum amount of data to buffer (see `StreamInlet::new()`).
+    * `max_chunklen`: The maximum size of the chunks that are transmitted (see
+      `StreamInlet::new()`).
+    * `timeout`: The time, in seconds, to wait for the stream's meta-data, which are needed to
+      recognize the stream later on.
+    */
+    pub fn new(
+        info: &StreamInfo,
+        max_buflen: i32,
+        max_chunklen: i32,
+        timeout: f64,
+    ) -> Result<ResilientInlet> {
+        let inlet = StreamInlet::new(info, max_buflen, max_chunklen, true)?;
+        let info = inlet.info(timeout)?;
+        Ok(ResilientInlet {
+            fingerprint: Fingerprint::of(&info)?,
+            inlet,
+            info,
+            max_buflen,
+            max_chunklen,
+            postprocessing: vec![],
+            lost: None,
+            resyncs: vec![],
+        })
+    }
+
+    /// Pull the next sample (see `Pullable::pull_sample()`).
+    pub fn pull_sample<T>(&mut self, timeout: f64) -> Result<(Vec<T>, f64)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.pull_with(timeout, (vec![], 0.0), |inlet, timeout| {
+            inlet.pull_sample(timeout)
+        })
+    }
+
+    /// Pull the samples that are currently available (see `Pullable::pull_chunk()`).
+    pub fn pull_chunk<T>(&mut self) -> Result<(Vec<Vec<T>>, Vec<f64>)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        self.pull_with(0.0, (vec![], vec![]), |inlet, _| inlet.pull_chunk())
+    }
+
+    /// Pull the next sample in any channel format (see `StreamInlet::pull_sample_dynamic()`).
+    pub fn pull_sample_dynamic(&mut self, timeout: f64) -> Result<Option<(SampleValue, f64)>> {
+        self.pull_with(timeout, None, |inlet, timeout| {
+            inlet.pull_sample_dynamic(timeout)
+        })
+    }
+
+    /**
+    Set the post-processing options (see `StreamInlet::set_postprocessing()`), which are also
+    applied to the inlets that are opened after a loss.
+    */
+    pub fn set_postprocessing(&mut self, options: &[ProcessingOption]) -> Result<()> {
+        self.inlet.set_postprocessing(options)?;
+        self.postprocessing = options.to_vec();
+        Ok(())
+    }
+
+    /// Whether the stream is currently lost (i.e., a replacement has not been found yet).
+    pub fn is_lost(&self) -> bool {
+        self.lost.is_some()
+    }
+
+    /// Take the reacquisitions that have happened since the last call.
+    pub fn take_resyncs(&mut self) -> Vec<Resync> {
+        std::mem::take(&mut self.resyncs)
+    }
+
+    /// The full declaration of the current stream (including its meta-data).
+    pub fn info(&self) -> &StreamInfo {
+        &self.info
+    }
+
+    /// The current inlet (which is replaced when the stream is reacquired).
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+
+    // Pull from the current inlet, and look for a replacement while the stream is lost, until
+    // the timeout has expired; returns `empty` if nothing could be pulled.
+    fn pull_with<R>(
+        &mut self,
+        timeout: f64,
+        empty: R,
+        pull: impl Fn(&StreamInlet, f64) -> Result<R>,
+    ) -> Result<R> {
+        let deadline = local_clock() + timeout;
+        loop {
+            if self.lost.is_none() {
+                match pull(&self.inlet, (deadline - local_clock()).max(0.0)) {
+                    Err(Error::StreamLost) => self.lose()?,
+                    result => return result,
+                }
+            }
+            if !self.reacquire()? {
+                let remaining = deadline - local_clock();
+                if remaining <= 0.0 {
+                    return Ok(empty);
+                }
+                let pause = REACQUIRE_POLL.min(remaining);
+                std::thread::sleep(std::time::Duration::from_secs_f64(pause));
+            }
+        }
+    }
+
+    fn lose(&mut self) -> Result<()> {
+        let resolver = ContinuousResolver::new_with_pred(self.fingerprint.query()?, 5.0)?;
+        let mut rejected = HashSet::new();
+        rejected.insert(self.info.uid());
+        self.lost = Some(Lost {
+            at: local_clock(),
+            resolver,
+            rejected,
+        });
+        Ok(())
+    }
+
+    // Check the streams that the resolver has found, and switch to the first one that matches;
+    // returns whether one did.
+    fn reacquire(&mut self) -> Result<bool> {
+        let lost = match &mut self.lost {
+            Some(lost) => lost,
+            None => return Ok(true),
+        };
+        for candidate in lost.resolver.results()? {
+            if !lost.rejected.insert(candidate.uid()) {
+                continue;
+            }
+            let inlet = StreamInlet::new(&candidate, self.max_buflen, self.max_chunklen, true)?;
+            let info = match inlet.info(CANDIDATE_TIMEOUT) {
+                Ok(info) => info,
+                // (the candidate may be gone again, or be slow to respond)
+                Err(_) => continue,
+            };
+            if Fingerprint::of(&info)? != self.fingerprint {
+                continue;
+            }
+            inlet.set_postprocessing(&self.postprocessing)?;
+            self.resyncs.push(Resync {
+                lost: std::mem::replace(&mut self.info, info.clone()),
+                found: info,
+                lost_at: lost.at,
+                reacquired_at: local_clock(),
+            });
+            self.inlet = inlet;
+            self.lost = None;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
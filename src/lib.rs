@@ -32,12 +32,43 @@ the main objects (`StreamInfo`, `StreamOutlet`, `StreamInlet`, `ContinuousResolv
 */
 
 use lsl_sys::*;
+use std::any;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::{From, TryFrom};
 use std::ffi;
 use std::fmt;
-use std::rc;
+use std::os::raw::c_ulong;
+use std::sync;
 use std::vec;
 
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+pub mod aio;
+pub mod barrier;
+pub mod bids;
+pub mod bridge;
+pub mod broadcast;
+pub mod buffered;
+pub mod calibration;
+pub mod channels;
+pub mod clock;
+pub mod clocksync;
+pub mod events;
+pub mod fragment;
+pub mod group;
+pub mod inlets;
+pub mod meta;
+pub mod playback;
+pub mod query;
+pub mod queue;
+pub mod sparse;
+#[cfg(feature = "futures")]
+pub mod stream;
+pub mod time;
+pub mod timebase;
+pub mod transforms;
+pub mod worker;
+mod xml;
+
 /// Constant to indicate that a stream has variable sampling rate.
 pub const IRREGULAR_RATE: f64 = 0.0;
 
@@ -58,8 +89,14 @@ operating systems (e.g., 32-bit UNIX).
 */
 pub const FOREVER: f64 = 32000000.0;
 
+/**
+The maximum number of streams that are returned by the one-shot resolve functions (e.g.,
+`resolve_streams()`); see their `_with_capacity()` variants for larger networks.
+*/
+pub const RESOLVE_CAPACITY: usize = 1024;
+
 /// Error type for all errors that can be returned by this library.
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Error {
     /// A bad argument was passed into a library function (e.g., negative number, string containing
     /// embedded zero bytes (which C libraries tend to not accept).
@@ -78,11 +115,45 @@ pub enum Error {
     /// An unknown error has happened. There are only very few calls where this can happen since no
     /// detailed error codes are available in those cases, and is very unlikely to occur.
     Unknown,
+    /// The channel format of a stream is not supported by the linked native library (e.g.,
+    /// `ChannelFormat::Int64` on 32-bit builds), or cannot be transmitted at all
+    /// (`ChannelFormat::Undefined`).
+    UnsupportedFormat,
+    /// A string-formatted channel did not hold valid UTF-8 and strict decoding was requested (see
+    /// `StreamInlet::set_string_decoding()`).
+    InvalidUtf8,
+    /// An I/O operation (e.g., reading a file) failed with the given kind of error.
+    Io(std::io::ErrorKind),
+    /// The requested value type is incompatible with the channel format of the stream (e.g.,
+    /// pulling `f32` values from a string-formatted stream); see
+    /// `StreamInlet::set_format_checking()`.
+    FormatMismatch,
+    /// Data of the wrong length was passed in, e.g., a sample whose number of values does not
+    /// match the channel count of the outlet, or a chunk with a differing number of time stamps.
+    LengthMismatch { expected: usize, actual: usize },
+    /// Input data (e.g., the contents of a file) could not be parsed; `line` is the 1-based line
+    /// number at which the problem was found.
+    Parse { line: usize, reason: &'static str },
+    /// A value in the meta-data could not be converted to the requested type (see
+    /// `XMLElement::child_value_as()`); `field` is the name (or path) of the value, and `text` is
+    /// its text in the meta-data.
+    InvalidValue { field: String, text: String },
+    /// An operation on the meta-data was applied to an element that does not exist (e.g., a
+    /// child that was not found), or did not take effect (see `XMLElement::try_set_name()`).
+    InvalidElement,
 }
 
 /// Result type alias for results with library-specific errors.
 type Result<T> = std::result::Result<T, Error>;
 
+// the header comment of stream declarations saved by `StreamInfo::save_xml()`, followed by the
+// version of the file format, which is incremented on incompatible changes
+const DECLARATION_HEADER: &str = "lsl stream declaration, format version";
+const DECLARATION_VERSION: u32 = 1;
+
+// the size of a time stamp, for estimates of data rates and buffer sizes
+const TIMESTAMP_BYTES: f64 = 8.0;
+
 /// Data format of a channel (each transmitted sample holds an array of channels).
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum ChannelFormat {
@@ -136,6 +207,58 @@ pub enum ProcessingOption {
     ALL = 1 | 2 | 4 | 8,
 }
 
+/// Decoding mode for string-typed channels pulled as `String` (see `StreamInlet`).
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum StringDecoding {
+    /// Replace invalid UTF-8 sequences by the Unicode replacement character (the default).
+    Lossy,
+    /// Fail with an `Error::InvalidUtf8` if a channel does not hold valid UTF-8.
+    Strict,
+}
+
+/**
+How the blocking pull calls of an inlet wait for data (see `StreamInlet::set_wait_strategy()`).
+
+A thread that blocks in a pull call is woken up by the operating system when data arrive, which
+adds a scheduling delay to the reaction time (on the order of tens of microseconds on an idle
+system, but up to milliseconds under load or with coarse timer resolution, as on some Windows
+systems). Spinning first, i.e., repeatedly checking for data without yielding the CPU, avoids this
+delay for data that arrive within the spin time, at the cost of keeping a CPU core fully busy for
+that time on every pull. The `measure_pull_latency` example compares the strategies on the
+system at hand.
+*/
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum WaitStrategy {
+    /// Block in the native library until data arrive (the default, which uses no CPU while
+    /// waiting).
+    Block,
+    /// Spin for up to the given time, in seconds, before blocking for the rest of the timeout
+    /// (for sub-millisecond reaction times, e.g., in closed-loop stimulation).
+    SpinThenBlock(f64),
+}
+
+/**
+Transmission granularity of a stream outlet (see `StreamOutlet::new()`).
+
+This determines when pushed data actually go out on the network:
+* With `PerPush`, each push operation yields one chunk, i.e., the `pushthrough` flag of the
+  `push_*_ex()` methods decides whether data are sent right away (`true`) or are held back and
+  sent along with the data of the next push (`false`).
+* With `Samples(n)`, data are re-aggregated into chunks of `n` samples, and this setting takes
+  precedence over the `pushthrough` flag of the individual push calls.
+
+For backwards compatibility, a plain `u32` can be used wherever a `ChunkSpec` is accepted, where
+0 means `PerPush` and any other value `n` means `Samples(n)`. Note that inlets can override the
+chunk granularity on their end (see the `max_chunklen` argument of `StreamInlet::new()`).
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ChunkSpec {
+    /// Each push operation yields one chunk (the default).
+    PerPush,
+    /// Data are transmitted in chunks of the given number of samples.
+    Samples(u32),
+}
+
 /**
 Protocol version number.
 - The major version is protocol_version() / 100;
@@ -168,6 +291,42 @@ pub fn library_info() -> String {
     unsafe { make_string(lsl_library_info()) }
 }
 
+/// Optional features of the linked native library, as reported by `lsl::capabilities()`.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Capabilities {
+    /// Whether streams with the channel format `ChannelFormat::Int64` can be transmitted.
+    pub int64: bool,
+    /// Whether the library can drop queued samples of an inlet (`lsl_inlet_flush()`).
+    pub inlet_flush: bool,
+    /// Whether outlets and inlets can be created with transport options
+    /// (`lsl_create_outlet_ex()` and `lsl_create_inlet_ex()`).
+    pub create_ex: bool,
+    /// Whether the library can communicate over IPv6 (subject to its configuration file).
+    pub ipv6: bool,
+}
+
+/**
+Query the optional features that are supported by the linked native library.
+
+Applications that need to run against different liblsl versions can use this to adapt at
+runtime. The capabilities are derived from `library_version()` and the build platform.
+
+**Note:** This crate links liblsl statically (there is no mode in which the library is loaded at
+runtime, e.g., via `dlopen()`), so all symbols of the bundled headers are always present, and
+their availability is not probed.
+*/
+pub fn capabilities() -> Capabilities {
+    let version = library_version();
+    Capabilities {
+        // 32-bit builds can't transmit int64 data (Windows builds can since liblsl 1.14, which the
+        // build of lsl-sys requires)
+        int64: cfg!(target_pointer_width = "64"),
+        inlet_flush: version >= 114,
+        create_ex: version >= 114,
+        ipv6: version >= 110,
+    }
+}
+
 /**
 Obtain a local system time stamp in seconds.
 
@@ -217,7 +376,7 @@ github repository). You can find various uses of the `StreamInfo` object in most
 #[derive(Debug)]
 pub struct StreamInfo {
     // internal fields
-    handle: rc::Rc<StreamInfoHandle>,
+    handle: sync::Arc<StreamInfoHandle>,
 }
 
 impl StreamInfo {
@@ -270,12 +429,147 @@ impl StreamInfo {
                 source_id.as_ptr(),
             );
             match handle.is_null() {
-                false => Ok(StreamInfo { handle: rc::Rc::new(StreamInfoHandle { handle }) }),
+                false => Ok(StreamInfo {
+                    handle: sync::Arc::new(StreamInfoHandle::new(handle)),
+                }),
                 true => Err(Error::ResourceCreation),
             }
         }
     }
 
+    // =================
+    // === Templates ===
+    // =================
+    // (declarations whose meta-data follow the XDF recommendations for their content type)
+
+    /**
+    Declare an EEG stream, with a channel of type "EEG" in microvolts for each of the given
+    labels (see `set_channels()`).
+
+    See `new()` for the other arguments; the channel format is `ChannelFormat::Float32`.
+    */
+    pub fn template_eeg(
+        stream_name: &str,
+        labels: &[&str],
+        nominal_srate: f64,
+        source_id: &str,
+    ) -> Result<StreamInfo> {
+        let count = labels.len() as u32;
+        let mut info = StreamInfo::new(
+            stream_name,
+            "EEG",
+            count,
+            nominal_srate,
+            ChannelFormat::Float32,
+            source_id,
+        )?;
+        let channels: vec::Vec<_> = labels
+            .iter()
+            .map(|label| channels::ChannelDescriptor {
+                chan_type: "EEG".to_string(),
+                ..channels::ChannelDescriptor::new(label, "microvolts")
+            })
+            .collect();
+        info.set_channels(&channels)?;
+        Ok(info)
+    }
+
+    /**
+    Declare a stream of event markers: a single string channel with an irregular rate, of type
+    "Markers".
+
+    See `new()` for the arguments.
+    */
+    pub fn template_markers(stream_name: &str, source_id: &str) -> Result<StreamInfo> {
+        let mut info = StreamInfo::new(
+            stream_name,
+            "Markers",
+            1,
+            IRREGULAR_RATE,
+            ChannelFormat::String,
+            source_id,
+        )?;
+        info.set_channels(&[channels::ChannelDescriptor {
+            label: "Marker".to_string(),
+            chan_type: "Marker".to_string(),
+            ..channels::ChannelDescriptor::default()
+        }])?;
+        Ok(info)
+    }
+
+    /**
+    Declare a stream of gaze positions on a screen, with the channels "ScreenX" and "ScreenY" in
+    pixels (for the average of both eyes).
+
+    See `new()` for the other arguments; the channel format is `ChannelFormat::Float32`.
+    */
+    pub fn template_gaze(
+        stream_name: &str,
+        nominal_srate: f64,
+        source_id: &str,
+    ) -> Result<StreamInfo> {
+        let mut info = StreamInfo::new(
+            stream_name,
+            "Gaze",
+            2,
+            nominal_srate,
+            ChannelFormat::Float32,
+            source_id,
+        )?;
+        let channels: vec::Vec<_> = ["ScreenX", "ScreenY"]
+            .iter()
+            .map(|label| channels::ChannelDescriptor {
+                chan_type: label.to_string(),
+                ..channels::ChannelDescriptor::new(label, "pixels")
+            })
+            .collect();
+        info.set_channels(&channels)?;
+        let mut channel = info.desc_mut().child("channels").child("channel");
+        while channel.is_valid() && !channel.empty() {
+            channel.append_child_value("eye", "both");
+            channel = channel.next_sibling_named("channel");
+        }
+        Ok(info)
+    }
+
+    /**
+    Declare an audio stream with the given number of channels of type "Audio", labeled "Mono",
+    "Left" and "Right", or "Channel1" and so on for more than two.
+
+    See `new()` for the other arguments; the channel format is `ChannelFormat::Float32`, with the
+    samples normalized to the range -1 to 1.
+    */
+    pub fn template_audio(
+        stream_name: &str,
+        channel_count: u32,
+        nominal_srate: f64,
+        source_id: &str,
+    ) -> Result<StreamInfo> {
+        let mut info = StreamInfo::new(
+            stream_name,
+            "Audio",
+            channel_count,
+            nominal_srate,
+            ChannelFormat::Float32,
+            source_id,
+        )?;
+        let label = |k: u32| match channel_count {
+            1 => "Mono".to_string(),
+            2 if k == 0 => "Left".to_string(),
+            2 => "Right".to_string(),
+            _ => format!("Channel{}", k + 1),
+        };
+        let channels: vec::Vec<_> = (0..channel_count)
+            .map(|k| channels::ChannelDescriptor {
+                chan_type: "Audio".to_string(),
+                range: Some((-1.0, 1.0)),
+                ..channels::ChannelDescriptor::new(&label(k), "normalized")
+            })
+            .collect();
+        info.set_channels(&channels)?;
+        Ok(info)
+    }
+
     // ========================
     // === Core Information ===
     // ========================
@@ -290,7 +584,7 @@ impl StreamInfo {
     experimenter).
     */
     pub fn stream_name(&self) -> String {
-        unsafe { make_string(lsl_get_name(self.handle.handle )) }
+        unsafe { make_string(lsl_get_name(*self.handle.lock())) }
     }
 
     /**
@@ -303,7 +597,7 @@ impl StreamInfo {
     search for: XDF meta-data).
     */
     pub fn stream_type(&self) -> String {
-        unsafe { make_string(lsl_get_type(self.handle.handle )) }
+        unsafe { make_string(lsl_get_type(*self.handle.lock())) }
     }
 
     /**
@@ -311,7 +605,7 @@ impl StreamInfo {
     A stream has at least one channel; the channel count stays constant for all samples.
     */
     pub fn channel_count(&self) -> i32 {
-        unsafe { lsl_get_channel_count(self.handle.handle ) }
+        unsafe { lsl_get_channel_count(*self.handle.lock()) }
     }
 
     /**
@@ -325,7 +619,7 @@ impl StreamInfo {
     of the device.
     */
     pub fn nominal_srate(&self) -> f64 {
-        unsafe { lsl_get_nominal_srate(self.handle.handle) }
+        unsafe { lsl_get_nominal_srate(*self.handle.lock()) }
     }
 
     /**
@@ -334,7 +628,7 @@ impl StreamInfo {
     time-synched streams each with its own format.
     */
     pub fn channel_format(&self) -> ChannelFormat {
-        unsafe { ChannelFormat::from_native(lsl_get_channel_format(self.handle.handle)) }
+        unsafe { ChannelFormat::from_native(lsl_get_channel_format(*self.handle.lock())) }
     }
 
     /** Unique identifier of the stream's source, if available.
@@ -343,7 +637,66 @@ impl StreamInfo {
     automatically once it is back online.
     */
     pub fn source_id(&self) -> String {
-        unsafe { make_string(lsl_get_source_id(self.handle.handle)) }
+        unsafe { make_string(lsl_get_source_id(*self.handle.lock())) }
+    }
+
+    // ==================================
+    // === Modifying Core Information ===
+    // ==================================
+    // (the native library has no setters for these fields, so the info is rebuilt from its XML
+    // declaration, which keeps the remaining fields, such as the UID, and the meta-data)
+
+    /**
+    Change the name of the stream (e.g., before a relay re-publishes a resolved stream).
+
+    Returns an `Error::BadArgument` if the name is empty. Note that `XMLElement`s that were
+    obtained from the info before (e.g., via `desc()`) refer to the previous declaration.
+    */
+    pub fn set_name(&mut self, name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(Error::BadArgument);
+        }
+        self.set_core_field("name", name)
+    }
+
+    /// Change the content type of the stream (see `set_name()`).
+    pub fn set_type(&mut self, type_: &str) -> Result<()> {
+        self.set_core_field("type", type_)
+    }
+
+    /**
+    Change the nominal sampling rate of the stream (see `set_name()`), e.g., for a replay at a
+    different speed.
+
+    Returns an `Error::BadArgument` if the rate is negative or not finite.
+    */
+    pub fn set_nominal_srate(&mut self, srate: f64) -> Result<()> {
+        if !(srate >= 0.0 && srate.is_finite()) {
+            return Err(Error::BadArgument);
+        }
+        self.set_core_field("nominal_srate", &srate.to_string())
+    }
+
+    /// Change the source id of the stream (see `set_name()`).
+    pub fn set_source_id(&mut self, source_id: &str) -> Result<()> {
+        self.set_core_field("source_id", source_id)
+    }
+
+    // Replace the value of a field in the XML declaration, and rebuild the info from it.
+    fn set_core_field(&mut self, tag: &str, value: &str) -> Result<()> {
+        let xml = self.to_xml()?;
+        // (fields without a value may be written as self-closing elements, which are replaced
+        // as a whole)
+        let field = xml::root_fields(&xml)?.remove(tag);
+        let range = field.ok_or(Error::ResourceCreation)?.range;
+        let escaped = value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        let field = format!("<{}>{}</{}>", tag, escaped, tag);
+        let xml = format!("{}{}{}", &xml[..range.start], field, &xml[range.end..]);
+        *self = StreamInfo::from_xml(&xml)?;
+        Ok(())
     }
 
     // ======================================
@@ -355,7 +708,7 @@ impl StreamInfo {
     Protocol version used to deliver the stream. Formatted like `lsl::protocol_version()`.
     */
     pub fn version(&self) -> i32 {
-        unsafe { lsl_get_version(self.handle.handle) }
+        unsafe { lsl_get_version(*self.handle.lock()) }
     }
 
     /**
@@ -364,7 +717,7 @@ impl StreamInfo {
     (as determined via `lsl::local_clock()` on the providing machine).
     */
     pub fn created_at(&self) -> f64 {
-        unsafe { lsl_get_created_at(self.handle.handle) }
+        unsafe { lsl_get_created_at(*self.handle.lock()) }
     }
 
     /**
@@ -373,7 +726,7 @@ impl StreamInfo {
     across multiple instantiations of the same outlet (e.g., after a re-start).
     */
     pub fn uid(&self) -> String {
-        unsafe { make_string(lsl_get_uid(self.handle.handle)) }
+        unsafe { make_string(lsl_get_uid(*self.handle.lock())) }
     }
 
     /**
@@ -385,14 +738,14 @@ impl StreamInfo {
     wiki).
     */
     pub fn session_id(&self) -> String {
-        unsafe { make_string(lsl_get_session_id(self.handle.handle)) }
+        unsafe { make_string(lsl_get_session_id(*self.handle.lock())) }
     }
 
     /**
     Hostname of the providing machine.
     */
     pub fn hostname(&self) -> String {
-        unsafe { make_string(lsl_get_hostname(self.handle.handle)) }
+        unsafe { make_string(lsl_get_hostname(*self.handle.lock())) }
     }
 
     // ========================
@@ -411,11 +764,14 @@ impl StreamInfo {
     **Important:** if you use a stream content type for which meta-data recommendations exist,
     please try to lay out your meta-data in agreement with these recommendations for compatibility
     with other applications.
+
+    The element is meant for reading the meta-data (e.g., of a shared info); use `desc_mut()` to
+    modify them.
     */
-    pub fn desc(&mut self) -> XMLElement {
+    pub fn desc(&self) -> XMLElement {
         unsafe {
             XMLElement {
-                cursor: lsl_get_desc(self.handle.handle),
+                cursor: lsl_get_desc(*self.handle.lock()),
                 // keep a shared ref of the underlying native handle since the xml element or
                 // elements obtained from it may outlive the StreamInfo object
                 doc: self.handle.clone()
@@ -423,18 +779,269 @@ impl StreamInfo {
         }
     }
 
+    /**
+    Access the extended description of the stream for modification (see `desc()`), e.g., to
+    declare the channels or the recording device.
+    */
+    pub fn desc_mut(&mut self) -> XMLElement {
+        self.desc()
+    }
+
+    /**
+    Set an attribute of an element of the meta-data (see `XMLElement::attribute()`), replacing
+    its previous value, if any.
+
+    The native library has no functions for attributes, so the info is rebuilt from its XML form
+    with the changed attribute (like `set_name()`). This invalidates the elements that were
+    obtained from the info before: they still refer to the previous meta-data, and changes made
+    through them are lost. The element in the rebuilt info is returned.
+
+    Returns an `Error::BadArgument` if the element does not belong to this info or the name is not
+    a valid attribute name, or an `Error::InvalidElement` if the element is a text body or empty.
+    */
+    pub fn set_attribute(
+        &mut self,
+        element: &XMLElement,
+        name: &str,
+        value: &str,
+    ) -> Result<XMLElement> {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == ':');
+        if !sync::Arc::ptr_eq(&element.doc, &self.handle) || !valid_name {
+            return Err(Error::BadArgument);
+        }
+        let elements = element.document_elements();
+        let index = elements
+            .iter()
+            .position(|&e| e == element.cursor)
+            .ok_or(Error::InvalidElement)?;
+        let xml = xml::set_attribute(&self.to_xml()?, index, &element.name(), name, value)
+            .ok_or(Error::InvalidElement)?;
+        *self = StreamInfo::from_xml(&xml)?;
+        let desc = self.desc();
+        let cursor = desc.document_elements()[index];
+        Ok(XMLElement {
+            cursor,
+            doc: desc.doc,
+        })
+    }
+
+    /**
+    Check the meta-data of the stream against the XDF recommendations for its content type (see
+    `lsl::meta`), and return the problems that were found (e.g., channels without a label, or
+    misnamed elements).
+
+    An empty result does not guarantee full conformance, since only the most common problems are
+    checked. The info must have been fully retrieved (e.g., via `StreamInlet::info()`).
+    */
+    pub fn validate_xdf_metadata(&self) -> vec::Vec<meta::MetadataIssue> {
+        meta::validate(self)
+    }
+
+    /// The description of the recording device in the meta-data (see `lsl::meta`).
+    pub fn acquisition(&mut self) -> meta::Acquisition {
+        meta::Acquisition::new(self.desc_mut())
+    }
+
+    /// The description of the EEG cap in the meta-data (see `lsl::meta`).
+    pub fn cap(&mut self) -> meta::Cap {
+        meta::Cap::new(self.desc_mut())
+    }
+
+    /// The description of the subject in the meta-data (see `lsl::meta`).
+    pub fn subject(&mut self) -> meta::Subject {
+        meta::Subject::new(self.desc_mut())
+    }
+
+    /**
+    Compare the declaration of the stream with another one, and return the fields in which they
+    differ (see `lsl::meta::Difference`): the core fields (e.g., the name or the sampling rate),
+    and the elements of the meta-data that have no child elements (e.g., the channel labels).
+
+    The fields that identify a particular instance of the stream (e.g., its UID) are not
+    compared, as for `==`. The infos must have been fully retrieved (e.g., via
+    `StreamInlet::info()`).
+    */
+    pub fn diff(&self, other: &StreamInfo) -> vec::Vec<meta::Difference> {
+        meta::diff(self, other)
+    }
+
+    /**
+    Merge the meta-data of another info into this one (e.g., to complete the info of a resolved
+    stream with the declarations of a device template).
+
+    Elements of the other info that this info does not have are copied, and elements that both
+    have (matched by their path, see `lsl::meta::Difference`) are merged recursively. Where both
+    have an element without child elements (e.g., a channel label), or where only one of them
+    has child elements, the policy decides which one is kept.
+    */
+    pub fn merge_desc(&mut self, other: &StreamInfo, policy: meta::MergePolicy) {
+        meta::merge(&mut self.desc_mut(), &other.desc(), policy)
+    }
+
+    /// The description of the recording session in the meta-data (see `lsl::meta`).
+    pub fn session(&mut self) -> meta::Session {
+        meta::Session::new(self.desc_mut())
+    }
+
+    /**
+    Declare the channels of the stream in its meta-data, following the usual
+    `<channels><channel>` layout of the XDF meta-data recommendations (see
+    `lsl::channels::ChannelDescriptor`).
+
+    Previously declared channels are replaced. Returns an `Error::LengthMismatch` if the number of
+    descriptors differs from the channel count.
+    */
+    pub fn set_channels(&mut self, channels: &[channels::ChannelDescriptor]) -> Result<()> {
+        if channels.len() != self.channel_count() as usize {
+            return Err(Error::LengthMismatch {
+                expected: self.channel_count() as usize,
+                actual: channels.len(),
+            });
+        }
+        let mut desc = self.desc_mut();
+        desc.remove_child_named("channels");
+        let mut parent = desc.append_child("channels");
+        for descriptor in channels {
+            let mut channel = parent.append_child("channel");
+            let fields = [
+                ("label", &descriptor.label),
+                ("unit", &descriptor.unit),
+                ("type", &descriptor.chan_type),
+            ];
+            for (name, value) in fields.iter().filter(|(_, value)| !value.is_empty()) {
+                channel.append_child_value(name, value);
+            }
+            if let Some((min, max)) = descriptor.range {
+                channel
+                    .append_child("range")
+                    .append_child_value("min", &min.to_string())
+                    .append_child_value("max", &max.to_string());
+            }
+            if let Some(location) = descriptor.location {
+                channels::write_location(&mut channel, location);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Read the channels declared in the meta-data of the stream (see `set_channels()`).
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`). Fields that are not
+    declared are left empty; if the stream declares no channels at all, the result is empty.
+    */
+    pub fn channels(&self) -> vec::Vec<channels::ChannelDescriptor> {
+        let mut result = vec![];
+        let mut channel = self.desc().child("channels").child("channel");
+        while channel.is_valid() && !channel.empty() {
+            let range = channel.child("range");
+            let min = range.child_value_named("min").trim().parse::<f64>();
+            let max = range.child_value_named("max").trim().parse::<f64>();
+            result.push(channels::ChannelDescriptor {
+                label: channel.child_value_named("label"),
+                unit: channel.child_value_named("unit"),
+                chan_type: channel.child_value_named("type"),
+                range: match (min, max) {
+                    (Ok(min), Ok(max)) if min <= max => Some((min, max)),
+                    _ => None,
+                },
+                location: channels::read_location(&channel),
+            });
+            channel = channel.next_sibling_named("channel");
+        }
+        result
+    }
+
+    /**
+    Obtain the network details of the stream: the host's addresses, the ports of the data and
+    service endpoints, and the protocol version (e.g., for diagnostics or to set up firewall
+    rules). The addresses and ports are only known for a stream info that was obtained from the
+    network (i.e., from a resolver or an inlet).
+    */
+    pub fn network_info(&self) -> Result<NetworkInfo> {
+        // (the native library has no getters for these fields, so they are read from the XML
+        // declaration)
+        let xml = self.to_xml()?;
+        let fields = xml::root_fields(&xml)?;
+        let field = |tag: &str| fields.get(tag).map(|field| field.text.as_str());
+        let port = |tag: &str| field(tag)?.parse().ok().filter(|&port: &u16| port != 0);
+        Ok(NetworkInfo {
+            hostname: self.hostname(),
+            protocol_version: self.version(),
+            v4address: field("v4address").and_then(|a| a.parse().ok()),
+            v4data_port: port("v4data_port"),
+            v4service_port: port("v4service_port"),
+            v6address: field("v6address").and_then(|a| a.parse().ok()),
+            v6data_port: port("v6data_port"),
+            v6service_port: port("v6service_port"),
+        })
+    }
+
+    /**
+    The network addresses (IPv4 and/or IPv6) of the host that provides the stream (see
+    `network_info()`), or an empty vector if they are not known.
+    */
+    pub fn addresses(&self) -> vec::Vec<std::net::IpAddr> {
+        match self.network_info() {
+            Ok(net) => {
+                let v4 = net.v4address.map(std::net::IpAddr::V4);
+                let v6 = net.v6address.map(std::net::IpAddr::V6);
+                v4.into_iter().chain(v6).collect()
+            }
+            Err(_) => vec![],
+        }
+    }
+
     /**
     Test whether the stream information matches the given query string.
     The query is evaluated using the same rules that govern `lsl::resolve_bypred()`.
     */
-    pub fn matches_query(&self, query: &str) -> bool {
-        if let Ok(query) = ffi::CString::new(query) {
-            unsafe { lsl_stream_info_matches_query(self.handle.handle, query.as_ptr()) != 0 }
+    pub fn matches_query(&self, query: impl AsRef<str>) -> bool {
+        if let Ok(query) = ffi::CString::new(query.as_ref()) {
+            unsafe { lsl_stream_info_matches_query(*self.handle.lock(), query.as_ptr()) != 0 }
         } else {
             false
         }
     }
 
+    /**
+    A hash of the content of the info, i.e., of the fields that are compared by `==`: the core
+    fields (name, type, channel count, sampling rate, channel format and source id) and the
+    meta-data, but not the fields that identify a particular instance of the stream (e.g., its
+    UID or hostname).
+
+    Equal infos have the same fingerprint, so it can serve as a key in caches (e.g., of resolved
+    streams). The hash is not meant to be stored, since it may differ between builds of the
+    program.
+    */
+    pub fn fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let (name, type_, channel_count, srate, format, source_id, desc) = self.content();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (name, type_, channel_count, srate.to_bits()).hash(&mut hasher);
+        (format as i32, source_id, desc).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // The fields that are compared by `==`, with the meta-data as XML.
+    fn content(&self) -> (String, String, i32, f64, ChannelFormat, String, String) {
+        let xml = self.to_xml().unwrap_or_default();
+        let start = xml.find("<desc").unwrap_or(xml.len());
+        let end = xml.rfind("</info>").unwrap_or(xml.len()).max(start);
+        (
+            self.stream_name(),
+            self.stream_type(),
+            self.channel_count(),
+            self.nominal_srate(),
+            self.channel_format(),
+            self.source_id(),
+            xml[start..end].trim().to_string(),
+        )
+    }
+
     // ===============================
     // === Miscellaneous Functions ===
     // ===============================
@@ -454,7 +1061,7 @@ impl StreamInfo {
     */
     pub fn to_xml(&self) -> Result<String> {
         unsafe {
-            let tmpstr = lsl_get_xml(self.handle.handle);
+            let tmpstr = lsl_get_xml(*self.handle.lock());
             if tmpstr.is_null() {
                 return Err(Error::Internal);
             }
@@ -464,14 +1071,88 @@ impl StreamInfo {
         }
     }
 
+    /**
+    Retrieve the entire streaminfo in XML format (see `to_xml()`), indented for human readers
+    (e.g., for logging, or for diffing the declarations of two recordings).
+
+    Each element is on its own line, nested by `indent` spaces per level, except that elements
+    which only contain text are kept on one line (e.g., `<label>C3</label>`). The result can be
+    passed to `from_xml()`.
+    */
+    pub fn to_xml_pretty(&self, indent: usize) -> Result<String> {
+        Ok(xml::pretty(&self.to_xml()?, indent))
+    }
+
     /// Number of bytes occupied by a channel (0 for string-typed channels).
     pub fn channel_bytes(&self) -> i32 {
-        unsafe { lsl_get_channel_bytes(self.handle.handle) }
+        unsafe { lsl_get_channel_bytes(*self.handle.lock()) }
     }
 
     /// Number of bytes occupied by a sample (0 for string-typed channels).
     pub fn sample_bytes(&self) -> i32 {
-        unsafe { lsl_get_sample_bytes(self.handle.handle) }
+        unsafe { lsl_get_sample_bytes(*self.handle.lock()) }
+    }
+
+    /**
+    The data rate of the stream, in bytes per second, as estimated from its nominal sampling
+    rate, channel count and channel format (with a time stamp for each sample, but without the
+    overhead of the network protocol).
+
+    Returns `None` for streams with an irregular rate or string-formatted channels, whose data
+    rate is not known up-front.
+    */
+    pub fn estimated_bandwidth(&self) -> Option<f64> {
+        let srate = self.nominal_srate();
+        if srate == IRREGULAR_RATE || self.channel_format() == ChannelFormat::String {
+            return None;
+        }
+        Some(srate * (self.sample_bytes() as f64 + TIMESTAMP_BYTES))
+    }
+
+    /**
+    Translate a buffer duration, in seconds, into the units of the `max_buflen` argument of
+    `StreamInlet::new()` (and the `max_buffered` argument of `StreamOutlet::new()`), which are
+    seconds for streams with a nominal sampling rate, and hundreds of samples for streams with an
+    irregular rate.
+
+    Arguments:
+    * `seconds`: The duration of the data that the buffer should be able to hold.
+    * `irregular_rate`: The expected number of samples per second, if the stream has an irregular
+      rate (ignored otherwise).
+
+    The result is rounded up, and is at least 1.
+    */
+    pub fn buflen_for_duration(&self, seconds: f64, irregular_rate: f64) -> i32 {
+        duration_to_buflen(seconds, self.nominal_srate(), irregular_rate)
+    }
+
+    /**
+    The memory, in bytes, that a buffer of the given length (in the units of `max_buflen`, see
+    `buflen_for_duration()`) takes up when it is full, with a time stamp for each sample.
+
+    Returns `None` for string-formatted streams, whose samples have no fixed size.
+    */
+    pub fn estimated_buffer_bytes(&self, max_buflen: i32) -> Option<u64> {
+        if self.channel_format() == ChannelFormat::String {
+            return None;
+        }
+        let samples = match self.nominal_srate() {
+            srate if srate == IRREGULAR_RATE => max_buflen as f64 * 100.0,
+            srate => (max_buflen as f64 * srate).ceil(),
+        };
+        Some((samples * (self.sample_bytes() as f64 + TIMESTAMP_BYTES)) as u64)
+    }
+
+    /**
+    The layout of the samples of this stream in raw byte buffers (see `RawLayout`).
+
+    Returns an error for string-formatted streams, whose samples have no fixed layout.
+    */
+    pub fn raw_layout(&self) -> Result<RawLayout> {
+        let layout = RawLayout::new(self.channel_format(), self.channel_count() as usize)?;
+        debug_assert_eq!(layout.sample_bytes(), self.sample_bytes() as usize);
+        debug_assert_eq!(layout.value_bytes, self.channel_bytes() as usize);
+        Ok(layout)
     }
 
     /// Construct a blank `StreamInfo`.
@@ -480,20 +1161,106 @@ impl StreamInfo {
     }
 
     /**
-    Create a `StreamInfo` from an XML string.
+    Create a `StreamInfo` from an XML string (in the format produced by `to_xml()`).
+
+    The document is checked before it is passed to the native library, and problems are reported
+    as an `Error::Parse` with the line at which they were found: the document must be well-formed
+    XML with an `<info>` root element, which must have the `<name>`, `<channel_count>`,
+    `<nominal_srate>` and `<channel_format>` fields, with a non-negative integer, a non-negative
+    number and a valid format name (e.g., "float32"), respectively. Returns an
+    `Error::BadArgument` if the string contains a zero byte.
     */
     pub fn from_xml(xml: &str) -> Result<StreamInfo> {
+        xml::check(xml)?;
+        StreamInfo::from_checked_xml(xml)
+    }
+
+    /**
+    Save the declaration of the stream (including its meta-data) to an XML file, e.g., so that a
+    calibration tool can reuse it in a later session (see `load_xml()`).
+
+    The file starts with a comment that records the version of the file format. Returns an
+    `Error::Io` if the file cannot be written.
+    */
+    pub fn save_xml(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let xml = self.to_xml()?;
+        // (the document may already start with an XML declaration)
+        let body = match xml.trim_start().starts_with("<?xml") {
+            true => &xml[xml.find("?>").map_or(0, |at| at + 2)..],
+            false => &xml[..],
+        };
+        let contents = format!(
+            "<?xml version=\"1.0\"?>\n<!-- {} {} -->\n{}\n",
+            DECLARATION_HEADER,
+            DECLARATION_VERSION,
+            body.trim()
+        );
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /**
+    Load a stream declaration from an XML file, as written by `save_xml()` (or in the format
+    produced by `to_xml()`, without the header).
+
+    Returns an `Error::Io` if the file cannot be read, and an `Error::Parse` (with the line of
+    the offending element) if it is not a valid stream declaration, e.g., if the name is missing,
+    if the channel count, sampling rate or channel format is invalid, or if the file was written
+    in a newer version of the file format.
+    */
+    pub fn load_xml(path: impl AsRef<std::path::Path>) -> Result<StreamInfo> {
+        let xml = std::fs::read_to_string(path)?;
+        StreamInfo::parse_declaration(&xml)
+    }
+
+    // === internal methods ===
+
+    // Create a `StreamInfo` from an XML document that has passed `xml::check()`.
+    fn from_checked_xml(xml: &str) -> Result<StreamInfo> {
         let xml = ffi::CString::new(xml)?;
         unsafe {
             let handle = lsl_streaminfo_from_xml(xml.as_ptr());
             match handle.is_null() {
-                false => Ok(StreamInfo { handle: rc::Rc::new(StreamInfoHandle { handle }) }),
+                false => Ok(StreamInfo {
+                    handle: sync::Arc::new(StreamInfoHandle::new(handle)),
+                }),
                 true => Err(Error::ResourceCreation),
             }
         }
     }
 
-    // === internal methods ===
+    // Create a `StreamInfo` from an XML document, and check that it is a valid declaration.
+    fn parse_declaration(xml: &str) -> Result<StreamInfo> {
+        let line_at = |at: usize| xml[..at].matches('\n').count() + 1;
+        if let Some(at) = xml.find(DECLARATION_HEADER) {
+            let rest = &xml[at + DECLARATION_HEADER.len()..];
+            let version = rest.split_whitespace().next().map(str::parse::<u32>);
+            if !matches!(version, Some(Ok(version)) if version <= DECLARATION_VERSION) {
+                return Err(Error::Parse {
+                    line: line_at(at),
+                    reason: "unsupported file format version",
+                });
+            }
+        }
+        let lines = xml::check(xml)?;
+        let parse_error = |field: &str, reason| Error::Parse {
+            line: lines.of(field),
+            reason,
+        };
+        let info = StreamInfo::from_checked_xml(xml)
+            .map_err(|_| parse_error("info", "malformed stream declaration"))?;
+        // (an outlet additionally requires a name, channels and a format)
+        if info.stream_name().is_empty() {
+            return Err(parse_error("name", "missing stream name"));
+        }
+        if info.channel_count() <= 0 {
+            return Err(parse_error("channel_count", "invalid channel count"));
+        }
+        if info.channel_format() == ChannelFormat::Undefined {
+            return Err(parse_error("channel_format", "invalid channel format"));
+        }
+        Ok(info)
+    }
 
     /*
     Create a `StreamInfo` from a native handle.
@@ -506,24 +1273,28 @@ impl StreamInfo {
             !handle.is_null(),
             "Attempted to create a StreamInfo from a NULL handle."
         );
-        StreamInfo { handle: rc::Rc::new(StreamInfoHandle { handle } ) }
+        StreamInfo {
+            handle: sync::Arc::new(StreamInfoHandle::new(handle)),
+        }
     }
 
-    // Get the native implementation handle.
-    fn native_handle(&self) -> lsl_streaminfo {
-        self.handle.handle
+    // Get the native implementation handle (locked while the returned guard is alive).
+    fn native_handle(&self) -> sync::MutexGuard<'_, lsl_streaminfo> {
+        self.handle.lock()
     }
 }
 
 impl Clone for StreamInfo {
     fn clone(&self) -> StreamInfo {
         unsafe {
-            let handle = lsl_copy_streaminfo(self.handle.handle);
+            let handle = lsl_copy_streaminfo(*self.handle.lock());
             assert!(
                 !handle.is_null(),
                 "Failed to clone native lsl_streaminfo object."
             );
-            StreamInfo { handle: rc::Rc::new(StreamInfoHandle { handle }) }
+            StreamInfo {
+                handle: sync::Arc::new(StreamInfoHandle::new(handle)),
+            }
         }
     }
 }
@@ -541,62 +1312,513 @@ impl fmt::Display for StreamInfo {
     }
 }
 
-// =======================
-// ==== Stream Outlet ====
-// =======================
-
 /**
-A stream outlet.
-Outlets are used to make streaming data (and the meta-data) available on the lab network.
+With the `serde` feature, a `StreamInfo` is serialized as its XML declaration (as returned by
+`to_xml()`), which is a string in formats such as JSON or TOML. This keeps the full declaration,
+including the meta-data and the stream's identity (e.g., its UID), so that it can be stored in
+configuration files or in the manifest of a recording, and turned back into a `StreamInfo` (e.g.,
+to open an outlet) via deserialization.
+*/
+#[cfg(feature = "serde")]
+impl serde::Serialize for StreamInfo {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let xml = self.to_xml().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&xml)
+    }
+}
 
-The actual sample-pushing functionality is provided via the `Pushable` and `ExPushable` traits
-below.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StreamInfo {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let xml = <String as serde::Deserialize>::deserialize(deserializer)?;
+        StreamInfo::from_xml(&xml).map_err(serde::de::Error::custom)
+    }
+}
 
-**Examples:** the `send_*.rs` examples (found in the crate's github repository) illustrate the use
-of `StreamOutlet`.
+/**
+Two infos are equal if they declare the same stream content, i.e., if their core fields (name,
+type, channel count, sampling rate, channel format and source id) and their meta-data are equal.
+The fields that identify a particular instance of the stream (e.g., the UID, hostname or creation
+time) are not compared, so an info that was resolved from the network equals the info with which
+its outlet was created (if the full info was retrieved, e.g., via `StreamInlet::info()`).
 */
-#[derive(Debug)]
-pub struct StreamOutlet {
-    // internal fields used by the Rust wrapper
-    handle: lsl_outlet,
-    channel_count: usize,
-    nominal_rate: f64,
+impl PartialEq for StreamInfo {
+    fn eq(&self, other: &StreamInfo) -> bool {
+        self.content() == other.content()
+    }
 }
 
-impl StreamOutlet {
-    /**
-    Establish a new stream outlet. This makes the stream discoverable.
+/// The network details of a stream, as returned by `StreamInfo::network_info()`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct NetworkInfo {
+    /// The name of the host that provides the stream.
+    pub hostname: String,
+    /// The protocol version used to deliver the stream (see `StreamInfo::version()`).
+    pub protocol_version: i32,
+    /// The IPv4 address of the host, if known.
+    pub v4address: Option<std::net::Ipv4Addr>,
+    /// The TCP port of the IPv4 data endpoint, if known.
+    pub v4data_port: Option<u16>,
+    /// The port of the IPv4 service endpoint (which answers queries about the stream), if known.
+    pub v4service_port: Option<u16>,
+    /// The IPv6 address of the host, if known.
+    pub v6address: Option<std::net::Ipv6Addr>,
+    /// The TCP port of the IPv6 data endpoint, if known.
+    pub v6data_port: Option<u16>,
+    /// The port of the IPv6 service endpoint, if known.
+    pub v6service_port: Option<u16>,
+}
 
-    Arguments:
-    * `info`: The stream information to use for creating this stream. Stays constant over the
-       lifetime of the outlet.
-    * `chunk_size`: The desired chunk granularity (in samples) for transmission.
-       If specified as 0, each push operation yields one chunk. Inlets can override this setting.
-    * `max_buffered`: The maximum amount of data to buffer (in seconds if there is a
+// ======================
+// ==== Sample Types ====
+// ======================
+
+/**
+A numeric value type that can be pushed into outlets and pulled from inlets.
+
+This trait is implemented for all scalar types that LSL supports natively (`f32`, `f64`, `i8`,
+`i16`, `i32` and `i64`), and cannot be implemented for other types. It allows to write generic
+code over these types, for instance:
+
+```no_run
+use lsl::{LslSample, Pullable, StreamInlet};
+
+fn next_value<T: LslSample>(inlet: &StreamInlet) -> Result<Option<T>, lsl::Error> {
+    let (sample, ts) = inlet.pull_sample(lsl::FOREVER)?;
+    Ok(if ts != 0.0 { sample.first().copied() } else { None })
+}
+```
+*/
+pub trait LslSample: sealed::Native + Copy + From<i8> + Send + Sync + 'static {
+    /// The channel format that natively holds values of this type.
+    const CHANNEL_FORMAT: ChannelFormat;
+}
+
+// the native functions backing each sample type; private so that LslSample can't be implemented
+// outside of this crate
+mod sealed {
+    use super::*;
+
+    pub trait Native: Sized {
+        const PUSH_SAMPLE: NativePushFunction<Self>;
+        const PULL_SAMPLE: NativePullFunction<Self>;
+        const PULL_CHUNK: NativePullChunkFunction<Self>;
+    }
+}
+
+macro_rules! impl_lsl_sample {
+    ($type:ty, $format:ident, $push:ident, $pull:ident, $pull_chunk:ident) => {
+        impl sealed::Native for $type {
+            const PUSH_SAMPLE: NativePushFunction<Self> = $push;
+            const PULL_SAMPLE: NativePullFunction<Self> = $pull;
+            const PULL_CHUNK: NativePullChunkFunction<Self> = $pull_chunk;
+        }
+
+        impl LslSample for $type {
+            const CHANNEL_FORMAT: ChannelFormat = ChannelFormat::$format;
+        }
+    };
+}
+
+impl_lsl_sample!(
+    f32,
+    Float32,
+    lsl_push_sample_ftp,
+    lsl_pull_sample_f,
+    lsl_pull_chunk_f
+);
+impl_lsl_sample!(
+    f64,
+    Double64,
+    lsl_push_sample_dtp,
+    lsl_pull_sample_d,
+    lsl_pull_chunk_d
+);
+impl_lsl_sample!(
+    i8,
+    Int8,
+    lsl_push_sample_ctp,
+    lsl_pull_sample_c,
+    lsl_pull_chunk_c
+);
+impl_lsl_sample!(
+    i16,
+    Int16,
+    lsl_push_sample_stp,
+    lsl_pull_sample_s,
+    lsl_pull_chunk_s
+);
+impl_lsl_sample!(
+    i32,
+    Int32,
+    lsl_push_sample_itp,
+    lsl_pull_sample_i,
+    lsl_pull_chunk_i
+);
+impl_lsl_sample!(
+    i64,
+    Int64,
+    lsl_push_sample_ltp,
+    lsl_pull_sample_l,
+    lsl_pull_chunk_l
+);
+
+/**
+The layout of a sample in the untyped byte buffers of `StreamOutlet::push_sample_raw()` and
+`StreamInlet::pull_sample_raw()`, for driver code that already holds its data in wire form.
+
+The guarantees are:
+* The values of the channels are stored back to back, in channel order and without padding, so
+  that channel `k` occupies the bytes `channel_range(k)`.
+* Each value is stored in the host's native byte order (see `RawLayout::is_little_endian()`).
+  The native library negotiates the byte order with the other side of the connection and converts
+  the data if necessary, so raw buffers never need to be byte-swapped by the application.
+* Integer values are two's-complement, floating-point values are IEEE 754.
+
+String-formatted streams have no fixed sample layout and therefore cannot be used with the raw
+APIs.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct RawLayout {
+    /// The channel format of the stream.
+    pub channel_format: ChannelFormat,
+    /// The number of channels of the stream.
+    pub channel_count: usize,
+    /// The size of one channel value, in bytes.
+    pub value_bytes: usize,
+}
+
+impl RawLayout {
+    /**
+    Describe the raw layout of samples of the given format and channel count.
+
+    Returns an `Error::FormatMismatch` for string-formatted streams and an
+    `Error::UnsupportedFormat` for `ChannelFormat::Undefined`.
+    */
+    pub fn new(channel_format: ChannelFormat, channel_count: usize) -> Result<RawLayout> {
+        match channel_format.value_bytes() {
+            Some(value_bytes) => Ok(RawLayout {
+                channel_format,
+                channel_count,
+                value_bytes,
+            }),
+            None if channel_format == ChannelFormat::String => Err(Error::FormatMismatch),
+            None => Err(Error::UnsupportedFormat),
+        }
+    }
+
+    /// The size of one sample, in bytes.
+    pub fn sample_bytes(&self) -> usize {
+        self.value_bytes * self.channel_count
+    }
+
+    /// The byte range of the given channel within a sample.
+    pub fn channel_range(&self, channel: usize) -> std::ops::Range<usize> {
+        channel * self.value_bytes..(channel + 1) * self.value_bytes
+    }
+
+    /// Whether values are stored in little-endian byte order (which is the host's byte order).
+    pub fn is_little_endian() -> bool {
+        cfg!(target_endian = "little")
+    }
+
+    // Check that a buffer holds exactly one sample.
+    fn check_len(&self, len: usize) -> Result<()> {
+        match len == self.sample_bytes() {
+            true => Ok(()),
+            false => Err(Error::LengthMismatch {
+                expected: self.sample_bytes(),
+                actual: len,
+            }),
+        }
+    }
+}
+
+/**
+The channel layout of a struct that is transmitted as a multi-channel sample (one channel per
+field).
+
+This is normally implemented via `#[derive(LslPush)]`, which requires the `derive` feature, and
+which also implements pushing such structs into a `StreamOutlet`:
+
+```ignore
+use lsl::{LslPush, Pushable, SampleLayout};
+
+#[derive(LslPush)]
+struct Accelerometer {
+    #[lsl(label = "AccX")]
+    x: f32,
+    #[lsl(label = "AccY")]
+    y: f32,
+    #[lsl(label = "AccZ")]
+    z: f32,
+}
+
+fn main() -> Result<(), lsl::Error> {
+    let info = Accelerometer::stream_info("MyIMU", "Accelerometer", 100.0, "imu-1234")?;
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360)?;
+    outlet.push_sample(&Accelerometer { x: 0.0, y: 0.0, z: 9.81 })?;
+    Ok(())
+}
+```
+*/
+pub trait SampleLayout {
+    /// The value type of all channels.
+    type Value: LslSample;
+
+    /// The labels of the channels, in order.
+    const LABELS: &'static [&'static str];
+
+    /**
+    Create the declaration of a stream whose samples have this layout, including the channel
+    labels (as `<channels><channel><label>` in the `desc()` field).
+
+    See `StreamInfo::new()` for the arguments.
+    */
+    fn stream_info(name: &str, type_: &str, srate: f64, source_id: &str) -> Result<StreamInfo> {
+        let mut info = StreamInfo::new(
+            name,
+            type_,
+            Self::LABELS.len() as u32,
+            srate,
+            Self::Value::CHANNEL_FORMAT,
+            source_id,
+        )?;
+        let mut channels = info.desc_mut().append_child("channels");
+        for label in Self::LABELS {
+            channels
+                .append_child("channel")
+                .append_child_value("label", label);
+        }
+        Ok(info)
+    }
+}
+
+/**
+A struct that can be filled from a pulled multi-channel sample (one channel per field), via
+`StreamInlet::pull_as()`.
+
+This is normally implemented via `#[derive(LslPull)]`, which requires the `derive` feature:
+
+```ignore
+use lsl::LslPull;
+
+#[derive(LslPull)]
+#[lsl(by_label)] // match fields to the channel labels of the stream, rather than by position
+struct Accelerometer {
+    #[lsl(label = "AccX")]
+    x: f32,
+    #[lsl(label = "AccY")]
+    y: f32,
+    #[lsl(label = "AccZ")]
+    z: f32,
+}
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "Accelerometer", 1, 5.0)?;
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    if let Some((acc, ts)) = inlet.pull_as::<Accelerometer>(lsl::FOREVER)? {
+        println!("acceleration: {}, {}, {} at {}", acc.x, acc.y, acc.z, ts);
+    }
+    Ok(())
+}
+```
+*/
+pub trait LslPull: Sized {
+    /// The value type of all channels.
+    type Value: LslSample;
+
+    /// The labels of the channels that correspond to the fields, in order.
+    const LABELS: &'static [&'static str];
+
+    /// Whether the fields are matched to channels by label (rather than by position).
+    const BY_LABEL: bool = false;
+
+    /// Build the struct from the values of a sample, where `indices[k]` is the index of the
+    /// channel that corresponds to the k'th field.
+    fn from_channels(values: &[Self::Value], indices: &[usize]) -> Self;
+}
+
+#[cfg(feature = "derive")]
+pub use lsl_derive::{LslPull, LslPush};
+
+// =======================
+// ==== Stream Outlet ====
+// =======================
+
+/**
+A stream outlet.
+Outlets are used to make streaming data (and the meta-data) available on the lab network.
+
+The actual sample-pushing functionality is provided via the `Pushable` and `ExPushable` traits
+below.
+
+Outlets can be cloned cheaply: all clones refer to the same stream, which disappears from the
+network when the last clone is dropped. Since pushing is thread-safe, this allows several
+components (e.g., threads that emit markers) to push into the same outlet without further
+synchronization.
+
+**Examples:** the `send_*.rs` examples (found in the crate's github repository) illustrate the use
+of `StreamOutlet`.
+*/
+#[derive(Debug, Clone)]
+pub struct StreamOutlet {
+    // internal fields used by the Rust wrapper
+    handle: sync::Arc<OutletHandle>,
+    channel_count: usize,
+    channel_format: ChannelFormat,
+    nominal_rate: f64,
+    chunk_size: ChunkSpec,
+}
+
+impl StreamOutlet {
+    /**
+    Establish a new stream outlet. This makes the stream discoverable.
+
+    Arguments:
+    * `info`: The stream information to use for creating this stream. Stays constant over the
+       lifetime of the outlet.
+    * `chunk_size`: The desired chunk granularity for transmission, either as a `ChunkSpec` or
+      as a number of samples. If specified as `ChunkSpec::PerPush` (or 0), each push operation
+      yields one chunk, and the `pushthrough` flag of the push calls decides when data are sent.
+      If specified as `ChunkSpec::Samples(n)` (or n), data are sent in chunks of n samples
+      regardless of the `pushthrough` flag. Inlets can override this setting.
+    * `max_buffered`: The maximum amount of data to buffer (in seconds if there is a
        nominal sampling rate, otherwise x100 in samples). A good default is 360, which corresponds
        to 6 minutes of data. Note that, for high-bandwidth data you should consider using a lower
        value here to avoid running out of RAM in case data have to be buffered unexpectedly.
+
+    If the channel format of the stream cannot be transmitted by the linked native library, this
+    returns an `Error::UnsupportedFormat` (rather than failing later when data are pushed).
     */
-    pub fn new(info: &StreamInfo, chunk_size: i32, max_buffered: i32) -> Result<StreamOutlet> {
+    pub fn new(
+        info: &StreamInfo,
+        chunk_size: impl Into<ChunkSpec>,
+        max_buffered: i32,
+    ) -> Result<StreamOutlet> {
+        let chunk_size = chunk_size.into();
         let channel_count = info.channel_count() as usize;
+        let channel_format = info.channel_format();
         let nominal_rate = info.nominal_srate();
-        if chunk_size < 0 || max_buffered < 0 || channel_count >= 0x80000000 || nominal_rate < 0.0 {
+        if chunk_size.to_native() < 0
+            || max_buffered < 0
+            || channel_count >= 0x80000000
+            || nominal_rate < 0.0
+        {
             return Err(Error::BadArgument);
         }
+        if !channel_format.is_supported() {
+            return Err(Error::UnsupportedFormat);
+        }
         unsafe {
             let handle =
-                lsl_create_outlet(info.native_handle(), chunk_size as i32, max_buffered as i32);
+                lsl_create_outlet(*info.native_handle(), chunk_size.to_native(), max_buffered);
             match handle.is_null() {
                 false => Ok(StreamOutlet {
-                    handle,
+                    handle: sync::Arc::new(OutletHandle(handle)),
                     channel_count,
+                    channel_format,
                     nominal_rate,
+                    chunk_size,
                 }),
                 true => Err(Error::ResourceCreation),
             }
         }
     }
 
+    /**
+    Establish a new stream outlet whose declaration is loaded from an XML file.
+
+    This allows deployments to keep their stream declarations (including the meta-data) as
+    versioned XML files, in the format produced by `StreamInfo::to_xml()`.
+
+    Arguments:
+    * `path`: The path of the XML file.
+    * `options`: The chunk size and buffering of the outlet (see `new()`).
+
+    Returns an `Error::Io` if the file cannot be read, and an `Error::Parse` (with the line of
+    the offending element) if it is not a valid stream declaration (see `StreamInfo::load_xml()`).
+    */
+    pub fn from_xml_file(
+        path: impl AsRef<std::path::Path>,
+        options: OutletOptions,
+    ) -> Result<StreamOutlet> {
+        let info = StreamInfo::load_xml(path)?;
+        StreamOutlet::new(&info, options.chunk_size, options.max_buffered)
+    }
+
+    /**
+    The chunk granularity with which this outlet transmits data, as specified at construction.
+
+    If this is `ChunkSpec::Samples(n)`, the `pushthrough` flag of the push calls has no effect.
+    */
+    pub fn chunk_size(&self) -> ChunkSpec {
+        self.chunk_size
+    }
+
+    /// The channel format of the stream, as declared when the outlet was created.
+    pub fn channel_format(&self) -> ChannelFormat {
+        self.channel_format
+    }
+
+    /**
+    Push a sample given as a `SampleValue`, e.g., one that was pulled from an arbitrary stream via
+    `StreamInlet::pull_sample_dynamic()`.
+
+    The variant of the value must match the channel format of the outlet, otherwise an
+    `Error::FormatMismatch` is returned. This is also available via the `Pushable` and
+    `ExPushable` traits (e.g., to push chunks of `SampleValue`s).
+
+    Arguments:
+    * `data`: The values to push (one for each channel).
+    * `timestamp`: Optionally the capture time of the sample, in agreement with `local_clock()`;
+      if passed as 0.0, the current time is used.
+    */
+    pub fn push_sample_dynamic(&self, data: &SampleValue, timestamp: f64) -> Result<()> {
+        self.push_sample_ex(data, timestamp, true)
+    }
+
+    /**
+    Push a sample given as raw bytes in the layout described by `raw_layout()`.
+
+    This is meant for drivers that receive their data from a device in wire form, and avoids
+    converting it into typed values first. The buffer must hold exactly one sample, otherwise an
+    `Error::LengthMismatch` is returned.
+
+    Arguments:
+    * `data`: The channel values, back to back, in the host's native byte order.
+    * `timestamp`: Optionally the capture time of the sample, in agreement with `local_clock()`;
+      if passed as 0.0, the current time is used.
+    * `pushthrough`: Whether to push the sample through to the receivers instead of buffering it
+      with subsequent samples.
+    */
+    pub fn push_sample_raw(&self, data: &[u8], timestamp: f64, pushthrough: bool) -> Result<()> {
+        self.raw_layout()?.check_len(data.len())?;
+        unsafe {
+            errcode_to_result(lsl_push_sample_vtp(
+                self.handle.0,
+                data.as_ptr() as *const std::os::raw::c_void,
+                timestamp,
+                pushthrough as i32,
+            ))?;
+        }
+        Ok(())
+    }
+
+    /**
+    The layout of the samples of this outlet in raw byte buffers (see `RawLayout`).
+
+    Returns an `Error::FormatMismatch` for string-formatted streams.
+    */
+    pub fn raw_layout(&self) -> Result<RawLayout> {
+        RawLayout::new(self.channel_format, self.channel_count)
+    }
+
     // ===============================
     // === Miscellaneous Functions ===
     // ===============================
@@ -608,7 +1830,7 @@ impl StreamOutlet {
     embedded device) -- however, this is not necessary and most production clients do not use it.
     */
     pub fn have_consumers(&self) -> bool {
-        unsafe { lsl_have_consumers(self.handle) != 0 }
+        unsafe { lsl_have_consumers(self.handle.0) != 0 }
     }
 
     /**
@@ -620,7 +1842,25 @@ impl StreamOutlet {
     Note that it is not necessary to do this, and most production clients do not use this feature.
     */
     pub fn wait_for_consumers(&self, timeout: f64) -> bool {
-        unsafe { lsl_wait_for_consumers(self.handle, timeout) != 0 }
+        unsafe { lsl_wait_for_consumers(self.handle.0, timeout) != 0 }
+    }
+
+    /**
+    Wait until this outlet can be discovered on the network (by resolving it locally).
+
+    Outlets take a moment to become visible to resolvers after they have been created. An
+    application that starts a consumer right after creating an outlet (or that signals another
+    party to do so) can use this to make sure that the consumer won't miss the stream.
+
+    Arguments:
+    * `timeout`: The maximum time to wait, in seconds. You can use the value `lsl::FOREVER` to
+      have no timeout.
+
+    Returns true if the stream was found, or false if the timeout expired.
+    */
+    pub fn wait_until_discoverable(&self, timeout: f64) -> Result<bool> {
+        let pred = format!("uid='{}'", self.info()?.uid());
+        Ok(!resolve_bypred(&pred, 1, timeout)?.is_empty())
     }
 
     /**
@@ -634,7 +1874,7 @@ impl StreamOutlet {
     */
     pub fn info(&self) -> Result<StreamInfo> {
         unsafe {
-            let info_handle = lsl_get_info(self.handle);
+            let info_handle = lsl_get_info(self.handle.0);
             match info_handle.is_null() {
                 // the handle already refers to a copy the outlet's info object so this operation
                 // is trivial
@@ -647,14 +1887,22 @@ impl StreamOutlet {
     // --- internal methods ---
 
     // Internal utility function that checks whether a given length value matches the channel count
-    fn assert_len(&self, len: usize) {
-        // we use assert since that's almost surely a sign of a fatal application bug
-        assert_eq!(
-            len, self.channel_count,
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len == self.channel_count {
+            return Ok(());
+        }
+        // with this feature, a mismatch is treated as the fatal application bug that it likely is
+        #[cfg(feature = "assert-lengths")]
+        panic!(
             "StreamOutlet received data whose length {} does not \
                    match the outlet's channel count {}",
             len, self.channel_count
         );
+        #[cfg(not(feature = "assert-lengths"))]
+        Err(Error::LengthMismatch {
+            expected: self.channel_count,
+            actual: len,
+        })
     }
 
     /*
@@ -672,13 +1920,18 @@ impl StreamOutlet {
     fn safe_push_numeric<T>(
         &self,
         func: NativePushFunction<T>,
-        data: &vec::Vec<T>,
+        data: &[T],
         timestamp: f64,
         pushthrough: bool,
     ) -> Result<()> {
-        self.assert_len(data.len());
+        self.check_len(data.len())?;
         unsafe {
-            errcode_to_result(func(self.handle, data.as_ptr(), timestamp, pushthrough as i32))?;
+            errcode_to_result(func(
+                self.handle.0,
+                data.as_ptr(),
+                timestamp,
+                pushthrough as i32,
+            ))?;
         }
         Ok(())
     }
@@ -701,7 +1954,7 @@ impl StreamOutlet {
         timestamp: f64,
         pushthrough: bool,
     ) -> Result<()> {
-        self.assert_len(data.len());
+        self.check_len(data.len())?;
         let ptrs: Vec<_> = data.iter().map(|x| x.as_ref().as_ptr()).collect();
         let lens: Vec<_> = data
             .iter()
@@ -709,7 +1962,7 @@ impl StreamOutlet {
             .collect();
         unsafe {
             errcode_to_result(lsl_push_sample_buftp(
-                self.handle,
+                self.handle.0,
                 ptrs.as_ptr() as *mut *const std::os::raw::c_char,
                 lens.as_ptr(),
                 timestamp,
@@ -727,8 +1980,9 @@ StreamOutlet.
 See also the `ExPushable` trait for the extended-argument versions of these methods,
 `push_sample_ex<T>()` and `push_chunk_ex<T>()`.
 
-**Note:** If you push in data that as the wrong size (array length not matching the declared number
-of channels), these functions will trigger an assertion and panic.
+**Note:** If you push in data that has the wrong size (array length not matching the declared number
+of channels), these functions return an `Error::LengthMismatch` (or panic, if the crate feature
+`assert-lengths` is enabled).
 */
 pub trait Pushable<T> {
     /**
@@ -744,8 +1998,9 @@ pub trait Pushable<T> {
     fn push_sample(&self, data: &T) -> Result<()>;
 
     /**
-    Push a chunk of samples (batched into a `Vec`) into the outlet. Each element of the given
-    vector must itself be in a format accepted by `push_sample()` (e.g., `Vec`).
+    Push a chunk of samples (batched into a `Vec` or slice) into the outlet. Each element of the
+    given chunk must itself be in a format accepted by `push_sample()` (e.g., a `Vec`, a slice,
+    or an array).
 
     The data are time-stamped with the current time (using `local_clock()`), and immediately
     transmitted (unless a `chunk_size` was provided at outlet construction, which causes the data
@@ -753,22 +2008,23 @@ pub trait Pushable<T> {
     `push_chunk_ex()` (provided by `ExPushable` trait) for a variant that allows for overriding the
     timestamp and implicit push-through (flush) behavior.
     */
-    fn push_chunk(&self, data: &vec::Vec<T>) -> Result<()>;
+    fn push_chunk(&self, data: &[T]) -> Result<()>;
 
     /**
-    Push a chunk of samples (batched into a `Vec`) along with a separate time stamp for each
-    sample (for irregular-rate streams) into the outlet.
+    Push a chunk of samples (batched into a `Vec` or slice) along with a separate time stamp for
+    each sample (for irregular-rate streams) into the outlet.
 
     Arguments:
-    * `samples`: A `Vec` of samples, each in a format accepted by `push_sample()` (e.g., `Vec`).
-    * `timestamps`: A `Vec` of capture times for each sample, in agreement with `local_clock()`.
+    * `samples`: A `Vec` or slice of samples, each in a format accepted by `push_sample()`.
+    * `timestamps`: A `Vec` or slice of capture times for each sample, in agreement with
+      `local_clock()`.
 
     The data are immediately transmitted (unless a `chunk_size` was provided at outlet
     construction, which causes the data to be internally re-aggregated into chunks of that
     specified size for ttransmission). See also `push_chunk_ex()` (provided by `ExPushable` trait)
     for a variant that allows for overriding this behavior.
     */
-    fn push_chunk_stamped(&self, samples: &vec::Vec<T>, stamps: &vec::Vec<f64>) -> Result<()>;
+    fn push_chunk_stamped(&self, samples: &[T], stamps: &[f64]) -> Result<()>;
 }
 
 // Pushable is basically a convenience layer on top of ExPushable
@@ -777,11 +2033,11 @@ impl<T, U: ExPushable<T>> Pushable<T> for U {
         self.push_sample_ex(data, 0.0, true)
     }
 
-    fn push_chunk(&self, data: &vec::Vec<T>) -> Result<()> {
+    fn push_chunk(&self, data: &[T]) -> Result<()> {
         self.push_chunk_ex(data, 0.0, true)
     }
 
-    fn push_chunk_stamped(&self, samples: &vec::Vec<T>, stamps: &vec::Vec<f64>) -> Result<()> {
+    fn push_chunk_stamped(&self, samples: &[T], stamps: &[f64]) -> Result<()> {
         self.push_chunk_stamped_ex(samples, stamps, true)
     }
 }
@@ -792,8 +2048,9 @@ Implemented by StreamOutlet.
 
 See also the `Pushable` trait for the simpler methods `push_sample<T>()` and `push_chunk<T>()`.
 
-**Note:** If you push in data that as the wrong size (array length not matching the declared number
-of channels), these functions will trigger an assertion and panic.
+**Note:** If you push in data that has the wrong size (array length not matching the declared number
+of channels), these functions return an `Error::LengthMismatch` (or panic, if the crate feature
+`assert-lengths` is enabled).
 */
 pub trait ExPushable<T>: HasNominalRate {
     /**
@@ -815,10 +2072,10 @@ pub trait ExPushable<T>: HasNominalRate {
     fn push_sample_ex(&self, data: &T, timestamp: f64, pushthrough: bool) -> Result<()>;
 
     /**
-    Push a chunk of samples (batched into a `Vec`) into the outlet.
+    Push a chunk of samples (batched into a `Vec` or slice) into the outlet.
 
     Arguments:
-    * `samples`: A `Vec` of samples, each in a format accepted by `push_sample()` (e.g., `Vec`).
+    * `samples`: A `Vec` or slice of samples, each in a format accepted by `push_sample()`.
     * `timestamp`: Optionally the capture time of the most recent sample, in agreement with
        `local_clock()`; if specified as 0.0, the current time is used. The time stamps of other
        samples are automatically derived according to the sampling rate of the stream.
@@ -829,12 +2086,7 @@ pub trait ExPushable<T>: HasNominalRate {
     See also `push_chunk()` for a simpler variant with default values for `timestamp` and
     `pushthrough` (defined in `Pushable` trait).
     */
-    fn push_chunk_ex(
-        &self,
-        samples: &vec::Vec<T>,
-        timestamp: f64,
-        pushthrough: bool,
-    ) -> Result<()> {
+    fn push_chunk_ex(&self, samples: &[T], timestamp: f64, pushthrough: bool) -> Result<()> {
         if !samples.is_empty() {
             let mut timestamp = if timestamp == 0.0 {
                 local_clock()
@@ -857,70 +2109,61 @@ pub trait ExPushable<T>: HasNominalRate {
     }
 
     /**
-    Push a chunk of samples (batched into a `Vec`) into the outlet.
+    Push a chunk of samples (batched into a `Vec` or slice) into the outlet.
     Allows for specifying a separate time stamp for each sample (for irregular-rate streams).
 
     Arguments:
-    * `samples`: A `Vec` of samples, each in a format accepted by `push_sample()` (e.g., `Vec`).
-    * `timestamps`: A `Vec` of capture times for each sample, in agreement with `local_clock()`.
+    * `samples`: A `Vec` or slice of samples, each in a format accepted by `push_sample()`.
+    * `timestamps`: A `Vec` or slice of capture times for each sample, in agreement with
+      `local_clock()`.
     * `pushthrough`: Whether to push the chunk through to the receivers instead of buffering it
        with subsequent samples. Typically this would be `true`. Note that the `chunk_size`, if
        specified at outlet construction, takes precedence over the pushthrough flag.
     */
     fn push_chunk_stamped_ex(
         &self,
-        samples: &vec::Vec<T>,
-        timestamps: &vec::Vec<f64>,
+        samples: &[T],
+        timestamps: &[f64],
         pushthrough: bool,
     ) -> Result<()> {
+        // with this feature, a mismatch is treated as a fatal application bug (as in check_len())
+        #[cfg(feature = "assert-lengths")]
         assert_eq!(samples.len(), timestamps.len());
+        if samples.len() != timestamps.len() {
+            return Err(Error::LengthMismatch {
+                expected: samples.len(),
+                actual: timestamps.len(),
+            });
+        }
+        if samples.is_empty() {
+            return Ok(());
+        }
         let max_k = samples.len() - 1;
         // send all except last sample
         for k in 0..max_k {
             self.push_sample_ex(&samples[k], timestamps[k], false)?;
         }
         // send last sample with given pushthrough flag
-        if !samples.is_empty() {
-            self.push_sample_ex(&samples[max_k], timestamps[max_k], pushthrough)?;
-        }
+        self.push_sample_ex(&samples[max_k], timestamps[max_k], pushthrough)?;
         Ok(())
     }
 }
 
-impl ExPushable<vec::Vec<f32>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<f32>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_ftp, data, timestamp, pushthrough)
-    }
-}
-
-impl ExPushable<vec::Vec<f64>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<f64>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_dtp, data, timestamp, pushthrough)
-    }
-}
-
-impl ExPushable<vec::Vec<i8>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<i8>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_ctp, data, timestamp, pushthrough)
-    }
-}
-
-impl ExPushable<vec::Vec<i16>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<i16>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_stp, data, timestamp, pushthrough)
+impl<T: LslSample> ExPushable<vec::Vec<T>> for StreamOutlet {
+    fn push_sample_ex(&self, data: &vec::Vec<T>, timestamp: f64, pushthrough: bool) -> Result<()> {
+        self.safe_push_numeric(T::PUSH_SAMPLE, data, timestamp, pushthrough)
     }
 }
 
-impl ExPushable<vec::Vec<i32>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<i32>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_itp, data, timestamp, pushthrough)
+impl<T: LslSample> ExPushable<&[T]> for StreamOutlet {
+    fn push_sample_ex(&self, data: &&[T], timestamp: f64, pushthrough: bool) -> Result<()> {
+        self.safe_push_numeric(T::PUSH_SAMPLE, data, timestamp, pushthrough)
     }
 }
 
-#[cfg(not(windows))] // TODO: once we upgrade to liblsl 1.14, we can drop this platform restriction
-impl ExPushable<vec::Vec<i64>> for StreamOutlet {
-    fn push_sample_ex(&self, data: &vec::Vec<i64>, timestamp: f64, pushthrough: bool) -> Result<()> {
-        self.safe_push_numeric(lsl_push_sample_ltp, data, timestamp, pushthrough)
+impl<T: LslSample, const N: usize> ExPushable<[T; N]> for StreamOutlet {
+    fn push_sample_ex(&self, data: &[T; N], timestamp: f64, pushthrough: bool) -> Result<()> {
+        self.safe_push_numeric(T::PUSH_SAMPLE, data, timestamp, pushthrough)
     }
 }
 
@@ -942,14 +2185,40 @@ impl ExPushable<vec::Vec<&[u8]>> for StreamOutlet {
     }
 }
 
-impl Drop for StreamOutlet {
+impl ExPushable<SampleValue> for StreamOutlet {
+    fn push_sample_ex(&self, data: &SampleValue, timestamp: f64, pushthrough: bool) -> Result<()> {
+        if data.channel_format() != self.channel_format {
+            return Err(Error::FormatMismatch);
+        }
+        match data {
+            SampleValue::F32(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::F64(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::I8(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::I16(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::I32(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::I64(x) => self.push_sample_ex(x, timestamp, pushthrough),
+            SampleValue::Str(x) => self.push_sample_ex(x, timestamp, pushthrough),
+        }
+    }
+}
+
+// wrapper around a native outlet handle, which is shared by the clones of a `StreamOutlet`
+#[derive(Debug)]
+struct OutletHandle(lsl_outlet);
+
+impl Drop for OutletHandle {
     fn drop(&mut self) {
         unsafe {
-            lsl_destroy_outlet(self.handle);
+            lsl_destroy_outlet(self.0);
         }
     }
 }
 
+// the native outlet is thread-safe, i.e., it can be moved across threads and pushed into from
+// several threads concurrently
+unsafe impl Send for OutletHandle {}
+unsafe impl Sync for OutletHandle {}
+
 /// Exposes a sampling rate via the method nominal_srate().
 #[doc(hidden)]
 pub trait HasNominalRate {
@@ -962,6 +2231,129 @@ impl HasNominalRate for StreamOutlet {
     }
 }
 
+/// The chunk size and buffering of an outlet that is created from a file (see `StreamOutlet`).
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct OutletOptions {
+    /// The chunk granularity (see `StreamOutlet::new()`); defaults to `ChunkSpec::PerPush`.
+    pub chunk_size: ChunkSpec,
+    /// The maximum amount of data to buffer (see `StreamOutlet::new()`); defaults to 360.
+    pub max_buffered: i32,
+}
+
+impl Default for OutletOptions {
+    fn default() -> OutletOptions {
+        OutletOptions {
+            chunk_size: ChunkSpec::PerPush,
+            max_buffered: 360,
+        }
+    }
+}
+
+/**
+A builder for outlets, which takes the buffer size in explicit units (see `BufferSpec`).
+
+```no_run
+use lsl::{BufferSpec, ChannelFormat, OutletBuilder, Pushable, StreamInfo};
+use std::time::Duration;
+
+fn main() -> Result<(), lsl::Error> {
+    let info = StreamInfo::new("Amp", "EEG", 8, 500.0, ChannelFormat::Float32, "amp-1")?;
+    let outlet = OutletBuilder::new(&info)
+        .buffer(BufferSpec::Duration(Duration::from_secs(30)))
+        .build()?;
+    outlet.push_sample(&vec![0.0f32; 8])?;
+    Ok(())
+}
+```
+*/
+#[derive(Clone, Debug)]
+pub struct OutletBuilder {
+    info: StreamInfo,
+    chunk_size: ChunkSpec,
+    buffer: BufferSpec,
+}
+
+impl OutletBuilder {
+    /// Start building an outlet for the given stream (see `StreamOutlet::new()`).
+    pub fn new(info: &StreamInfo) -> OutletBuilder {
+        OutletBuilder {
+            info: info.clone(),
+            chunk_size: ChunkSpec::PerPush,
+            buffer: BufferSpec::Duration(std::time::Duration::from_secs(360)),
+        }
+    }
+
+    /// Set the chunk granularity (see `StreamOutlet::new()`); the default is `ChunkSpec::PerPush`.
+    pub fn chunk_size(mut self, chunk_size: impl Into<ChunkSpec>) -> OutletBuilder {
+        self.chunk_size = chunk_size.into();
+        self
+    }
+
+    /**
+    Set the maximum amount of data to buffer, which is converted based on the nominal sampling
+    rate of the stream (see `BufferSpec::to_buflen()`); the default is 6 minutes of data.
+    */
+    pub fn buffer(mut self, buffer: BufferSpec) -> OutletBuilder {
+        self.buffer = buffer;
+        self
+    }
+
+    /// Establish the outlet (see `StreamOutlet::new()`).
+    pub fn build(self) -> Result<StreamOutlet> {
+        let max_buffered = self.buffer.to_buflen(self.info.nominal_srate());
+        StreamOutlet::new(&self.info, self.chunk_size, max_buffered)
+    }
+}
+
+/**
+A stream property by which streams can be resolved (see `resolve_byprop()`).
+
+Using one of the named variants rather than a property name as a string catches misspelled
+properties (e.g., "source_Id") at compile time, which would otherwise silently match no stream. A
+`&str` converts into a `Raw` property name, so strings can still be used where needed.
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ResolveProp<'a> {
+    /// The name of the stream (see `StreamInfo::stream_name()`).
+    Name,
+    /// The content type of the stream (see `StreamInfo::stream_type()`).
+    Type,
+    /// The unique identifier of the stream's source (see `StreamInfo::source_id()`).
+    SourceId,
+    /// The name of the host that provides the stream (see `StreamInfo::hostname()`).
+    Hostname,
+    /// A field of the stream's meta-data, given as a path below `desc` (e.g., "manufacturer").
+    DescPath(&'a str),
+    /// Any other property, given by its name in the stream's XML declaration.
+    Raw(&'a str),
+}
+
+impl<'a> ResolveProp<'a> {
+    /// The name of the property, as understood by the native library (e.g., "source_id").
+    pub fn name(&self) -> String {
+        match self {
+            ResolveProp::Name => "name".to_string(),
+            ResolveProp::Type => "type".to_string(),
+            ResolveProp::SourceId => "source_id".to_string(),
+            ResolveProp::Hostname => "hostname".to_string(),
+            ResolveProp::DescPath(path) => format!("desc/{}", path),
+            ResolveProp::Raw(name) => name.to_string(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ResolveProp<'a> {
+    fn from(name: &'a str) -> ResolveProp<'a> {
+        ResolveProp::Raw(name)
+    }
+}
+
+impl<'a> From<&'a String> for ResolveProp<'a> {
+    fn from(name: &'a String) -> ResolveProp<'a> {
+        ResolveProp::Raw(name)
+    }
+}
+
 // ===========================
 // ==== Resolve Functions ====
 // ===========================
@@ -982,26 +2374,30 @@ Arguments:
    the outlets that are present on the network may be returned.
 
 Returns a `Vec` of `StreamInfo` objects (excluding their desc field), any of which can subsequently
-be used to open an inlet. The full info can be retrieved from the inlet if needed.
+be used to open an inlet. The full info can be retrieved from the inlet if needed. At most
+`RESOLVE_CAPACITY` streams are returned (see `resolve_streams_with_capacity()`).
 
 **Examples: the `receive_*.rs` examples (found in the crate's github repository) illustrate
 the use of the resolve functions.
 */
 pub fn resolve_streams(wait_time: f64) -> Result<vec::Vec<StreamInfo>> {
-    // the fixed-size buffer is safe since the native function uses it as the max number of results
-    let mut buffer = [0 as lsl_streaminfo; 1024];
-    unsafe {
-        let num_resolved = errcode_to_result(lsl_resolve_all(
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            wait_time,
-        ))? as usize;
-        let results: Vec<_> = buffer[0..num_resolved]
-            .iter()
-            .map(|x| StreamInfo::from_handle(*x))
-            .collect();
-        Ok(results)
-    }
+    Ok(resolve_streams_with_capacity(wait_time, RESOLVE_CAPACITY)?.streams)
+}
+
+/**
+Resolve all streams on the network, returning up to `capacity` of them.
+
+Like `resolve_streams()`, but with a configurable maximum number of results, and an indication of
+whether that maximum was reached (see `Resolved`). Returns an `Error::BadArgument` if `capacity`
+is 0.
+
+Memory for `capacity` results is allocated up front, since the native library needs a buffer of
+that size for the resolve.
+*/
+pub fn resolve_streams_with_capacity(wait_time: f64, capacity: usize) -> Result<Resolved> {
+    collect_resolved(capacity, |buffer, len| unsafe {
+        lsl_resolve_all(buffer, len, wait_time)
+    })
 }
 
 /**
@@ -1011,8 +2407,8 @@ If the goal is to resolve a specific stream, this method is preferred over resol
 and then selecting the desired one.
 
 Arguments:
-* `prop`: The `StreamInfo` property that should have a specific value (e.g., "name", "type",
-  "source_id", or "desc/manufaturer").
+* `prop`: The `StreamInfo` property that should have a specific value (e.g.,
+  `ResolveProp::Type` or `ResolveProp::DescPath("manufacturer")`; see `ResolveProp`).
 * `value`: The string value that the property should have (e.g., "EEG" as the type property).
 * `minimum`: Return at least this number of streams.
 * `timeout`: A timeout for the operation, in seconds. If the timeout expires, less than the desired
@@ -1024,35 +2420,44 @@ Arguments:
 Returns a `Vec` of `StreamInfo` objects (excluding their desc field), any of which can subsequently
 be used to open an inlet. The full info can be retrieved from the inlet if needed. In case of a
 timeout, the result is *not* an `Error::Timeout` but instead an shorter or empty result vector.
+At most `RESOLVE_CAPACITY` streams are returned (see `resolve_byprop_with_capacity()`).
 
 **Examples: the `receive_*.rs` examples (found in the crate's github repository) illustrate
 the use of the resolve functions.
 */
-pub fn resolve_byprop(
-    prop: &str,
+pub fn resolve_byprop<'a, P: Into<ResolveProp<'a>>>(
+    prop: P,
     value: &str,
     minimum: i32,
     wait_time: f64,
 ) -> Result<vec::Vec<StreamInfo>> {
-    // the fixed-size buffer is safe since the native function uses it as the max number of results
-    let mut buffer = [0 as lsl_streaminfo; 1024];
-    let prop = ffi::CString::new(prop)?;
+    Ok(resolve_byprop_with_capacity(prop, value, minimum, wait_time, RESOLVE_CAPACITY)?.streams)
+}
+
+/**
+Resolve the streams with a specific value for a given property, returning up to `capacity` of
+them.
+
+Like `resolve_byprop()`, but with a configurable maximum number of results, and an indication of
+whether that maximum was reached (see `Resolved`). Returns an `Error::BadArgument` if `capacity`
+is 0.
+
+Memory for `capacity` results is allocated up front, since the native library needs a buffer of
+that size for the resolve.
+*/
+pub fn resolve_byprop_with_capacity<'a, P: Into<ResolveProp<'a>>>(
+    prop: P,
+    value: &str,
+    minimum: i32,
+    wait_time: f64,
+    capacity: usize,
+) -> Result<Resolved> {
+    let prop = ffi::CString::new(prop.into().name())?;
     let value = ffi::CString::new(value)?;
-    unsafe {
-        let num_resolved = errcode_to_result(lsl_resolve_byprop(
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            prop.as_ptr(),
-            value.as_ptr(),
-            minimum,
-            wait_time,
-        ))? as usize;
-        let results: Vec<_> = buffer[0..num_resolved]
-            .iter()
-            .map(|x| StreamInfo::from_handle(*x))
-            .collect();
-        Ok(results)
-    }
+    let (prop, value) = (prop.as_ptr(), value.as_ptr());
+    collect_resolved(capacity, |buffer, len| unsafe {
+        lsl_resolve_byprop(buffer, len, prop, value, minimum, wait_time)
+    })
 }
 
 /**
@@ -1065,7 +2470,8 @@ surrounding []'s), for each stream that's on the network.
 
 Arguments:
 * `pred`: The predicate string, e.g. `name='BioSemi'` or
-     `type='EEG' and starts-with(name,'BioSemi') and count(info/desc/channel)=32`
+  `type='EEG' and starts-with(name,'BioSemi') and count(info/desc/channel)=32`, or a
+  `lsl::query::Query` that renders it.
 * `minimum`: Return at least this many streams.
 * `timeout`: A timeout for the operation, in seconds. If the timeout expires, less than the desired
    number of streams (possibly none) will be returned. To have no timeout you can use the value
@@ -1076,32 +2482,223 @@ Arguments:
 Returns a `Vec` of `StreamInfo` objects (excluding their desc field), any of which can subsequently
 be used to open an inlet. The full info can be retrieved from the inlet if needed. In case of a
 timeout, the result is *not* an `Error:Timeout` but instead an shorter or empty result vector.
+At most `RESOLVE_CAPACITY` streams are returned (see `resolve_bypred_with_capacity()`).
 
 **Examples: the `receive_*.rs` examples (found in the crate's github repository) illustrate
 the use of the resolve functions.
 */
-pub fn resolve_bypred(pred: &str, minimum: i32, wait_time: f64) -> Result<vec::Vec<StreamInfo>> {
-    // the fixed-size buffer is safe since the native function uses it as the max number of results
-    let mut buffer = [0 as lsl_streaminfo; 1024];
-    let pred = ffi::CString::new(pred)?;
-    unsafe {
-        let num_resolved = errcode_to_result(lsl_resolve_bypred(
-            buffer.as_mut_ptr(),
-            buffer.len() as u32,
-            pred.as_ptr(),
-            minimum,
-            wait_time,
-        ))? as usize;
-        let results: Vec<_> = buffer[0..num_resolved]
-            .iter()
-            .map(|x| StreamInfo::from_handle(*x))
-            .collect();
-        Ok(results)
-    }
+pub fn resolve_bypred(
+    pred: impl AsRef<str>,
+    minimum: i32,
+    wait_time: f64,
+) -> Result<vec::Vec<StreamInfo>> {
+    Ok(resolve_bypred_with_capacity(pred, minimum, wait_time, RESOLVE_CAPACITY)?.streams)
 }
 
-// ======================
-// ==== Stream Inlet ====
+/**
+Resolve the streams that match a given predicate, returning up to `capacity` of them.
+
+Like `resolve_bypred()`, but with a configurable maximum number of results, and an indication of
+whether that maximum was reached (see `Resolved`). Returns an `Error::BadArgument` if `capacity`
+is 0.
+
+Memory for `capacity` results is allocated up front, since the native library needs a buffer of
+that size for the resolve.
+*/
+pub fn resolve_bypred_with_capacity(
+    pred: impl AsRef<str>,
+    minimum: i32,
+    wait_time: f64,
+    capacity: usize,
+) -> Result<Resolved> {
+    let pred = ffi::CString::new(pred.as_ref())?;
+    collect_resolved(capacity, |buffer, len| unsafe {
+        lsl_resolve_bypred(buffer, len, pred.as_ptr(), minimum, wait_time)
+    })
+}
+
+/// The results of a resolve function with a capacity (see `resolve_streams_with_capacity()`).
+#[derive(Clone, Debug)]
+pub struct Resolved {
+    /// The resolved streams (excluding their desc field).
+    pub streams: vec::Vec<StreamInfo>,
+    /**
+    Whether the number of results reached the capacity, in which case further matching streams
+    may have been left out (the native library does not report how many there were).
+    */
+    pub truncated: bool,
+}
+
+/**
+Collapse duplicate entries in a list of resolved streams.
+
+The same stream can be reported more than once, e.g., when it is visible over several network
+interfaces, or when its source was restarted while the previous instance was still remembered.
+Entries are considered duplicates if they have the same source id or, for streams without a
+source id, the same UID. Of each set of duplicates, the most recently created stream (see
+`StreamInfo::created_at()`) is kept, or the first one if they were created at the same time; the
+result is in the order in which the streams first occur in the input.
+*/
+pub fn dedup_streams(streams: vec::Vec<StreamInfo>) -> vec::Vec<StreamInfo> {
+    let mut result: vec::Vec<StreamInfo> = vec![];
+    // the key of each stream in the result, and its position
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for info in streams {
+        let key = match info.source_id() {
+            id if id.is_empty() => format!("uid:{}", info.uid()),
+            id => format!("source_id:{}", id),
+        };
+        match positions.get(&key) {
+            Some(&k) => {
+                if info.created_at() > result[k].created_at() {
+                    result[k] = info;
+                }
+            }
+            None => {
+                positions.insert(key, result.len());
+                result.push(info);
+            }
+        }
+    }
+    result
+}
+
+/**
+A restriction of resolve results to the streams of certain hosts (e.g., those of a lab's own rig,
+in a network that is shared with other labs).
+
+Filters can be given to a `ResolveBuilder` (see `ResolveBuilder::host()`) or to a
+`ContinuousResolver` (see `ContinuousResolver::with_host_filters()`), and are applied to the
+results on the receiving side.
+*/
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum HostFilter {
+    /// The stream's hostname (see `StreamInfo::hostname()`) equals the given one, ignoring case.
+    Hostname(String),
+    /**
+    The stream's hostname matches a pattern, ignoring case, in which `*` stands for any
+    sequence of characters and `?` for any single character (e.g., "rig3-*").
+    */
+    Pattern(String),
+    /**
+    An address of the stream's host (see `StreamInfo::addresses()`) lies in the subnet with the
+    given address and prefix length (e.g., 192.168.1.0 and 24).
+    */
+    Subnet(std::net::IpAddr, u8),
+}
+
+impl HostFilter {
+    /**
+    Create a subnet filter from the usual notation (e.g., "192.168.1.0/24" or "fd00::/8").
+
+    Returns an `Error::BadArgument` if the notation is malformed, or if the prefix length exceeds
+    the number of bits of the address.
+    */
+    pub fn subnet(cidr: &str) -> Result<HostFilter> {
+        let (addr, len) = cidr.split_once('/').ok_or(Error::BadArgument)?;
+        let addr: std::net::IpAddr = addr.trim().parse().map_err(|_| Error::BadArgument)?;
+        let len: u8 = len.trim().parse().map_err(|_| Error::BadArgument)?;
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        if len > bits {
+            return Err(Error::BadArgument);
+        }
+        Ok(HostFilter::Subnet(addr, len))
+    }
+
+    /// Whether the given stream matches the filter.
+    pub fn matches(&self, info: &StreamInfo) -> bool {
+        match self {
+            HostFilter::Hostname(name) => info.hostname().eq_ignore_ascii_case(name),
+            HostFilter::Pattern(pattern) => {
+                let hostname = info.hostname().to_ascii_lowercase();
+                glob_match(pattern.to_ascii_lowercase().as_bytes(), hostname.as_bytes())
+            }
+            HostFilter::Subnet(net, len) => info
+                .addresses()
+                .iter()
+                .any(|addr| in_subnet(addr, net, *len)),
+        }
+    }
+}
+
+// Keep the streams that match any of the filters (or all streams if there are no filters).
+fn filter_hosts(streams: vec::Vec<StreamInfo>, filters: &[HostFilter]) -> vec::Vec<StreamInfo> {
+    if filters.is_empty() {
+        return streams;
+    }
+    streams
+        .into_iter()
+        .filter(|info| filters.iter().any(|f| f.matches(info)))
+        .collect()
+}
+
+// Match a text against a pattern with the wildcards `*` and `?`.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // the position of the last `*` in the pattern, and of the text where it began to match
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // let the last `*` match one more character
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+// Whether an address lies in a subnet (addresses of different families never do).
+fn in_subnet(addr: &std::net::IpAddr, net: &std::net::IpAddr, len: u8) -> bool {
+    use std::net::IpAddr::{V4, V6};
+    match (addr, net) {
+        (V4(addr), V4(net)) => {
+            let shift = 32 - u32::from(len.min(32));
+            let mask = u32::MAX.checked_shl(shift).unwrap_or(0);
+            u32::from(*addr) & mask == u32::from(*net) & mask
+        }
+        (V6(addr), V6(net)) => {
+            let shift = 128 - u32::from(len.min(128));
+            let mask = u128::MAX.checked_shl(shift).unwrap_or(0);
+            u128::from(*addr) & mask == u128::from(*net) & mask
+        }
+        _ => false,
+    }
+}
+
+// Run a native resolve function with a buffer of the given capacity, and collect its results.
+fn collect_resolved<F>(capacity: usize, resolve: F) -> Result<Resolved>
+where
+    F: FnOnce(*mut lsl_streaminfo, u32) -> i32,
+{
+    if capacity == 0 {
+        return Err(Error::BadArgument);
+    }
+    // (the native function uses the length of the buffer as the max number of results, and
+    // silently drops the others)
+    let len = capacity.min(i32::MAX as usize);
+    let mut buffer: vec::Vec<lsl_streaminfo> = vec![std::ptr::null_mut(); len];
+    let num_resolved = errcode_to_result(resolve(buffer.as_mut_ptr(), len as u32))? as usize;
+    let streams: vec::Vec<_> = buffer[0..num_resolved]
+        .iter()
+        .map(|&x| StreamInfo::from_handle(x))
+        .collect();
+    Ok(Resolved {
+        truncated: streams.len() >= len,
+        streams,
+    })
+}
+
+// ======================
+// ==== Stream Inlet ====
 // ======================
 
 /**
@@ -1125,6 +2722,30 @@ pub struct StreamInlet {
     // internal fields used by the Rust wrapper
     handle: lsl_inlet,
     channel_count: usize,
+    channel_format: ChannelFormat,
+    format_checking: bool,
+    string_decoding: StringDecoding,
+    wait_strategy: WaitStrategy,
+    // the information of the stream as it was resolved (see `core_info()`)
+    resolved: StreamInfo,
+    // channel indices of the fields of `LslPull` structs that are matched by label
+    field_maps: sync::Mutex<HashMap<any::TypeId, Vec<usize>>>,
+    // the time-correction history and the worker that records it, if enabled
+    tc_history: Option<(TimeCorrectionHistory, worker::Worker<TimeCorrectionLogger>)>,
+    // the connection events and the worker that detects them, if enabled
+    connection_log: Option<(ConnectionLog, worker::Worker<ConnectionMonitor>)>,
+    // the clock resets and time-correction queries, shared with the background workers, and the
+    // number of resets that were reported by `was_clock_reset()`
+    clock: sync::Arc<ClockObserver>,
+    reported_resets: sync::atomic::AtomicU64,
+    // how `open_stream()` and `info()` retry after a timeout, if at all
+    retry_policy: Option<RetryPolicy>,
+    // the arguments and settings of the native inlet, which are carried over by `rebind()`
+    max_buflen: i32,
+    max_chunklen: i32,
+    recover: bool,
+    postprocessing: sync::atomic::AtomicU32,
+    smoothing_halftime: sync::Mutex<Option<f32>>,
 }
 
 impl StreamInlet {
@@ -1164,7 +2785,7 @@ impl StreamInlet {
         }
         unsafe {
             let handle = lsl_create_inlet(
-                info.native_handle(),
+                *info.native_handle(),
                 max_buflen,
                 max_chunklen,
                 recover as i32,
@@ -1173,6 +2794,22 @@ impl StreamInlet {
                 false => Ok(StreamInlet {
                     handle,
                     channel_count,
+                    channel_format: info.channel_format(),
+                    format_checking: true,
+                    string_decoding: StringDecoding::Lossy,
+                    wait_strategy: WaitStrategy::Block,
+                    resolved: info.clone(),
+                    field_maps: sync::Mutex::new(HashMap::new()),
+                    tc_history: None,
+                    connection_log: None,
+                    clock: sync::Arc::new(ClockObserver::default()),
+                    reported_resets: sync::atomic::AtomicU64::new(0),
+                    retry_policy: None,
+                    max_buflen,
+                    max_chunklen,
+                    recover,
+                    postprocessing: sync::atomic::AtomicU32::new(0),
+                    smoothing_halftime: sync::Mutex::new(None),
                 }),
                 true => Err(Error::ResourceCreation),
             }
@@ -1185,16 +2822,57 @@ impl StreamInlet {
 
     Arguments:
     * `timeout`: Timeout of the operation. You can use the value `lsl::FOREVER` to have no timeout.
+      If a retry policy is set (see `set_retry_policy()`), this is the timeout of each attempt.
     */
     pub fn info(&self, timeout: f64) -> Result<StreamInfo> {
-        let mut ec = [0 as i32];
-        unsafe {
-            let handle = lsl_get_fullinfo(self.handle, timeout, ec.as_mut_ptr());
-            errcode_to_result(ec[0])?;
-            match handle.is_null() {
-                false => Ok(StreamInfo::from_handle(handle)),
-                true => Err(Error::Unknown),
+        self.with_retries(|| {
+            let mut ec = [0i32];
+            unsafe {
+                let handle = lsl_get_fullinfo(self.handle, timeout, ec.as_mut_ptr());
+                errcode_to_result(ec[0])?;
+                match handle.is_null() {
+                    false => Ok(StreamInfo::from_handle(handle)),
+                    true => Err(Error::Unknown),
+                }
             }
+        })
+    }
+
+    /**
+    The information of the stream without its extended description (`desc()`), as known when the
+    inlet was created. This does not involve the network.
+    */
+    pub fn core_info(&self) -> StreamInfo {
+        let mut core = self.resolved.clone();
+        let mut desc = core.desc_mut();
+        while desc.first_child().is_valid() {
+            let child = desc.first_child();
+            desc.remove_child(child);
+        }
+        core
+    }
+
+    /**
+    Retrieve the information of the stream, falling back to the core information (see
+    `core_info()`) if the full information cannot be retrieved within `timeout` seconds.
+
+    Some streams carry very large meta-data (e.g., multi-megabyte channel layouts), which can
+    take a long time to retrieve; this keeps them from stalling, e.g., the startup of a recorder.
+    Whether the extended description is included is reported in the result. Note that only the
+    time is limited: the native library cannot retrieve part of a description, so its size cannot
+    be capped (to not use the network at all, use `core_info()`).
+    */
+    pub fn info_or_core(&self, timeout: f64) -> Result<InfoFallback> {
+        match self.info(timeout) {
+            Ok(info) => Ok(InfoFallback {
+                info,
+                complete: true,
+            }),
+            Err(Error::Timeout) => Ok(InfoFallback {
+                info: self.core_info(),
+                complete: false,
+            }),
+            Err(e) => Err(e),
         }
     }
 
@@ -1210,16 +2888,75 @@ impl StreamInlet {
 
     Arguments:
     * `timeout` Optional timeout of the operation. To have no timeout, you can use `lsl::FOREVER`
-       here. A timeout can make sense if you want to catch connection errors (e.g., due to
-       misconfigured firewalls or the like).
+      here. A timeout can make sense if you want to catch connection errors (e.g., due to
+      misconfigured firewalls or the like). If a retry policy is set (see
+      `set_retry_policy()`), this is the timeout of each attempt.
     */
     pub fn open_stream(&self, timeout: f64) -> Result<()> {
-        let mut ec = [0 as i32];
-        unsafe {
-            lsl_open_stream(self.handle, timeout, ec.as_mut_ptr());
-            errcode_to_result(ec[0])?;
+        self.with_retries(|| {
+            let mut ec = [0i32];
+            unsafe {
+                lsl_open_stream(self.handle, timeout, ec.as_mut_ptr());
+                errcode_to_result(ec[0])?;
+            }
+            Ok(())
+        })
+    }
+
+    /**
+    Set how `open_stream()` and `info()` retry after they have timed out (e.g., on an unreliable
+    wireless network), or `None` to fail right away (the default).
+    */
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    fn with_retries<R>(&self, mut op: impl FnMut() -> Result<R>) -> Result<R> {
+        match &self.retry_policy {
+            Some(policy) => policy.retry(op),
+            None => op(),
         }
-        Ok(())
+    }
+
+    /**
+    Subscribe to the data stream and measure how long it takes until data arrive.
+
+    This works like `open_stream()`, but additionally waits until the first sample has arrived,
+    and obtains a first time-correction estimate. The resulting `OpenMeasurement` can be logged as
+    a health metric of the connection at startup. Since the native library cannot wait for data
+    without pulling them, the first sample is pulled, and is handed over as part of the
+    measurement.
+
+    Arguments:
+    * `timeout`: Timeout of the entire operation. You can use `lsl::FOREVER` to have no timeout.
+      If no sample arrives within this time, an `Error::Timeout` is returned; if the sample
+      arrives, but the time correction cannot be estimated in the remaining time, the measurement
+      is returned without it (so that the sample is not lost).
+    */
+    pub fn open_and_measure(&self, timeout: f64) -> Result<OpenMeasurement> {
+        let start = local_clock();
+        let deadline = start + timeout;
+        let remaining = || match deadline - local_clock() {
+            remaining if remaining > 0.0 => Ok(remaining),
+            _ => Err(Error::Timeout),
+        };
+        self.open_stream(timeout)?;
+        let opened = local_clock();
+        let first_sample = self
+            .pull_sample_dynamic(remaining()?)?
+            .ok_or(Error::Timeout)?;
+        let arrived = local_clock();
+        let time_correction = match remaining().and_then(|left| self.time_correction(left)) {
+            Ok(correction) => Some(correction),
+            Err(Error::Timeout) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(OpenMeasurement {
+            open_duration: opened - start,
+            first_sample_latency: arrived - start,
+            time_correction,
+            first_sample,
+        })
     }
 
     /**
@@ -1256,6 +2993,7 @@ impl StreamInlet {
        time-correction estimate in the background, which can be queried in a subsequent call.
     */
     pub fn time_correction(&self, timeout: f64) -> Result<f64> {
+        let _querying = self.clock.lock_queries();
         let mut ec = [0 as i32];
         unsafe {
             let result = lsl_time_correction(self.handle, timeout, ec.as_mut_ptr());
@@ -1282,18 +3020,100 @@ impl StreamInlet {
        networks.
     */
     pub fn time_correction_ex(&self, timeout: f64) -> Result<(f64, f64, f64)> {
-        let mut ec = [0 as i32];
-        let mut retvals = [0.0, 0.0];
-        unsafe {
-            let result = lsl_time_correction_ex(
-                self.handle,
-                retvals[0..].as_mut_ptr(),
-                retvals[1..].as_mut_ptr(),
-                timeout,
-                ec.as_mut_ptr(),
-            );
-            errcode_to_result(ec[0])?;
-            Ok((result, retvals[0], retvals[1]))
+        self.clock.time_correction_ex(self.handle, timeout)
+    }
+
+    /**
+    Start recording a history of the time-correction measurements of the stream.
+
+    A background thread retrieves the latest measurement (see `time_correction_ex()`) every
+    `interval` seconds, and retains up to `capacity` of them, which can be retrieved at any time
+    via `time_correction_history()`. This allows to synchronize the recorded data offline later
+    on, in the same way as the LabRecorder does. Enabling the history again replaces the previous
+    one. The recording ends if the stream is lost (unless the inlet recovers it).
+
+    Arguments:
+    * `capacity`: The maximum number of retained measurements (the oldest ones are discarded).
+    * `interval`: The time between measurements, in seconds; the native library updates its
+      estimate every few seconds (5 by default), so shorter intervals gain nothing.
+    */
+    pub fn enable_time_correction_history(&mut self, capacity: usize, interval: f64) {
+        self.tc_history = None;
+        let history = TimeCorrectionHistory::default();
+        let logger = TimeCorrectionLogger {
+            inlet: NativeInlet(self.handle),
+            clock: self.clock.clone(),
+            history: history.clone(),
+            capacity: capacity.max(1),
+            interval,
+            last_remote_time: None,
+        };
+        self.tc_history = Some((history, worker::Worker::spawn(logger)));
+    }
+
+    /// Stop recording the time-correction history (and discard it).
+    pub fn disable_time_correction_history(&mut self) {
+        self.tc_history = None;
+    }
+
+    /**
+    The recorded time-correction measurements, oldest first (empty if the history is not
+    enabled, see `enable_time_correction_history()`).
+    */
+    pub fn time_correction_history(&self) -> vec::Vec<TimeCorrection> {
+        match &self.tc_history {
+            Some((history, _)) => lock(history).iter().copied().collect(),
+            None => vec![],
+        }
+    }
+
+    /**
+    Start reporting changes of the connection state of the inlet (see `ConnectionState`).
+
+    The native library recovers a stream that has a source id transparently, so that a consumer
+    would otherwise not notice the gap in the data. Once enabled, a background thread watches the
+    connection (via the time-correction measurements of the native library, which are refreshed
+    every few seconds), and queues a `ConnectionEvent` on each change, which can be retrieved via
+    `take_connection_events()` (e.g., once per processing cycle). Enabling the events again
+    discards the queued ones.
+
+    Arguments:
+    * `stall_after`: The time, in seconds, after which an outlet that has stopped answering is
+      reported as `ConnectionState::Recovering`; since the native library refreshes its
+      time-correction estimate every 5 seconds by default, this should be well above that.
+
+    A recovery that the native library flags as a potential clock reset (see `was_clock_reset()`,
+    which still reports it as well) is queued as a single `ConnectionState::ClockReset` event.
+    */
+    pub fn enable_connection_events(&mut self, stall_after: f64) {
+        self.connection_log = None;
+        let log = ConnectionLog::default();
+        let monitor = ConnectionMonitor {
+            inlet: NativeInlet(self.handle),
+            clock: self.clock.clone(),
+            log: log.clone(),
+            stall_after,
+            state: None,
+            seen_resets: self.clock.resets(self.handle),
+            last_remote_time: 0.0,
+            last_update: 0.0,
+        };
+        self.connection_log = Some((log, worker::Worker::spawn(monitor)));
+    }
+
+    /// Stop reporting changes of the connection state (and discard the queued events).
+    pub fn disable_connection_events(&mut self) {
+        self.connection_log = None;
+    }
+
+    /**
+    Take the connection events that have been queued since the last call, oldest first (empty if
+    the events are not enabled, see `enable_connection_events()`).
+    */
+    pub fn take_connection_events(&self) -> vec::Vec<ConnectionEvent> {
+        match &self.connection_log {
+            Some((log, _)) => lock(log).drain(..).collect(),
+            None => vec![],
         }
     }
 
@@ -1321,8 +3141,10 @@ impl StreamInlet {
         unsafe {
             let ec = lsl_set_postprocessing(self.handle, flags as u32);
             errcode_to_result(ec)?;
-            Ok(())
         }
+        self.postprocessing
+            .store(flags, sync::atomic::Ordering::Relaxed);
+        Ok(())
     }
 
     /**
@@ -1344,9 +3166,15 @@ impl StreamInlet {
     `time_correction` values to estimate precise clock drift; it allows to tolerate cases where
     the machine from which the stream is coming was hot-swapped or restarted in between two
     measurements.
+
+    Each reset is reported once by this method, independently of the connection events (see
+    `enable_connection_events()`), which observe the same resets.
     */
     pub fn was_clock_reset(&self) -> bool {
-        unsafe { lsl_was_clock_reset(self.handle) != 0 }
+        let resets = self.clock.resets(self.handle);
+        self.reported_resets
+            .swap(resets, sync::atomic::Ordering::Relaxed)
+            != resets
     }
 
     /**
@@ -1361,159 +3189,1090 @@ impl StreamInlet {
         unsafe {
             lsl_smoothing_halftime(self.handle, value as f32);
         }
+        *lock(&self.smoothing_halftime) = Some(value);
     }
 
-    // --- internal methods ---
-
-    /*
-    Internal helper to implement `pull_sample_buf()` safely for numeric value types, given a native
-    function to do the actual job.
-
-    Arguments:
-    * `func`: the native FFI function to call to pull a sample
-    * `buf`: a buffer to read into; will be resized if necessary
-    * `timeout`: the timeout to pass in
-
-    Returns the time stamp of the sample or 0.0 if no new data was available within the given
-    timeout.
+    /**
+    Switch the inlet to a different stream (e.g., a restarted one), in place.
+
+    This creates a new native inlet to the given stream, with the same arguments as the current
+    one (see `new()`), and carries over the settings of the inlet: the post-processing options,
+    the smoothing half-time, the time-correction history and connection events (if enabled;
+    the latter report `ConnectionState::Connected` once the new stream has answered), and the
+    other settings of the wrapper. Only once this has succeeded does the inlet switch
+    over, so that a consumer that owns it (e.g., a processing pipeline) can keep using it; on
+    error, the inlet remains bound to the current stream. Samples that were buffered for the old
+    stream but not yet pulled are discarded.
+
+    Returns an `Error::FormatMismatch` or `Error::LengthMismatch` if the new stream's channel
+    format or count differ from the current stream's, since the consumer would not expect them.
     */
-    fn safe_pull_numeric_buf<T: Clone + From<i8>>(
-        &self,
-        func: NativePullFunction<T>,
-        buf: &mut vec::Vec<T>,
-        timeout: f64,
-    ) -> Result<f64> {
-        let mut ec = [0 as i32];
-        if buf.len() != self.channel_count {
-            buf.resize(self.channel_count, T::from(0));
+    pub fn rebind(&mut self, info: &StreamInfo) -> Result<()> {
+        if info.channel_format() != self.channel_format {
+            return Err(Error::FormatMismatch);
         }
+        if info.channel_count() as usize != self.channel_count {
+            return Err(Error::LengthMismatch {
+                expected: self.channel_count,
+                actual: info.channel_count() as usize,
+            });
+        }
+        let mut new = StreamInlet::new(info, self.max_buflen, self.max_chunklen, self.recover)?;
+        let flags = self.postprocessing.load(sync::atomic::Ordering::Relaxed);
         unsafe {
-            let ts = func(
-                self.handle,
-                buf.as_mut_ptr(),
-                buf.len() as i32,
-                timeout,
-                ec.as_mut_ptr(),
-            );
-            errcode_to_result(ec[0])?;
-            Ok(ts)
+            errcode_to_result(lsl_set_postprocessing(new.handle, flags))?;
         }
+        if let Some(halftime) = *lock(&self.smoothing_halftime) {
+            new.smoothing_halftime(halftime);
+        }
+        // the background workers use the native inlet, so they are stopped while it is swapped
+        let tc_logger = self
+            .tc_history
+            .take()
+            .map(|(history, worker)| (history, worker.stop().0));
+        let monitor = self
+            .connection_log
+            .take()
+            .map(|(log, worker)| (log, worker.stop().0));
+        std::mem::swap(&mut self.handle, &mut new.handle);
+        std::mem::swap(&mut self.resolved, &mut new.resolved);
+        lock(&self.field_maps).clear();
+        self.tc_history = tc_logger.map(|(history, mut logger)| {
+            logger.inlet = NativeInlet(self.handle);
+            logger.last_remote_time = None;
+            (history, worker::Worker::spawn(logger))
+        });
+        self.connection_log = monitor.map(|(log, mut monitor)| {
+            monitor.inlet = NativeInlet(self.handle);
+            monitor.state = None;
+            monitor.seen_resets = self.clock.resets(self.handle);
+            (log, worker::Worker::spawn(monitor))
+        });
+        // (this destroys the old native inlet)
+        drop(new);
+        Ok(())
     }
 
-    /*
-    Internal helper to implement `pull_sample()` safely for numeric value types, given a native
-    function to do the actual job.
+    /**
+    Set how the channels of string-formatted streams are decoded when pulled as `String`.
 
-    Arguments:
-    * `func`: the native FFI function to call to pull a sample
-    * `timeout`: the timeout to pass in
+    By default, invalid UTF-8 sequences are replaced by the Unicode replacement character
+    (`StringDecoding::Lossy`), which can silently corrupt binary payloads. With
+    `StringDecoding::Strict`, such samples instead produce an `Error::InvalidUtf8`. To receive the
+    raw bytes, pull the data as `Vec<u8>` instead (or use `pull_sample_with()`).
     */
-    fn safe_pull_numeric<T: Clone + From<i8>>(
-        &self,
-        func: NativePullFunction<T>,
-        timeout: f64,
-    ) -> Result<(vec::Vec<T>, f64)> {
-        let mut result = vec![T::from(0); self.channel_count];
-        let ts = self.safe_pull_numeric_buf(func, &mut result, timeout)?;
-        if ts == 0.0 {
-            result.clear();
-        }
-        Ok((result, ts))
+    pub fn set_string_decoding(&mut self, mode: StringDecoding) {
+        self.string_decoding = mode;
     }
 
-    /*
-    Internal helper to implement `pull_sample_buf()` for types that can be be created from a
-    `&[u8]` slice of bytes.
+    /// The decoding mode for string-formatted channels (see `set_string_decoding()`).
+    pub fn string_decoding(&self) -> StringDecoding {
+        self.string_decoding
+    }
+
+    /**
+    Set how the pull calls wait for data when they are given a nonzero timeout.
+
+    By default, they block in the native library (`WaitStrategy::Block`); see `WaitStrategy` for
+    the trade-off of spinning instead. This affects the single-sample pulls (`pull_sample()`,
+    `pull_sample_buf()`, `pull_sample_with()` and `pull_sample_raw()`).
+    */
+    pub fn set_wait_strategy(&mut self, strategy: WaitStrategy) {
+        self.wait_strategy = strategy;
+    }
+
+    /// The strategy with which pull calls wait for data (see `set_wait_strategy()`).
+    pub fn wait_strategy(&self) -> WaitStrategy {
+        self.wait_strategy
+    }
+
+    /**
+    Enable or disable checking the requested value type against the stream's channel format.
+
+    By default, pulling numeric values from a string-formatted stream (or strings/blobs from a
+    numeric stream) yields an `Error::FormatMismatch`, since this is usually a mistake. If the
+    conversion is intentional (the native library converts between numbers and their string
+    representations), the check can be disabled here. Conversions between numeric types are
+    always allowed.
+    */
+    pub fn set_format_checking(&mut self, enabled: bool) {
+        self.format_checking = enabled;
+    }
+
+    /// Whether the requested value type is checked against the channel format.
+    pub fn format_checking(&self) -> bool {
+        self.format_checking
+    }
+
+    /// The channel format of the stream, as declared when the inlet was created.
+    pub fn channel_format(&self) -> ChannelFormat {
+        self.channel_format
+    }
+
+    /**
+    Pull the next sample of a string-formatted stream and pass its channels to a closure as
+    borrowed byte slices.
+
+    This is a variant of `pull_sample()` for blob data (e.g., video frames) that avoids copying
+    each channel into an owned `Vec<u8>` or `String`: the slices point directly into the buffers
+    handed out by the native library, and those are freed once the closure returns.
 
     Arguments:
-    * `mapper`: a function that converts a `&[u8]` to an owned copy of type `T`.
-    * `buf`: a buffer to read into; will be resized if necessary
-    * `timeout`: the timeout to pass to the native function
+    * `timeout`: The timeout for this operation, if any. If you use 0.0, the function will be
+      non-blocking. You can also use `lsl::FOREVER` to have no timeout.
+    * `f`: A closure that receives the channel contents (one slice per channel) and the capture
+      time of the sample on the remote side.
 
-    Returns the time stamp of the sample or 0.0 if no new data was available within the given
-    timeout.
+    Returns the value returned by the closure, or `None` if no new sample was available within
+    the given timeout (in which case the closure is not called).
     */
-    fn safe_pull_blob_buf<T: Clone>(
+    pub fn pull_sample_with<R, F: FnOnce(&[&[u8]], f64) -> R>(
         &self,
-        mapper: fn(&[u8]) -> T,
-        buf: &mut vec::Vec<T>,
         timeout: f64,
-    ) -> Result<f64> {
-        let mut ec = [0 as i32];
-        let mut ptrs = vec![0 as *mut ::std::os::raw::c_char; self.channel_count];
-        let mut lens = vec![0 as u32; self.channel_count];
+        f: F,
+    ) -> Result<Option<R>> {
+        self.check_format(true)?;
+        let mut ec = [0i32];
+        let mut strings = NativeStrings::new(self.channel_count);
+        let timeout = self.spin(timeout);
         unsafe {
             let ts = lsl_pull_sample_buf(
                 self.handle,
-                ptrs.as_mut_ptr(),
-                lens.as_mut_ptr(),
-                ptrs.len() as i32,
+                strings.ptrs.as_mut_ptr(),
+                strings.lens.as_mut_ptr(),
+                strings.ptrs.len() as i32,
                 timeout,
                 ec.as_mut_ptr(),
             );
             errcode_to_result(ec[0])?;
-            if buf.len() != self.channel_count {
-                buf.resize(self.channel_count, mapper(&[0 as u8; 0]));
-            }
-            if ts != 0.0 {
-                for k in 0..ptrs.len() {
-                    let slice = std::slice::from_raw_parts(ptrs[k] as *const u8, lens[k] as usize);
-                    buf[k] = mapper(slice);
-                    lsl_destroy_string(ptrs[k]);
-                }
+            if ts == 0.0 {
+                return Ok(None);
             }
-            Ok(ts)
+            let slices: Vec<&[u8]> = strings
+                .ptrs
+                .iter()
+                .zip(strings.lens.iter())
+                .map(|(&ptr, &len)| std::slice::from_raw_parts(ptr as *const u8, len as usize))
+                .collect();
+            Ok(Some(f(&slices, ts)))
         }
     }
 
-    /*
-    Internal helper to implement `pull_sample()` for types that can be be created from a
-    `&[u8]` slice of bytes.
+    /**
+    Pull the next sample into a raw byte buffer in the layout described by `raw_layout()`.
+
+    This is the counterpart of `StreamOutlet::push_sample_raw()`. The buffer must hold exactly one
+    sample, otherwise an `Error::LengthMismatch` is returned; the values are written back to back,
+    in the host's native byte order.
 
     Arguments:
-    * `mapper`: a function that converts a `&[u8]` to an owned copy of type `T`.
-    * `timeout`: the timeout to pass to the native function
+    * `buffer`: The buffer to receive the channel values.
+    * `timeout`: The timeout for this operation, if any. If you use 0.0, the function will be
+      non-blocking. You can also use `lsl::FOREVER` to have no timeout.
+
+    Returns the capture time of the sample on the remote side, or 0.0 if no new sample was
+    available within the given timeout (in which case the buffer is left unchanged).
     */
-    fn safe_pull_blob<T: Clone>(
-        &self,
-        mapper: fn(&[u8]) -> T,
-        timeout: f64,
-    ) -> Result<(vec::Vec<T>, f64)> {
-        let mut ec = [0 as i32];
-        let mut ptrs = vec![0 as *mut ::std::os::raw::c_char; self.channel_count];
-        let mut lens = vec![0 as u32; self.channel_count];
-        // we're not calling safe_pull_blob_buf here since that would make unnecessary allocations
-        // if there was no new data
+    pub fn pull_sample_raw(&self, buffer: &mut [u8], timeout: f64) -> Result<f64> {
+        self.raw_layout()?.check_len(buffer.len())?;
+        let mut ec = [0i32];
+        let timeout = self.spin(timeout);
         unsafe {
-            let ts = lsl_pull_sample_buf(
+            let ts = lsl_pull_sample_v(
                 self.handle,
-                ptrs.as_mut_ptr(),
-                lens.as_mut_ptr(),
-                ptrs.len() as i32,
+                buffer.as_mut_ptr() as *mut std::os::raw::c_void,
+                buffer.len() as i32,
                 timeout,
                 ec.as_mut_ptr(),
             );
             errcode_to_result(ec[0])?;
-            let mut sample = vec::Vec::<T>::new();
-            if ts != 0.0 {
-                for k in 0..ptrs.len() {
-                    let slice = std::slice::from_raw_parts(ptrs[k] as *const u8, lens[k] as usize);
-                    sample.push(mapper(slice));
-                    lsl_destroy_string(ptrs[k]);
-                }
-            }
-            Ok((sample, ts))
+            Ok(ts)
         }
     }
-}
 
-impl Drop for StreamInlet {
-    fn drop(&mut self) {
-        unsafe {
-            lsl_destroy_inlet(self.handle);
-        }
-    }
-}
+    /**
+    The layout of the samples of this inlet in raw byte buffers (see `RawLayout`).
+
+    Returns an `Error::FormatMismatch` for string-formatted streams.
+    */
+    pub fn raw_layout(&self) -> Result<RawLayout> {
+        RawLayout::new(self.channel_format, self.channel_count)
+    }
+
+    /**
+    Split the incoming samples into chunks at given times (see `TimeWindows`), e.g., to align
+    processing windows to the times of experiment events.
+    */
+    pub fn time_windows<T>(&self) -> TimeWindows<'_, T>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        TimeWindows {
+            inlet: self,
+            held: None,
+        }
+    }
+
+    /**
+    Pull the next sample into a struct (see `LslPull`).
+
+    If the struct's fields are matched to the channels by label, the first call for a given
+    struct type retrieves the stream's meta-data (within the given timeout) to look up the
+    labels, and returns an `Error::BadArgument` if any of them is missing, or an `Error::Timeout`
+    if the meta-data have not arrived in time (the native library keeps retrieving them in the
+    background, so that a later call can succeed). Otherwise, the struct must have as many fields
+    as the stream has channels (or an `Error::LengthMismatch` results).
+
+    Arguments:
+    * `timeout`: The timeout for this operation, if any. If you use 0.0, the function will be
+      non-blocking. You can also use `lsl::FOREVER` to have no timeout.
+
+    Returns the struct and the capture time of the sample, or `None` if no new sample was
+    available within the given timeout.
+    */
+    pub fn pull_as<S: LslPull + 'static>(&self, timeout: f64) -> Result<Option<(S, f64)>> {
+        let deadline = local_clock() + timeout;
+        let indices = self.field_indices::<S>(timeout)?;
+        let remaining = (deadline - local_clock()).max(0.0);
+        let (values, ts): (vec::Vec<S::Value>, f64) = self.pull_sample(remaining)?;
+        if ts == 0.0 {
+            return Ok(None);
+        }
+        Ok(Some((S::from_channels(&values, &indices), ts)))
+    }
+
+    /**
+    Pull the next sample as a `SampleValue`, whose variant is chosen according to the channel
+    format of the stream.
+
+    This allows to consume streams whose format is not known at compile time (e.g., in recording
+    applications) with a single code path. String-formatted streams are pulled as `String`s
+    (honoring the decoding mode set via `set_string_decoding()`).
+
+    Arguments:
+    * `timeout`: The timeout for this operation, if any. If you use 0.0, the function will be
+      non-blocking. You can also use `lsl::FOREVER` to have no timeout.
+
+    Returns the sample and its capture time, or `None` if no new sample was available within the
+    given timeout. Returns an `Error::UnsupportedFormat` if the stream's format cannot be pulled
+    on this platform.
+    */
+    pub fn pull_sample_dynamic(&self, timeout: f64) -> Result<Option<(SampleValue, f64)>> {
+        fn pull<T>(
+            inlet: &StreamInlet,
+            timeout: f64,
+            variant: fn(vec::Vec<T>) -> SampleValue,
+        ) -> Result<(SampleValue, f64)>
+        where
+            StreamInlet: Pullable<T>,
+        {
+            let (values, ts) = inlet.pull_sample(timeout)?;
+            Ok((variant(values), ts))
+        }
+        let (value, ts) = match self.channel_format {
+            ChannelFormat::Float32 => pull(self, timeout, SampleValue::F32)?,
+            ChannelFormat::Double64 => pull(self, timeout, SampleValue::F64)?,
+            ChannelFormat::Int8 => pull(self, timeout, SampleValue::I8)?,
+            ChannelFormat::Int16 => pull(self, timeout, SampleValue::I16)?,
+            ChannelFormat::Int32 => pull(self, timeout, SampleValue::I32)?,
+            ChannelFormat::Int64 => pull(self, timeout, SampleValue::I64)?,
+            ChannelFormat::String => pull(self, timeout, SampleValue::Str)?,
+            _ => return Err(Error::UnsupportedFormat),
+        };
+        if ts == 0.0 {
+            return Ok(None);
+        }
+        Ok(Some((value, ts)))
+    }
+
+    /**
+    Forward the samples of the inlet into a bounded channel, from which they can be received.
+
+    The samples are pulled by a background thread, which holds up to `capacity` samples in the
+    channel (further samples remain buffered in the inlet). When pulling fails (e.g., with an
+    `Error::StreamLost`), the error is received as the last item, and the channel is closed.
+    Dropping the returned receiver stops the thread and closes the inlet (see
+    `bridge::SampleReceiver` for details).
+    */
+    pub fn into_channel<T: Send + 'static>(self, capacity: usize) -> bridge::SampleReceiver<T>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        bridge::SampleReceiver::new(self, capacity)
+    }
+
+    /**
+    Turn the inlet into an asynchronous stream of samples (requires the `futures` feature).
+
+    The samples are pulled by a background thread; see `lsl::stream` for details.
+    */
+    #[cfg(feature = "futures")]
+    pub fn into_stream<T: Send + 'static>(self) -> stream::SampleStream<T>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        stream::SampleStream::new(self)
+    }
+
+    /**
+    Iterate over the samples that are currently buffered in the inlet.
+
+    The samples are pulled lazily (one at a time) as the iterator advances, and the iteration
+    stops as soon as no more data is immediately available; samples that were not consumed remain
+    buffered. This allows consumers to process the backlog incrementally and to stop early
+    without first materializing it as a chunk.
+
+    ```no_run
+    # fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    for sample in inlet.iter_available::<f32>() {
+        let sample = sample?;
+        if sample.data[0] > 100.0 {
+            break;
+        }
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn iter_available<T>(&self) -> AvailableSamples<'_, T>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        AvailableSamples {
+            inlet: self,
+            done: false,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /**
+    Iterate over the incoming samples, blocking until each one arrives, until cancelled.
+
+    A `worker::Handle` serves as the cancellation token: once `stop()` has been called on it (or
+    on any of its clones, e.g., from another thread), the iteration ends within
+    `worker::MAX_BLOCK` seconds, so that long-running reader threads can be shut down without
+    waiting for a `FOREVER` timeout to expire. After an error has been yielded, the iterator ends.
+
+    ```no_run
+    # fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true)?;
+    let cancel = lsl::worker::Handle::new();
+    let reader_cancel = cancel.clone();
+    let reader = std::thread::spawn(move || {
+        for sample in inlet.samples::<f32>(&reader_cancel) {
+            println!("{:?}", sample?.data);
+        }
+        Ok::<(), lsl::Error>(())
+    });
+    // ... later
+    cancel.stop();
+    reader.join().unwrap()?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn samples<'a, T>(&'a self, cancel: &'a worker::Handle) -> Samples<'a, T>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        Samples {
+            inlet: self,
+            cancel,
+            done: false,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    // --- internal methods ---
+
+    /*
+    Internal helper to implement `pull_sample_buf()` safely for numeric value types, given a native
+    function to do the actual job.
+
+    Arguments:
+    * `func`: the native FFI function to call to pull a sample
+    * `buf`: a buffer to read into; will be resized if necessary
+    * `timeout`: the timeout to pass in
+
+    Returns the time stamp of the sample or 0.0 if no new data was available within the given
+    timeout.
+    */
+    fn safe_pull_numeric_buf<T: Clone + From<i8>>(
+        &self,
+        func: NativePullFunction<T>,
+        buf: &mut vec::Vec<T>,
+        timeout: f64,
+    ) -> Result<f64> {
+        self.check_format(false)?;
+        let mut ec = [0 as i32];
+        if buf.len() != self.channel_count {
+            buf.resize(self.channel_count, T::from(0));
+        }
+        let timeout = self.spin(timeout);
+        unsafe {
+            let ts = func(
+                self.handle,
+                buf.as_mut_ptr(),
+                buf.len() as i32,
+                timeout,
+                ec.as_mut_ptr(),
+            );
+            errcode_to_result(ec[0])?;
+            Ok(ts)
+        }
+    }
+
+    /*
+    Internal helper to implement `pull_sample()` safely for numeric value types, given a native
+    function to do the actual job.
+
+    Arguments:
+    * `func`: the native FFI function to call to pull a sample
+    * `timeout`: the timeout to pass in
+    */
+    fn safe_pull_numeric<T: Clone + From<i8>>(
+        &self,
+        func: NativePullFunction<T>,
+        timeout: f64,
+    ) -> Result<(vec::Vec<T>, f64)> {
+        let mut result = vec![T::from(0); self.channel_count];
+        let ts = self.safe_pull_numeric_buf(func, &mut result, timeout)?;
+        if ts == 0.0 {
+            result.clear();
+        }
+        Ok((result, ts))
+    }
+
+    /*
+    Internal helper to implement `pull_latest()` for numeric value types, given a native chunk
+    pull function to drain the buffered samples with.
+    */
+    fn safe_pull_latest_numeric<T: Clone + From<i8>>(
+        &self,
+        func: NativePullChunkFunction<T>,
+    ) -> Result<Option<(Sample<T>, usize)>> {
+        const BATCH_SAMPLES: usize = 256;
+        self.check_format(false)?;
+        let mut latest = None;
+        let mut discarded = 0;
+        let n = self.channel_count;
+        let mut data = vec![T::from(0); BATCH_SAMPLES * n];
+        let mut stamps = vec![0.0; BATCH_SAMPLES];
+        loop {
+            let mut ec = [0i32];
+            let elements = unsafe {
+                func(
+                    self.handle,
+                    data.as_mut_ptr(),
+                    stamps.as_mut_ptr(),
+                    data.len() as c_ulong,
+                    stamps.len() as c_ulong,
+                    0.0,
+                    ec.as_mut_ptr(),
+                )
+            };
+            errcode_to_result(ec[0])?;
+            let count = elements as usize / n.max(1);
+            if count == 0 {
+                break;
+            }
+            discarded += count - 1 + latest.is_some() as usize;
+            latest = Some(Sample {
+                data: data[(count - 1) * n..count * n].to_vec(),
+                timestamp: stamps[count - 1],
+            });
+            if count < BATCH_SAMPLES {
+                break; // drained
+            }
+        }
+        Ok(latest.map(|x| (x, discarded)))
+    }
+
+    /*
+    Internal helper to implement `pull_sample_buf()` for types that can be be created from a
+    `&[u8]` slice of bytes.
+
+    Arguments:
+    * `mapper`: a function that converts a `&[u8]` to an owned copy of type `T` (or fails).
+    * `buf`: a buffer to read into; will be resized if necessary
+    * `timeout`: the timeout to pass to the native function
+
+    Returns the time stamp of the sample or 0.0 if no new data was available within the given
+    timeout.
+    */
+    fn safe_pull_blob_buf<T: Clone>(
+        &self,
+        mapper: impl Fn(&[u8]) -> Result<T>,
+        buf: &mut vec::Vec<T>,
+        timeout: f64,
+    ) -> Result<f64> {
+        self.check_format(true)?;
+        if buf.len() != self.channel_count {
+            buf.resize(self.channel_count, mapper(&[])?);
+        }
+        let pulled = self.pull_sample_with(timeout, |slices, ts| {
+            for (dst, src) in buf.iter_mut().zip(slices) {
+                *dst = mapper(src)?;
+            }
+            Ok(ts)
+        })?;
+        pulled.unwrap_or(Ok(0.0))
+    }
+
+    /*
+    Internal helper to implement `pull_sample()` for types that can be be created from a
+    `&[u8]` slice of bytes.
+
+    Arguments:
+    * `mapper`: a function that converts a `&[u8]` to an owned copy of type `T` (or fails).
+    * `timeout`: the timeout to pass to the native function
+    */
+    fn safe_pull_blob<T>(
+        &self,
+        mapper: impl Fn(&[u8]) -> Result<T>,
+        timeout: f64,
+    ) -> Result<(vec::Vec<T>, f64)> {
+        self.check_format(true)?;
+        // we're not calling safe_pull_blob_buf here since that would make unnecessary allocations
+        // if there was no new data
+        let pulled = self.pull_sample_with(timeout, |slices, ts| {
+            let sample = slices
+                .iter()
+                .map(|x| mapper(x))
+                .collect::<Result<vec::Vec<T>>>()?;
+            Ok((sample, ts))
+        })?;
+        pulled.unwrap_or_else(|| Ok((vec![], 0.0)))
+    }
+
+    // The channel index of each field of an `LslPull` struct.
+    fn field_indices<S: LslPull + 'static>(&self, timeout: f64) -> Result<vec::Vec<usize>> {
+        if !S::BY_LABEL {
+            if S::LABELS.len() != self.channel_count {
+                return Err(Error::LengthMismatch {
+                    expected: self.channel_count,
+                    actual: S::LABELS.len(),
+                });
+            }
+            return Ok((0..self.channel_count).collect());
+        }
+        let maps = || self.field_maps.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(indices) = maps().get(&any::TypeId::of::<S>()) {
+            return Ok(indices.clone());
+        }
+        // (the meta-data are retrieved without holding the lock)
+        let indices = channels::indices_of(&self.info(timeout)?, S::LABELS)?;
+        maps().insert(any::TypeId::of::<S>(), indices.clone());
+        Ok(indices)
+    }
+
+    // Spin-wait for data according to the wait strategy, and return the remaining timeout for
+    // the subsequent (blocking) native pull.
+    fn spin(&self, timeout: f64) -> f64 {
+        let spin = match self.wait_strategy {
+            WaitStrategy::SpinThenBlock(spin) if timeout > 0.0 => spin.min(timeout),
+            _ => return timeout,
+        };
+        let start = local_clock();
+        loop {
+            let elapsed = local_clock() - start;
+            if elapsed >= spin || unsafe { lsl_samples_available(self.handle) } > 0 {
+                return (timeout - elapsed).max(0.0);
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    // Check whether values of a string (or numeric) type may be pulled from the stream.
+    fn check_format(&self, string_type: bool) -> Result<()> {
+        let is_string = self.channel_format == ChannelFormat::String;
+        if self.format_checking
+            && self.channel_format != ChannelFormat::Undefined
+            && is_string != string_type
+        {
+            return Err(Error::FormatMismatch);
+        }
+        Ok(())
+    }
+
+    // Decode the contents of a string-typed channel according to the configured decoding mode.
+    fn decode_string(&self, bytes: &[u8]) -> Result<String> {
+        match self.string_decoding {
+            StringDecoding::Lossy => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            StringDecoding::Strict => match std::str::from_utf8(bytes) {
+                Ok(s) => Ok(s.to_owned()),
+                Err(_) => Err(Error::InvalidUtf8),
+            },
+        }
+    }
+}
+
+/// The stream information returned by `StreamInlet::info_or_core()`.
+#[derive(Debug)]
+pub struct InfoFallback {
+    /// The stream information, with or without the extended description.
+    pub info: StreamInfo,
+    /// Whether the extended description is included (false if it could not be retrieved in time).
+    pub complete: bool,
+}
+
+/// Startup timings of an inlet, as measured by `StreamInlet::open_and_measure()`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct OpenMeasurement {
+    /// Time, in seconds, that it took to subscribe to the stream.
+    pub open_duration: f64,
+    /// Time, in seconds, from the start of the operation until the first sample was available.
+    pub first_sample_latency: f64,
+    /// The initial time-correction estimate (see `StreamInlet::time_correction()`), or `None` if
+    /// none was obtained within the timeout.
+    pub time_correction: Option<f64>,
+    /// The first sample, along with its time stamp (see `StreamInlet::pull_sample_dynamic()`).
+    pub first_sample: (SampleValue, f64),
+}
+
+/// A sample that was pulled from an inlet, along with its time stamp.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Sample<T> {
+    /// The values of the sample (one per channel).
+    pub data: vec::Vec<T>,
+    /// The capture time of the sample on the remote side.
+    pub timestamp: f64,
+}
+
+/**
+The values of a sample of any channel format, as returned by `StreamInlet::pull_sample_dynamic()`.
+
+There is one variant per channel format; the variant's vector holds one value per channel.
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub enum SampleValue {
+    /// Values of a `ChannelFormat::Float32` stream.
+    F32(vec::Vec<f32>),
+    /// Values of a `ChannelFormat::Double64` stream.
+    F64(vec::Vec<f64>),
+    /// Values of a `ChannelFormat::Int8` stream.
+    I8(vec::Vec<i8>),
+    /// Values of a `ChannelFormat::Int16` stream.
+    I16(vec::Vec<i16>),
+    /// Values of a `ChannelFormat::Int32` stream.
+    I32(vec::Vec<i32>),
+    /// Values of a `ChannelFormat::Int64` stream.
+    I64(vec::Vec<i64>),
+    /// Values of a `ChannelFormat::String` stream.
+    Str(vec::Vec<String>),
+}
+
+impl SampleValue {
+    /// The channel format that corresponds to this variant.
+    pub fn channel_format(&self) -> ChannelFormat {
+        match self {
+            SampleValue::F32(_) => ChannelFormat::Float32,
+            SampleValue::F64(_) => ChannelFormat::Double64,
+            SampleValue::I8(_) => ChannelFormat::Int8,
+            SampleValue::I16(_) => ChannelFormat::Int16,
+            SampleValue::I32(_) => ChannelFormat::Int32,
+            SampleValue::I64(_) => ChannelFormat::Int64,
+            SampleValue::Str(_) => ChannelFormat::String,
+        }
+    }
+
+    /// The number of channels.
+    pub fn len(&self) -> usize {
+        match self {
+            SampleValue::F32(x) => x.len(),
+            SampleValue::F64(x) => x.len(),
+            SampleValue::I8(x) => x.len(),
+            SampleValue::I16(x) => x.len(),
+            SampleValue::I32(x) => x.len(),
+            SampleValue::I64(x) => x.len(),
+            SampleValue::Str(x) => x.len(),
+        }
+    }
+
+    /// Whether the sample has no channels.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+    Convert the values of a numeric sample to `f64` (note that `i64` values beyond 53 bits lose
+    precision). Returns `None` for string samples.
+    */
+    pub fn to_f64(&self) -> Option<vec::Vec<f64>> {
+        match self {
+            SampleValue::F32(x) => Some(x.iter().map(|&v| v as f64).collect()),
+            SampleValue::F64(x) => Some(x.clone()),
+            SampleValue::I8(x) => Some(x.iter().map(|&v| v as f64).collect()),
+            SampleValue::I16(x) => Some(x.iter().map(|&v| v as f64).collect()),
+            SampleValue::I32(x) => Some(x.iter().map(|&v| v as f64).collect()),
+            SampleValue::I64(x) => Some(x.iter().map(|&v| v as f64).collect()),
+            SampleValue::Str(_) => None,
+        }
+    }
+}
+
+/**
+An iterator over the samples that are currently buffered in an inlet.
+
+Created by `StreamInlet::iter_available()`. After an error has been yielded, the iterator ends.
+*/
+#[derive(Debug)]
+pub struct AvailableSamples<'a, T> {
+    inlet: &'a StreamInlet,
+    done: bool,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> Iterator for AvailableSamples<'a, T>
+where
+    StreamInlet: Pullable<T>,
+{
+    type Item = Result<Sample<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.inlet.pull_sample(0.0) {
+            Ok((data, timestamp)) if timestamp != 0.0 => Some(Ok(Sample { data, timestamp })),
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/**
+A blocking iterator over the incoming samples of an inlet, which ends when it is cancelled.
+
+Created by `StreamInlet::samples()`.
+*/
+#[derive(Debug)]
+pub struct Samples<'a, T> {
+    inlet: &'a StreamInlet,
+    cancel: &'a worker::Handle,
+    done: bool,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> Iterator for Samples<'a, T>
+where
+    StreamInlet: Pullable<T>,
+{
+    type Item = Result<Sample<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done && !self.cancel.is_stopped() {
+            match self.inlet.pull_sample(worker::MAX_BLOCK) {
+                Ok((data, timestamp)) if timestamp != 0.0 => {
+                    return Some(Ok(Sample { data, timestamp }))
+                }
+                Ok(_) => continue,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/**
+Pulls the samples of an inlet in chunks that end at given times.
+
+Created by `StreamInlet::time_windows()`. Since the native library cannot return a sample to the
+inlet's buffer, the first sample that is at or after the end of a chunk is held back by the
+`TimeWindows`, and returned at the start of the next chunk; it is lost if the `TimeWindows` is
+dropped without calling `into_held()`.
+*/
+#[derive(Debug)]
+pub struct TimeWindows<'a, T> {
+    inlet: &'a StreamInlet,
+    // the first sample (and its time stamp) that was not part of the previous chunk
+    held: Option<(vec::Vec<T>, f64)>,
+}
+
+impl<'a, T> TimeWindows<'a, T>
+where
+    StreamInlet: Pullable<T>,
+{
+    /**
+    Pull a chunk of the buffered samples whose time stamps are earlier than a given time.
+
+    This returns the samples that are buffered in the inlet (in the same way as `pull_chunk()`),
+    but stops at the first sample whose time stamp is at or after `t`; that sample and all later
+    ones are returned by subsequent calls.
+
+    Arguments:
+    * `t`: The time (in the time domain of the returned time stamps, e.g., as remapped by
+      `StreamInlet::set_postprocessing()`) before which samples shall be returned.
+    */
+    pub fn pull_chunk_until(&mut self, t: f64) -> Result<(vec::Vec<vec::Vec<T>>, vec::Vec<f64>)> {
+        let mut samples: vec::Vec<vec::Vec<T>> = vec![];
+        let mut stamps: vec::Vec<f64> = vec![];
+        loop {
+            let (sample, stamp) = match self.held.take() {
+                Some(held) => held,
+                None => self.inlet.pull_sample(0.0)?,
+            };
+            if stamp == 0.0 {
+                break; // no more data
+            }
+            if stamp >= t {
+                self.held = Some((sample, stamp));
+                break;
+            }
+            samples.push(sample);
+            stamps.push(stamp);
+        }
+        Ok((samples, stamps))
+    }
+
+    /// The inlet that the samples are pulled from.
+    pub fn inlet(&self) -> &'a StreamInlet {
+        self.inlet
+    }
+
+    /// The sample that is held back for the next chunk, if any.
+    pub fn into_held(self) -> Option<Sample<T>> {
+        self.held
+            .map(|(data, timestamp)| Sample { data, timestamp })
+    }
+}
+
+impl Drop for StreamInlet {
+    fn drop(&mut self) {
+        // stops the time-correction logger and the connection monitor, which use the native inlet
+        self.tc_history = None;
+        self.connection_log = None;
+        unsafe {
+            lsl_destroy_inlet(self.handle);
+        }
+    }
+}
+
+/**
+A time-correction measurement of a stream, as recorded by
+`StreamInlet::enable_time_correction_history()` (see `StreamInlet::time_correction_ex()`).
+*/
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct TimeCorrection {
+    /// The remote time at which the measurement was made.
+    pub remote_time: f64,
+    /// The offset to add to remote time stamps to map them into the local clock domain.
+    pub offset: f64,
+    /// The round-trip time of the measurement, which bounds the uncertainty of the offset.
+    pub rtt: f64,
+}
+
+type TimeCorrectionHistory = sync::Arc<sync::Mutex<std::collections::VecDeque<TimeCorrection>>>;
+
+// What an inlet shares with its background workers about the remote clock: the native library
+// clears its clock-reset flag whenever it is queried, so the flag is only ever queried here, and
+// counted, so that each observer can tell the resets that it has not seen yet; and the
+// time-correction queries, which are serialized (rather than made concurrently from several
+// threads).
+#[derive(Debug, Default)]
+struct ClockObserver {
+    resets: sync::atomic::AtomicU64,
+    queries: sync::Mutex<()>,
+}
+
+impl ClockObserver {
+    // the number of clock resets of the given native inlet so far
+    fn resets(&self, inlet: lsl_inlet) -> u64 {
+        let _querying = self.lock_queries();
+        match unsafe { lsl_was_clock_reset(inlet) != 0 } {
+            true => self.resets.fetch_add(1, sync::atomic::Ordering::Relaxed) + 1,
+            false => self.resets.load(sync::atomic::Ordering::Relaxed),
+        }
+    }
+
+    fn lock_queries(&self) -> sync::MutexGuard<'_, ()> {
+        self.queries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn time_correction_ex(&self, inlet: lsl_inlet, timeout: f64) -> Result<(f64, f64, f64)> {
+        let _querying = self.lock_queries();
+        let (mut remote_time, mut rtt, mut ec) = (0.0, 0.0, 0);
+        let offset =
+            unsafe { lsl_time_correction_ex(inlet, &mut remote_time, &mut rtt, timeout, &mut ec) };
+        errcode_to_result(ec)?;
+        Ok((offset, remote_time, rtt))
+    }
+}
+
+// the native handle of an inlet, for use by a background thread
+#[derive(Debug)]
+struct NativeInlet(lsl_inlet);
+
+// time corrections can be queried concurrently with pulling data (the native library measures
+// them on a separate connection), and the logger and the connection monitor are stopped before
+// the inlet is destroyed
+unsafe impl Send for NativeInlet {}
+
+// The task that records the time-correction history of an inlet.
+#[derive(Debug)]
+struct TimeCorrectionLogger {
+    inlet: NativeInlet,
+    clock: sync::Arc<ClockObserver>,
+    history: TimeCorrectionHistory,
+    capacity: usize,
+    interval: f64,
+    last_remote_time: Option<f64>,
+}
+
+impl worker::Task for TimeCorrectionLogger {
+    fn run_once(&mut self, handle: &worker::Handle) -> Result<()> {
+        let (offset, remote_time, rtt) = match self
+            .clock
+            .time_correction_ex(self.inlet.0, worker::MAX_BLOCK)
+        {
+            Ok(measurement) => measurement,
+            // the first measurement is still in progress
+            Err(Error::Timeout) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        // only record new measurements
+        if self.last_remote_time != Some(remote_time) {
+            self.last_remote_time = Some(remote_time);
+            let mut history = lock(&self.history);
+            if history.len() >= self.capacity {
+                history.pop_front();
+            }
+            history.push_back(TimeCorrection {
+                remote_time,
+                offset,
+                rtt,
+            });
+        }
+        handle.wait(self.interval);
+        Ok(())
+    }
+}
+
+/// The state of the connection of an inlet to its stream (see `ConnectionEvent`).
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ConnectionState {
+    /// The inlet has connected to the stream (i.e., the outlet has answered for the first time).
+    Connected,
+    /// The outlet has stopped answering, and the inlet is trying to recover the stream; the data
+    /// will have a gap.
+    Recovering,
+    /// The inlet has reconnected to the stream.
+    Recovered,
+    /// The inlet has reconnected to the stream, and the native library has flagged that its clock
+    /// may have been reset (e.g., because the outlet was restarted; see
+    /// `StreamInlet::was_clock_reset()`).
+    ClockReset,
+    /// The stream has been lost for good (i.e., it cannot be recovered, see `StreamInlet::new()`);
+    /// no further events follow.
+    Lost,
+}
+
+/**
+A change of the connection state of an inlet, as reported by
+`StreamInlet::take_connection_events()`.
+*/
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ConnectionEvent {
+    /// The new state.
+    pub state: ConnectionState,
+    /// When the change was detected (in agreement with `lsl::local_clock()`).
+    pub time: f64,
+}
+
+type ConnectionLog = sync::Arc<sync::Mutex<std::collections::VecDeque<ConnectionEvent>>>;
+
+// the interval, in seconds, at which a `ConnectionMonitor` checks the connection
+const CONNECTION_POLL: f64 = 0.5;
+
+// The task that watches the connection of an inlet.
+#[derive(Debug)]
+struct ConnectionMonitor {
+    inlet: NativeInlet,
+    clock: sync::Arc<ClockObserver>,
+    log: ConnectionLog,
+    stall_after: f64,
+    // the last reported state
+    state: Option<ConnectionState>,
+    // the number of clock resets that have been reported
+    seen_resets: u64,
+    // the remote time of the last time-correction measurement, and when it was first seen
+    last_remote_time: f64,
+    last_update: f64,
+}
+
+impl ConnectionMonitor {
+    fn report(&mut self, state: ConnectionState) {
+        self.state = Some(state);
+        lock(&self.log).push_back(ConnectionEvent {
+            state,
+            time: local_clock(),
+        });
+    }
+}
+
+impl worker::Task for ConnectionMonitor {
+    fn run_once(&mut self, handle: &worker::Handle) -> Result<()> {
+        let measurement = self
+            .clock
+            .time_correction_ex(self.inlet.0, worker::MAX_BLOCK);
+        let now = local_clock();
+        // the native library flags a potential clock reset when it has recovered the stream,
+        // which may happen before a stall has been noticed
+        let resets = self.clock.resets(self.inlet.0);
+        let reset = resets != self.seen_resets && self.state.is_some();
+        self.seen_resets = resets;
+        match measurement {
+            Err(Error::StreamLost) => {
+                self.report(ConnectionState::Lost);
+                handle.stop();
+                return Ok(());
+            }
+            // no measurement yet (e.g., the outlet has not answered so far)
+            Err(Error::Timeout) => {}
+            Err(e) => return Err(e),
+            // a new measurement means that the outlet has answered
+            Ok((_, remote_time, _)) if remote_time != self.last_remote_time => {
+                self.last_remote_time = remote_time;
+                self.last_update = now;
+                match self.state {
+                    None => self.report(ConnectionState::Connected),
+                    Some(ConnectionState::Recovering) if !reset => {
+                        self.report(ConnectionState::Recovered)
+                    }
+                    _ => {}
+                }
+            }
+            Ok(_) => {}
+        }
+        if reset {
+            self.last_update = now;
+            self.report(ConnectionState::ClockReset);
+        }
+        let stalled = now - self.last_update > self.stall_after;
+        if stalled && self.state.is_some() && self.state != Some(ConnectionState::Recovering) {
+            self.report(ConnectionState::Recovering);
+        }
+        handle.wait(CONNECTION_POLL);
+        Ok(())
+    }
+}
+
+// the native inlet is not tied to the thread that created it, so it can be moved across threads
+// (e.g., to a blocking worker thread of an async runtime); it is not `Sync`, since concurrent pulls
+// from several threads are not safe unless `ProcessingOption::Threadsafe` is set
+unsafe impl Send for StreamInlet {}
 
 /**
 A trait that enables the methods `pull_sample<T>()` and `pull_chunk<T>()`.
@@ -1592,86 +4351,408 @@ pub trait Pullable<T> {
         }
         Ok((samples, stamps))
     }
+
+    /**
+    Pull only the most recent of the buffered samples, discarding all older ones.
+
+    This is meant for display or feedback loops that only care about the current value. For
+    numeric value types, the backlog is drained efficiently via the native chunk pull.
+
+    Returns the newest sample together with the number of older samples that were discarded, or
+    `None` if no new sample was available.
+    */
+    fn pull_latest(&self) -> Result<Option<(Sample<T>, usize)>> {
+        let mut latest = None;
+        let mut discarded = 0;
+        loop {
+            let (data, timestamp) = self.pull_sample(0.0)?;
+            if timestamp == 0.0 {
+                break; // no more data
+            }
+            if latest.is_some() {
+                discarded += 1;
+            }
+            latest = Some(Sample { data, timestamp });
+        }
+        Ok(latest.map(|x| (x, discarded)))
+    }
 }
 
-impl Pullable<f32> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<f32>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_f, timeout)
+impl<T: LslSample> Pullable<T> for StreamInlet {
+    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<T>, f64)> {
+        self.safe_pull_numeric(T::PULL_SAMPLE, timeout)
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<f32>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_f, buf, timeout)
+    fn pull_sample_buf(&self, buf: &mut vec::Vec<T>, timeout: f64) -> Result<f64> {
+        self.safe_pull_numeric_buf(T::PULL_SAMPLE, buf, timeout)
+    }
+
+    fn pull_latest(&self) -> Result<Option<(Sample<T>, usize)>> {
+        self.safe_pull_latest_numeric(T::PULL_CHUNK)
     }
 }
 
-impl Pullable<f64> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<f64>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_d, timeout)
+impl Pullable<String> for StreamInlet {
+    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<String>, f64)> {
+        self.safe_pull_blob(|x| self.decode_string(x), timeout)
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<f64>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_d, buf, timeout)
+    fn pull_sample_buf(&self, buf: &mut vec::Vec<String>, timeout: f64) -> Result<f64> {
+        self.safe_pull_blob_buf(|x| self.decode_string(x), buf, timeout)
     }
 }
 
-#[cfg(not(windows))] // TODO: once we upgrade to liblsl 1.14, we can drop this platform restriction
-impl Pullable<i64> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<i64>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_l, timeout)
+impl Pullable<vec::Vec<u8>> for StreamInlet {
+    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<vec::Vec<u8>>, f64)> {
+        self.safe_pull_blob(|x| Ok(x.to_vec()), timeout)
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<i64>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_l, buf, timeout)
+    fn pull_sample_buf(&self, buf: &mut vec::Vec<vec::Vec<u8>>, timeout: f64) -> Result<f64> {
+        self.safe_pull_blob_buf(|x| Ok(x.to_vec()), buf, timeout)
     }
 }
 
-impl Pullable<i32> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<i32>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_i, timeout)
-    }
+/**
+A policy for retrying operations that time out, with exponential backoff.
+
+The delay before the `n`-th retry is `initial_delay * multiplier^(n-1)`, at most `max_delay`,
+and varied randomly by up to `jitter` times itself (so that many clients that failed at the same
+time do not retry in lockstep). Set it on an inlet via `StreamInlet::set_retry_policy()` or
+`InletBuilder::open_with()`.
+*/
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one; defaults to 5.
+    pub max_attempts: u32,
+    /// The delay before the first retry, in seconds; defaults to 0.5.
+    pub initial_delay: f64,
+    /// The factor by which the delay grows with each retry; defaults to 2.0.
+    pub multiplier: f64,
+    /// The maximum delay between two attempts, in seconds; defaults to 10.0.
+    pub max_delay: f64,
+    /// The fraction (between 0 and 1) by which each delay is varied randomly; defaults to 0.1.
+    pub jitter: f64,
+}
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<i32>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_i, buf, timeout)
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: 0.5,
+            multiplier: 2.0,
+            max_delay: 10.0,
+            jitter: 0.1,
+        }
     }
 }
 
-impl Pullable<i16> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<i16>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_s, timeout)
+impl RetryPolicy {
+    /// The delay, in seconds, before the given retry (starting at 1), without the jitter.
+    pub fn delay(&self, retry: u32) -> f64 {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay * self.multiplier.powi(exponent);
+        delay.min(self.max_delay).max(0.0)
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<i16>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_s, buf, timeout)
+    /**
+    Run an operation, and run it again after a delay whenever it fails with an `Error::Timeout`,
+    up to `max_attempts` times in total. Other errors are returned right away.
+    */
+    pub fn retry<R>(&self, mut op: impl FnMut() -> Result<R>) -> Result<R> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(Error::Timeout) if attempt < self.max_attempts => {}
+                result => return result,
+            }
+            // a random factor in [1 - jitter, 1 + jitter]
+            let random = (random_u64() as f64 / u64::MAX as f64) * 2.0 - 1.0;
+            let jitter = self.jitter.clamp(0.0, 1.0);
+            let delay = self.delay(attempt) * (1.0 + jitter * random);
+            std::thread::sleep(std::time::Duration::from_secs_f64(delay.max(0.0)));
+            attempt += 1;
+        }
     }
 }
 
-impl Pullable<i8> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<i8>, f64)> {
-        self.safe_pull_numeric(lsl_pull_sample_c, timeout)
+// A random number (good enough for jitter), from the randomly seeded hasher of the standard
+// library.
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u64(local_clock().to_bits());
+    hasher.finish()
+}
+
+// =======================
+// ==== Inlet Builder ====
+// =======================
+
+/**
+A builder that resolves a stream and opens an inlet to it in one call.
+
+This collapses the usual resolve-then-create boilerplate, with the recommended defaults for the
+inlet's arguments:
+
+```no_run
+use lsl::{InletBuilder, Pullable};
+
+fn main() -> Result<(), lsl::Error> {
+    let inlet = InletBuilder::resolve("type", "EEG").timeout(5.0).buffered(360).open()?;
+    let (sample, ts): (Vec<f32>, _) = inlet.pull_sample(lsl::FOREVER)?;
+    println!("got {:?} at time {}", sample, ts);
+    Ok(())
+}
+```
+
+### Errors
+
+`open()` returns an `Error::Timeout` if no matching stream was found within the timeout, and
+otherwise passes on the errors of the individual steps (e.g., an `Error::ResourceCreation` if the
+inlet could not be created).
+*/
+#[derive(Clone, Debug)]
+pub struct InletBuilder {
+    query: Query,
+    timeout: f64,
+    max_buflen: i32,
+    // the buffer size in explicit units, which overrides `max_buflen` if given
+    buffer: Option<BufferSpec>,
+    max_chunklen: i32,
+    recover: bool,
+    postprocessing: vec::Vec<ProcessingOption>,
+    open_stream: bool,
+    retry_policy: Option<RetryPolicy>,
+}
+
+// what to resolve
+#[derive(Clone, Debug)]
+enum Query {
+    Prop(String, String),
+    Pred(String),
+}
+
+impl InletBuilder {
+    /// Resolve a stream that has a specific value for a given property (see `resolve_byprop()`).
+    pub fn resolve<'a, P: Into<ResolveProp<'a>>>(prop: P, value: &str) -> InletBuilder {
+        InletBuilder::new(Query::Prop(prop.into().name(), value.to_string()))
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<i8>, timeout: f64) -> Result<f64> {
-        self.safe_pull_numeric_buf(lsl_pull_sample_c, buf, timeout)
+    /// Resolve a stream that matches a given XPath 1.0 predicate (see `resolve_bypred()`).
+    pub fn resolve_pred(pred: impl AsRef<str>) -> InletBuilder {
+        InletBuilder::new(Query::Pred(pred.as_ref().to_string()))
     }
-}
 
-impl Pullable<String> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<String>, f64)> {
-        self.safe_pull_blob(|x| String::from_utf8_lossy(x).into_owned(), timeout)
+    fn new(query: Query) -> InletBuilder {
+        InletBuilder {
+            query,
+            timeout: FOREVER,
+            max_buflen: 360,
+            buffer: None,
+            max_chunklen: 0,
+            recover: true,
+            postprocessing: vec![],
+            open_stream: false,
+            retry_policy: None,
+        }
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<String>, timeout: f64) -> Result<f64> {
-        self.safe_pull_blob_buf(|x| String::from_utf8_lossy(x).into_owned(), buf, timeout)
+    /**
+    Set the timeout, in seconds, for resolving the stream (and for opening it, if requested via
+    `open_stream()`). The default is `lsl::FOREVER`.
+    */
+    pub fn timeout(mut self, timeout: f64) -> InletBuilder {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the maximum amount of data to buffer (see `StreamInlet::new()`); the default is 360.
+    pub fn buffered(mut self, max_buflen: i32) -> InletBuilder {
+        self.max_buflen = max_buflen;
+        self.buffer = None;
+        self
+    }
+
+    /**
+    Set the maximum amount of data to buffer in explicit units, which are converted based on the
+    nominal sampling rate of the resolved stream (see `BufferSpec::to_buflen()`).
+    */
+    pub fn buffer(mut self, buffer: BufferSpec) -> InletBuilder {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /**
+    Set the maximum size, in samples, at which chunks are transmitted (see `StreamInlet::new()`);
+    the default is 0 (i.e., the sender's chunk sizes).
+    */
+    pub fn chunk_size(mut self, max_chunklen: i32) -> InletBuilder {
+        self.max_chunklen = max_chunklen;
+        self
+    }
+
+    /// Set whether lost streams shall be recovered (see `StreamInlet::new()`); default is `true`.
+    pub fn recover(mut self, recover: bool) -> InletBuilder {
+        self.recover = recover;
+        self
+    }
+
+    /// Set the post-processing options of the inlet (see `StreamInlet::set_postprocessing()`).
+    pub fn postprocessing(mut self, options: &[ProcessingOption]) -> InletBuilder {
+        self.postprocessing = options.to_vec();
+        self
+    }
+
+    /**
+    Set whether the stream shall be opened before the inlet is returned (see
+    `StreamInlet::open_stream()`), rather than on the first pull; the default is `false`.
+    */
+    pub fn open_stream(mut self, open_stream: bool) -> InletBuilder {
+        self.open_stream = open_stream;
+        self
+    }
+
+    /**
+    Set how the inlet's `open_stream()` and `info()` retry after a timeout (see
+    `StreamInlet::set_retry_policy()`), which also applies when the stream is opened by the
+    builder; by default, they do not retry.
+    */
+    pub fn open_with(mut self, policy: RetryPolicy) -> InletBuilder {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Resolve the stream and open an inlet to it (taking the first match).
+    pub fn open(self) -> Result<StreamInlet> {
+        let results = match &self.query {
+            Query::Prop(prop, value) => resolve_byprop(prop, value, 1, self.timeout)?,
+            Query::Pred(pred) => resolve_bypred(pred, 1, self.timeout)?,
+        };
+        let info = results.first().ok_or(Error::Timeout)?;
+        let max_buflen = match self.buffer {
+            Some(buffer) => buffer.to_buflen(info.nominal_srate()),
+            None => self.max_buflen,
+        };
+        let mut inlet = StreamInlet::new(info, max_buflen, self.max_chunklen, self.recover)?;
+        inlet.set_retry_policy(self.retry_policy);
+        if !self.postprocessing.is_empty() {
+            inlet.set_postprocessing(&self.postprocessing)?;
+        }
+        if self.open_stream {
+            inlet.open_stream(self.timeout)?;
+        }
+        Ok(inlet)
     }
 }
 
-impl Pullable<vec::Vec<u8>> for StreamInlet {
-    fn pull_sample(&self, timeout: f64) -> Result<(vec::Vec<vec::Vec<u8>>, f64)> {
-        self.safe_pull_blob(|x| x.to_vec(), timeout)
+/**
+A builder for resolving streams with additional options (e.g., deduplication of the results).
+
+```no_run
+# fn main() -> Result<(), lsl::Error> {
+let resolved = lsl::ResolveBuilder::byprop("type", "EEG").wait_time(2.0).dedup(true).run()?;
+for info in resolved.streams {
+    println!("{} ({})", info.stream_name(), info.source_id());
+}
+# Ok(())
+# }
+```
+*/
+#[derive(Clone, Debug)]
+pub struct ResolveBuilder {
+    // what to resolve (all streams if `None`)
+    query: Option<Query>,
+    minimum: i32,
+    wait_time: f64,
+    capacity: usize,
+    dedup: bool,
+    host_filters: vec::Vec<HostFilter>,
+}
+
+impl ResolveBuilder {
+    /// Resolve all streams on the network (see `resolve_streams()`).
+    pub fn all() -> ResolveBuilder {
+        ResolveBuilder::new(None)
     }
 
-    fn pull_sample_buf(&self, buf: &mut vec::Vec<vec::Vec<u8>>, timeout: f64) -> Result<f64> {
-        self.safe_pull_blob_buf(|x| x.to_vec(), buf, timeout)
+    /// Resolve the streams with a specific value for a given property (see `resolve_byprop()`).
+    pub fn byprop<'a, P: Into<ResolveProp<'a>>>(prop: P, value: &str) -> ResolveBuilder {
+        ResolveBuilder::new(Some(Query::Prop(prop.into().name(), value.to_string())))
+    }
+
+    /// Resolve the streams that match a given predicate (see `resolve_bypred()`).
+    pub fn bypred(pred: impl AsRef<str>) -> ResolveBuilder {
+        ResolveBuilder::new(Some(Query::Pred(pred.as_ref().to_string())))
+    }
+
+    fn new(query: Option<Query>) -> ResolveBuilder {
+        ResolveBuilder {
+            query,
+            minimum: 1,
+            wait_time: 2.0,
+            capacity: RESOLVE_CAPACITY,
+            dedup: false,
+            host_filters: vec![],
+        }
+    }
+
+    /**
+    Set the minimum number of streams to wait for (the default is 1); this has no effect when
+    resolving all streams, which always takes the full waiting time.
+    */
+    pub fn minimum(mut self, minimum: i32) -> ResolveBuilder {
+        self.minimum = minimum;
+        self
+    }
+
+    /// Set the waiting time (or timeout), in seconds; the default is 2.0.
+    pub fn wait_time(mut self, wait_time: f64) -> ResolveBuilder {
+        self.wait_time = wait_time;
+        self
+    }
+
+    /// Set the maximum number of results; the default is `RESOLVE_CAPACITY`.
+    pub fn capacity(mut self, capacity: usize) -> ResolveBuilder {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set whether duplicate results are collapsed (see `dedup_streams()`); default is `false`.
+    pub fn dedup(mut self, dedup: bool) -> ResolveBuilder {
+        self.dedup = dedup;
+        self
+    }
+
+    /**
+    Only return the streams of the hosts that match the given filter (see `HostFilter`). If this
+    is called several times, the streams that match any of the filters are returned.
+
+    Note that the filter is applied to the results, so the minimum number of streams (see
+    `minimum()`) also counts streams of other hosts, and `Resolved::truncated` refers to the
+    unfiltered results.
+    */
+    pub fn host(mut self, filter: HostFilter) -> ResolveBuilder {
+        self.host_filters.push(filter);
+        self
+    }
+
+    /// Resolve the streams.
+    pub fn run(&self) -> Result<Resolved> {
+        let (minimum, wait_time, capacity) = (self.minimum, self.wait_time, self.capacity);
+        let mut resolved = match &self.query {
+            None => resolve_streams_with_capacity(wait_time, capacity)?,
+            Some(Query::Prop(prop, value)) => {
+                resolve_byprop_with_capacity(prop, value, minimum, wait_time, capacity)?
+            }
+            Some(Query::Pred(pred)) => {
+                resolve_bypred_with_capacity(pred, minimum, wait_time, capacity)?
+            }
+        };
+        resolved.streams = filter_hosts(resolved.streams, &self.host_filters);
+        if self.dedup {
+            resolved.streams = dedup_streams(resolved.streams);
+        }
+        Ok(resolved)
     }
 }
 
@@ -1693,19 +4774,21 @@ additional documentation.
 **Note:** operations on non-existent nodes become safe no-ops instead of returning error variants
 or crashing. Since in most cases you will be writing data instead of navigating the tree and/or
 reading, you will rarely encounter this. You can rely on the `is_valid()` method to check the
-validity of the current element.
+validity of the current element, or use the `try_` variants of the modification operations (e.g.,
+`try_append_child_value()`), which return errors instead.
 
 **Examples:** the `*advanced.rs` examples (found in the crate's github repository) illustrate the
 use of `XMLElement` cursors.
 
 **Panics:** any strings passed into this function must be valid UTF8-encoded strings and contain no
-intermittent zero bytes (otherwise this will trigger an assertion).
+intermittent zero bytes (otherwise this will trigger an assertion); the `try_` variants return an
+`Error::BadArgument` instead.
 */
 #[derive(Clone, Debug)]
 pub struct XMLElement {
     // internal fields
     cursor: lsl_xml_ptr,
-    doc: rc::Rc<StreamInfoHandle>,
+    doc: sync::Arc<StreamInfoHandle>,
 }
 
 impl XMLElement {
@@ -1713,6 +4796,7 @@ impl XMLElement {
 
     /// Get the first child of the element.
     pub fn first_child(&self) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             XMLElement {
                 cursor: lsl_first_child(self.cursor),
@@ -1723,6 +4807,7 @@ impl XMLElement {
 
     /// Get the last child of the element.
     pub fn last_child(&self) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             XMLElement {
                 cursor: lsl_last_child(self.cursor),
@@ -1733,6 +4818,7 @@ impl XMLElement {
 
     /// Get the next sibling in the children list of the parent node.
     pub fn next_sibling(&self) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             XMLElement {
                 cursor: lsl_next_sibling(self.cursor),
@@ -1743,6 +4829,7 @@ impl XMLElement {
 
     /// Get the previous sibling in the children list of the parent node.
     pub fn previous_sibling(&self) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             XMLElement {
                 cursor: lsl_previous_sibling(self.cursor),
@@ -1753,6 +4840,7 @@ impl XMLElement {
 
     /// Get the parent node.
     pub fn parent(&self) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             XMLElement {
                 cursor: lsl_parent(self.cursor),
@@ -1765,6 +4853,7 @@ impl XMLElement {
 
     /// Get a child with a specified name.
     pub fn child(&self, name: &str) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             let name = make_cstring(name);
             XMLElement {
@@ -1776,6 +4865,7 @@ impl XMLElement {
 
     /// Get the next sibling with the specified name.
     pub fn next_sibling_named(&self, name: &str) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             let name = make_cstring(name);
             XMLElement {
@@ -1787,6 +4877,7 @@ impl XMLElement {
 
     /// Get the previous sibling with the specified name.
     pub fn previous_sibling_named(&self, name: &str) -> XMLElement {
+        let _doc = self.doc.lock();
         unsafe {
             let name = make_cstring(name);
             XMLElement {
@@ -1796,42 +4887,226 @@ impl XMLElement {
         }
     }
 
+    /**
+    Get a descendant by its path of element names separated by slashes, as in
+    `desc.find("channels/channel[3]/label")`.
+
+    A name may be followed by an index in brackets to select the k-th child of that name
+    (counting from 1, as in XPath); without one, the first child of that name is selected.
+    Returns `None` if there is no such element, or if the path is malformed.
+    */
+    pub fn find(&self, path: &str) -> Option<XMLElement> {
+        let mut element = self.clone();
+        for step in path.split('/').filter(|step| !step.is_empty()) {
+            let (name, index) = match step.find('[') {
+                Some(at) if step.ends_with(']') => {
+                    let index = step[at + 1..step.len() - 1].trim().parse::<usize>().ok()?;
+                    (&step[..at], index.checked_sub(1)?)
+                }
+                Some(_) => return None,
+                None => (step, 0),
+            };
+            element = element.child(name);
+            for _ in 0..index {
+                if element.empty() {
+                    break;
+                }
+                element = element.next_sibling_named(name);
+            }
+            if !element.is_valid() || element.empty() {
+                return None;
+            }
+        }
+        Some(element)
+    }
+
     // === Content Queries ===
 
     /// Whether this node is empty.
     pub fn empty(&self) -> bool {
+        let _doc = self.doc.lock();
         unsafe { lsl_empty(self.cursor) != 0 }
     }
 
     /// Whether this is a text body (instead of an XML element). True both for plain char
     /// data and CData.
     pub fn is_text(&self) -> bool {
+        let _doc = self.doc.lock();
         unsafe { lsl_is_text(self.cursor) != 0 }
     }
 
     /// Name of the element.
     pub fn name(&self) -> String {
+        let _doc = self.doc.lock();
         unsafe { make_string(lsl_name(self.cursor)) }
     }
 
     /// Value of the element.
     pub fn value(&self) -> String {
+        let _doc = self.doc.lock();
         unsafe { make_string(lsl_value(self.cursor)) }
     }
 
     /// Get child value (value of the first child that is text).
     pub fn child_value(&self) -> String {
+        let _doc = self.doc.lock();
         unsafe { make_string(lsl_child_value(self.cursor)) }
     }
 
     /// Get child value of a child with a specified name.
     pub fn child_value_named(&self, name: &str) -> String {
+        let _doc = self.doc.lock();
         unsafe {
             let name = make_cstring(name);
             make_string(lsl_child_value_n(self.cursor, name.as_ptr()))
         }
     }
 
+    /**
+    Get the value of a child with the specified name, converted to the given type (e.g., a
+    number or a `bool`; see `XMLValue`), as in `channel.child_value_as::<f64>("gain")`.
+
+    Returns `None` if there is no such child, or if it has no text, and an `Error::InvalidValue`
+    if its text cannot be converted.
+    */
+    pub fn child_value_as<T: XMLValue>(&self, name: &str) -> Result<Option<T>> {
+        let text = self.child_value_named(name);
+        if text.is_empty() {
+            return Ok(None);
+        }
+        T::from_xml_value(&text)
+            .map(Some)
+            .ok_or_else(|| Error::InvalidValue {
+                field: name.to_string(),
+                text,
+            })
+    }
+
+    /**
+    Get the value of a descendant by its path (see `find()`), converted to the given type, as in
+    `desc.find_value_as::<f64>("channels/channel[3]/gain")`.
+
+    Returns `None` if there is no such element, or if it has no text, and an
+    `Error::InvalidValue` if its text cannot be converted.
+    */
+    pub fn find_value_as<T: XMLValue>(&self, path: &str) -> Result<Option<T>> {
+        let text = match self.find(path) {
+            Some(element) => element.child_value(),
+            None => return Ok(None),
+        };
+        if text.is_empty() {
+            return Ok(None);
+        }
+        T::from_xml_value(&text)
+            .map(Some)
+            .ok_or_else(|| Error::InvalidValue {
+                field: path.to_string(),
+                text,
+            })
+    }
+
+    /**
+    Set the value of a child with the specified name to the text form of the given value (e.g.,
+    a number or a `bool`; see `XMLValue`), appending the child if there is none.
+
+    Returns the same element on which the operation was performed (not the child).
+    */
+    pub fn set_child_value_as<T: XMLValue>(&mut self, name: &str, value: T) -> XMLElement {
+        let text = value.to_xml_value();
+        // (setting the value fails if the child does not exist yet, or has no text)
+        if !self.set_child_value(name, &text) {
+            self.remove_child_named(name);
+            self.append_child_value(name, &text);
+        }
+        self.clone()
+    }
+
+    /**
+    Get the value of an attribute of the element (e.g., `<channel id="3">`), if it has one.
+
+    The native library keeps the attributes of meta-data that were parsed from XML (e.g., via
+    `StreamInfo::from_xml()`, or as received from a tool that emits them), but has no functions
+    to access them, so they are parsed from the XML form of the document. This happens once for
+    the whole document (and again after it has been modified), so the first query is
+    considerably slower than the later ones. For the same reason, attributes cannot be added or
+    changed through an `XMLElement`, but only via `StreamInfo::set_attribute()`.
+    */
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        let mut attributes = self.attributes();
+        let index = attributes.iter().position(|(key, _)| key == name)?;
+        Some(attributes.swap_remove(index).1)
+    }
+
+    /// Get the attributes of the element as name/value pairs, in document order (see
+    /// `attribute()`).
+    pub fn attributes(&self) -> vec::Vec<(String, String)> {
+        let generation = self.doc.generation.load(sync::atomic::Ordering::Relaxed);
+        let mut cache = self
+            .doc
+            .attributes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !matches!(&*cache, Some((parsed, _)) if *parsed == generation) {
+            let document = StreamInfo {
+                handle: self.doc.clone(),
+            };
+            let parsed = match document.to_xml() {
+                Ok(text) => xml::attributes(&text),
+                Err(_) => vec![],
+            };
+            let elements = self.document_elements();
+            // (the elements are matched with the parsed ones by their position, which is only
+            // trusted if the names agree as well)
+            let _doc = self.doc.lock();
+            let names_match = elements.iter().zip(&parsed).all(|(&element, (name, _))| {
+                let element_name = unsafe { make_string(lsl_name(element)) };
+                element_name == *name
+            });
+            let matched = elements.len() == parsed.len() && names_match;
+            *cache = Some((
+                generation,
+                match matched {
+                    true => elements
+                        .into_iter()
+                        .zip(parsed)
+                        .map(|(element, (_, attributes))| (element, attributes))
+                        .collect(),
+                    false => HashMap::new(),
+                },
+            ));
+        }
+        match &*cache {
+            Some((_, attributes)) => attributes.get(&self.cursor).cloned().unwrap_or_default(),
+            None => vec![],
+        }
+    }
+
+    // The elements of the document that contains this element, in document order.
+    fn document_elements(&self) -> vec::Vec<lsl_xml_ptr> {
+        let _doc = self.doc.lock();
+        let mut elements = vec![];
+        unsafe {
+            // (the parent of the root element is the document, which has no name)
+            let mut root = self.cursor;
+            while !root.is_null() && lsl_empty(root) == 0 && !make_string(lsl_name(root)).is_empty()
+            {
+                root = lsl_parent(root);
+            }
+            let mut pending = vec![lsl_first_child(root)];
+            while let Some(node) = pending.pop() {
+                if node.is_null() || lsl_empty(node) != 0 {
+                    continue;
+                }
+                pending.push(lsl_next_sibling(node));
+                if lsl_is_text(node) == 0 {
+                    elements.push(node);
+                    pending.push(lsl_first_child(node));
+                }
+            }
+        }
+        elements
+    }
+
     // === Modification ===
 
     /**
@@ -1841,6 +5116,8 @@ impl XMLElement {
     Returns the same element on which the operation was performed (not the child).
     */
     pub fn append_child_value(&mut self, name: &str, value: &str) -> XMLElement {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let name = make_cstring(name);
             let value = make_cstring(value);
@@ -1858,6 +5135,8 @@ impl XMLElement {
     Returns the same element on which the operation was performed (not the child).
     */
     pub fn prepend_child_value(&mut self, name: &str, value: &str) -> XMLElement {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let name = make_cstring(name);
             let value = make_cstring(value);
@@ -1870,6 +5149,8 @@ impl XMLElement {
 
     /// Set the text value of the (nameless) plain-text child of a named child node.
     pub fn set_child_value(&mut self, name: &str, value: &str) -> bool {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let name = make_cstring(name);
             let value = make_cstring(value);
@@ -1879,6 +5160,8 @@ impl XMLElement {
 
     /// Set the element's name. Returns false if the node is empty (or if out of memory).
     pub fn set_name(&mut self, rhs: &str) -> bool {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let rhs = make_cstring(rhs);
             lsl_set_name(self.cursor, rhs.as_ptr()) != 0
@@ -1887,6 +5170,8 @@ impl XMLElement {
 
     /// Set the element's value. Returns false if the node is empty (or if out of memory).
     pub fn set_value(&mut self, rhs: &str) -> bool {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let rhs = make_cstring(rhs);
             lsl_set_value(self.cursor, rhs.as_ptr()) != 0
@@ -1895,6 +5180,8 @@ impl XMLElement {
 
     /// Append a child element with the specified name and return it.
     pub fn append_child(&mut self, name: &str) -> XMLElement {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let name = make_cstring(name);
             XMLElement {
@@ -1906,6 +5193,8 @@ impl XMLElement {
 
     /// Prepend a child element with the specified name and return it.
     pub fn prepend_child(&mut self, name: &str) -> XMLElement {
+        let _doc = self.doc.lock();
+        self.doc.modified();
         unsafe {
             let name = make_cstring(name);
             XMLElement {
@@ -1915,45 +5204,265 @@ impl XMLElement {
         }
     }
 
-    /// Append a copy of the specified element as a child and return a cursor to the result.
-    pub fn append_copy(&mut self, e: XMLElement) -> XMLElement {
-        unsafe {
-            XMLElement {
-                cursor: lsl_append_copy(self.cursor, e.cursor),
-                doc: self.doc.clone(),
-            }
+    /// Append a copy of the specified element as a child and return a cursor to the result.
+    pub fn append_copy(&mut self, e: XMLElement) -> XMLElement {
+        let _docs = self.lock_with(&e);
+        self.doc.modified();
+        unsafe {
+            XMLElement {
+                cursor: lsl_append_copy(self.cursor, e.cursor),
+                doc: self.doc.clone(),
+            }
+        }
+    }
+
+    /// Prepend a child element with the specified name and return a cursor to the result.
+    pub fn prepend_copy(&mut self, e: XMLElement) -> XMLElement {
+        let _docs = self.lock_with(&e);
+        self.doc.modified();
+        unsafe {
+            XMLElement {
+                cursor: lsl_prepend_copy(self.cursor, e.cursor),
+                doc: self.doc.clone(),
+            }
+        }
+    }
+
+    /// Remove a specified child element.
+    pub fn remove_child(&mut self, e: XMLElement) {
+        let _docs = self.lock_with(&e);
+        self.doc.modified();
+        unsafe {
+            lsl_remove_child(self.cursor, e.cursor);
+        }
+    }
+
+    /// Remove a child element with the specified name.
+    pub fn remove_child_named(&mut self, name: &str) {
+        let _doc = self.doc.lock();
+        self.doc.modified();
+        unsafe {
+            let name = make_cstring(name);
+            lsl_remove_child_n(self.cursor, name.as_ptr());
+        }
+    }
+
+    // === Fallible Modification ===
+
+    /**
+    Append a child node with a given name and text value (see `append_child_value()`).
+
+    Returns the same element on which the operation was performed, an `Error::BadArgument` if a
+    string contains zero bytes, or an `Error::InvalidElement` if the element does not exist.
+    */
+    pub fn try_append_child_value(&mut self, name: &str, value: &str) -> Result<XMLElement> {
+        check_strings(&[name, value])?;
+        self.check_element()?;
+        Ok(self.append_child_value(name, value))
+    }
+
+    /// Prepend a child node with a given name and text value (see `try_append_child_value()`).
+    pub fn try_prepend_child_value(&mut self, name: &str, value: &str) -> Result<XMLElement> {
+        check_strings(&[name, value])?;
+        self.check_element()?;
+        Ok(self.prepend_child_value(name, value))
+    }
+
+    /**
+    Set the text value of a named child node (see `set_child_value()`).
+
+    Returns an `Error::BadArgument` if a string contains zero bytes, or an
+    `Error::InvalidElement` if there is no such child, or it has no text (see
+    `set_child_value_as()`, which appends the child in this case).
+    */
+    pub fn try_set_child_value(&mut self, name: &str, value: &str) -> Result<()> {
+        check_strings(&[name, value])?;
+        match self.set_child_value(name, value) {
+            true => Ok(()),
+            false => Err(Error::InvalidElement),
+        }
+    }
+
+    /**
+    Set the element's name.
+
+    Returns an `Error::BadArgument` if the name is empty or contains zero bytes, or an
+    `Error::InvalidElement` if the element does not exist (or the name could not be set).
+    */
+    pub fn try_set_name(&mut self, rhs: &str) -> Result<()> {
+        if rhs.is_empty() {
+            return Err(Error::BadArgument);
+        }
+        check_strings(&[rhs])?;
+        match self.set_name(rhs) {
+            true => Ok(()),
+            false => Err(Error::InvalidElement),
+        }
+    }
+
+    /// Set the element's value (see `try_set_name()`, except that the value may be empty).
+    pub fn try_set_value(&mut self, rhs: &str) -> Result<()> {
+        check_strings(&[rhs])?;
+        match self.set_value(rhs) {
+            true => Ok(()),
+            false => Err(Error::InvalidElement),
+        }
+    }
+
+    /**
+    Append a child element with the specified name and return it.
+
+    Returns an `Error::BadArgument` if the name contains zero bytes, or an
+    `Error::InvalidElement` if the element does not exist (or the child could not be created).
+    */
+    pub fn try_append_child(&mut self, name: &str) -> Result<XMLElement> {
+        check_strings(&[name])?;
+        self.check_element()?;
+        let child = self.append_child(name);
+        child.check_element()?;
+        Ok(child)
+    }
+
+    /// Prepend a child element with the specified name and return it (see `try_append_child()`).
+    pub fn try_prepend_child(&mut self, name: &str) -> Result<XMLElement> {
+        check_strings(&[name])?;
+        self.check_element()?;
+        let child = self.prepend_child(name);
+        child.check_element()?;
+        Ok(child)
+    }
+
+    /**
+    Remove a child element with the specified name.
+
+    Returns an `Error::BadArgument` if the name contains zero bytes, or an
+    `Error::InvalidElement` if there is no such child.
+    */
+    pub fn try_remove_child_named(&mut self, name: &str) -> Result<()> {
+        check_strings(&[name])?;
+        self.check_element()?;
+        self.child(name).check_element()?;
+        self.remove_child_named(name);
+        Ok(())
+    }
+
+    // Return an `Error::InvalidElement` if the element does not exist.
+    fn check_element(&self) -> Result<()> {
+        match self.is_valid() && !self.empty() {
+            true => Ok(()),
+            false => Err(Error::InvalidElement),
         }
     }
 
-    /// Prepend a child element with the specified name and return a cursor to the result.
-    pub fn prepend_copy(&mut self, e: XMLElement) -> XMLElement {
-        unsafe {
-            XMLElement {
-                cursor: lsl_prepend_copy(self.cursor, e.cursor),
-                doc: self.doc.clone(),
+    // === JSON Conversion ===
+
+    /**
+    Convert the content of the element into a JSON value (requires the `json` feature).
+
+    An element with child elements becomes an object that maps the name of each child to its
+    content, where children that share a name are collected into an array (in document order).
+    An element without child elements becomes a string with its text (empty if there is none).
+    Text between child elements is not included.
+
+    This allows meta-data to be inspected, compared or templated as ordinary data, e.g.,
+    `info.desc().to_json()["acquisition"]["model"]`.
+    */
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        let mut fields = serde_json::Map::new();
+        let mut child = self.first_child();
+        while child.is_valid() && !child.empty() {
+            if !child.is_text() {
+                let value = child.to_json();
+                // (the content of an element is never an array, so an array marks repeated names)
+                match fields.get_mut(&child.name()) {
+                    Some(Value::Array(items)) => items.push(value),
+                    Some(first) => *first = Value::Array(vec![first.take(), value]),
+                    None => {
+                        fields.insert(child.name(), value);
+                    }
+                }
             }
+            child = child.next_sibling();
+        }
+        match fields.is_empty() {
+            true => Value::String(self.child_value()),
+            false => Value::Object(fields),
         }
     }
 
-    /// Remove a specified child element.
-    pub fn remove_child(&mut self, e: XMLElement) {
-        unsafe {
-            lsl_remove_child(self.cursor, e.cursor);
+    /**
+    Append the content of a JSON object to the element, as child elements (requires the `json`
+    feature).
+
+    This is the inverse of `to_json()`: each field of the object becomes a child element with
+    the field's name, whose content is given by the field's value, where strings, numbers and
+    booleans become text, objects become child elements, arrays become repeated elements of the
+    same name, and `null` becomes an empty element. Existing children are kept (e.g., use
+    `remove_child_named()` beforehand to replace a section).
+
+    Returns an `Error::BadArgument` if the value is not an object, or if it contains nested
+    arrays (which have no counterpart in the meta-data).
+    */
+    #[cfg(feature = "json")]
+    pub fn from_json(&mut self, value: &serde_json::Value) -> Result<()> {
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (name, value) in fields {
+                    self.append_json(name, value)?;
+                }
+                Ok(())
+            }
+            _ => Err(Error::BadArgument),
         }
     }
 
-    /// Remove a child element with the specified name.
-    pub fn remove_child_named(&mut self, name: &str) {
-        unsafe {
-            let name = make_cstring(name);
-            lsl_remove_child_n(self.cursor, name.as_ptr());
+    // Append a child element with the given name and JSON content (see `from_json()`).
+    #[cfg(feature = "json")]
+    fn append_json(&mut self, name: &str, value: &serde_json::Value) -> Result<()> {
+        use serde_json::Value;
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    if item.is_array() {
+                        return Err(Error::BadArgument);
+                    }
+                    self.append_json(name, item)?;
+                }
+            }
+            Value::Object(_) => self.append_child(name).from_json(value)?,
+            Value::Null => {
+                self.append_child(name);
+            }
+            Value::String(text) => {
+                self.append_child_value(name, text);
+            }
+            _ => {
+                self.append_child_value(name, &value.to_string());
+            }
         }
+        Ok(())
     }
 
     /// Returns true if the current node is valid, false otherwise
     pub fn is_valid(&self) -> bool {
         !self.cursor.is_null()
     }
+
+    // Lock the documents of this and another element (in a consistent order, if they differ).
+    fn lock_with<'a>(&'a self, e: &'a XMLElement) -> (InfoGuard<'a>, Option<InfoGuard<'a>>) {
+        if sync::Arc::ptr_eq(&self.doc, &e.doc) {
+            return (self.doc.lock(), None);
+        }
+        match sync::Arc::as_ptr(&self.doc) < sync::Arc::as_ptr(&e.doc) {
+            true => (self.doc.lock(), Some(e.doc.lock())),
+            false => {
+                let other = e.doc.lock();
+                (self.doc.lock(), Some(other))
+            }
+        }
+    }
 }
 
 impl fmt::Display for XMLElement {
@@ -1972,6 +5481,57 @@ impl fmt::Display for XMLElement {
     }
 }
 
+/**
+A type whose values can be stored as text in the meta-data (see `XMLElement::child_value_as()`).
+
+This is implemented for the numeric types (which are parsed with surrounding whitespace ignored),
+`bool` (which is written as "true" or "false", and parsed from these, "1", "0", "yes" or "no",
+regardless of case), and `String`.
+*/
+pub trait XMLValue: Sized {
+    /// Parse a value from its text in the meta-data, or return `None` if the text is invalid.
+    fn from_xml_value(text: &str) -> Option<Self>;
+    /// The text of the value in the meta-data.
+    fn to_xml_value(&self) -> String;
+}
+
+macro_rules! impl_xml_value_number {
+    ($($t:ty),*) => {
+        $(impl XMLValue for $t {
+            fn from_xml_value(text: &str) -> Option<$t> {
+                text.trim().parse().ok()
+            }
+            fn to_xml_value(&self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+impl_xml_value_number!(i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64);
+
+impl XMLValue for bool {
+    fn from_xml_value(text: &str) -> Option<bool> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(true),
+            "false" | "0" | "no" => Some(false),
+            _ => None,
+        }
+    }
+    fn to_xml_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl XMLValue for String {
+    fn from_xml_value(text: &str) -> Option<String> {
+        Some(text.to_string())
+    }
+    fn to_xml_value(&self) -> String {
+        self.clone()
+    }
+}
+
 // =============================
 // ==== Continuous Resolver ====
 // =============================
@@ -1988,6 +5548,10 @@ illustrates the use of the `ContinuousResolver`.
 #[derive(Debug)]
 pub struct ContinuousResolver {
     handle: lsl_continuous_resolver,
+    // restricts the results to the matching hosts (if any)
+    host_filters: vec::Vec<HostFilter>,
+    // when each stream (by UID) was first and last reported by `sightings()`
+    sightings: sync::Mutex<HashMap<String, Sighting>>,
 }
 
 impl ContinuousResolver {
@@ -2009,7 +5573,11 @@ impl ContinuousResolver {
         unsafe {
             let handle = lsl_create_continuous_resolver(forget_after);
             match handle.is_null() {
-                false => Ok(ContinuousResolver { handle }),
+                false => Ok(ContinuousResolver {
+                    handle,
+                    host_filters: vec![],
+                    sightings: sync::Mutex::default(),
+                }),
                 true => Err(Error::ResourceCreation),
             }
         }
@@ -2022,23 +5590,31 @@ impl ContinuousResolver {
     This is analogous to the functionality provided by the free function `resolve_stream(prop,value)`.
 
     Arguments:
-    * `prop`: The `StreamInfo` property that should have a specific value (e.g., "name", "type",
-       "source_id", or "desc/manufaturer").
+    * `prop`: The `StreamInfo` property that should have a specific value (e.g.,
+      `ResolveProp::Type` or `ResolveProp::DescPath("manufacturer")`; see `ResolveProp`).
     * `value`: The string value that the property should have (e.g., "EEG" as the type property).
     * `forget_after`: When a stream is no longer visible on the network (e.g., because it was shut
        down), this is the time in seconds after which it is no longer reported by the resolver.
     */
-    pub fn new_with_prop(prop: &str, value: &str, forget_after: f64) -> Result<ContinuousResolver> {
+    pub fn new_with_prop<'a, P: Into<ResolveProp<'a>>>(
+        prop: P,
+        value: &str,
+        forget_after: f64,
+    ) -> Result<ContinuousResolver> {
         if forget_after <= 0.0 {
             return Err(Error::BadArgument);
         }
-        let prop = ffi::CString::new(prop)?;
+        let prop = ffi::CString::new(prop.into().name())?;
         let value = ffi::CString::new(value)?;
         unsafe {
             let handle =
                 lsl_create_continuous_resolver_byprop(prop.as_ptr(), value.as_ptr(), forget_after);
             match handle.is_null() {
-                false => Ok(ContinuousResolver { handle }),
+                false => Ok(ContinuousResolver {
+                    handle,
+                    host_filters: vec![],
+                    sightings: sync::Mutex::default(),
+                }),
                 true => Err(Error::ResourceCreation),
             }
         }
@@ -2057,15 +5633,19 @@ impl ContinuousResolver {
     * `forget_after`: When a stream is no longer visible on the network (e.g., because it was shut
        down), this is the time in seconds after which it is no longer reported by the resolver.
     */
-    pub fn new_with_pred(pred: &str, forget_after: f64) -> Result<ContinuousResolver> {
+    pub fn new_with_pred(pred: impl AsRef<str>, forget_after: f64) -> Result<ContinuousResolver> {
         if forget_after <= 0.0 {
             return Err(Error::BadArgument);
         }
-        let pred = ffi::CString::new(pred)?;
+        let pred = ffi::CString::new(pred.as_ref())?;
         unsafe {
             let handle = lsl_create_continuous_resolver_bypred(pred.as_ptr(), forget_after);
             match handle.is_null() {
-                false => Ok(ContinuousResolver { handle }),
+                false => Ok(ContinuousResolver {
+                    handle,
+                    host_filters: vec![],
+                    sightings: sync::Mutex::default(),
+                }),
                 true => Err(Error::ResourceCreation),
             }
         }
@@ -2075,23 +5655,140 @@ impl ContinuousResolver {
     Obtain the set of currently present streams on the network (i.e. resolve result).
 
     Returns a vector of matching stream info objects (excluding their meta-data), any of which can
-    subsequently be used to open an inlet.
+    subsequently be used to open an inlet. Unlike the one-shot resolve functions, the number of
+    results is not limited.
     */
     pub fn results(&self) -> Result<vec::Vec<StreamInfo>> {
-        // the fixed-size buffer is safe since the native function uses it as the max number of
-        // results
-        let mut buffer = [0 as lsl_streaminfo; 1024];
-        unsafe {
-            let num_resolved = errcode_to_result(lsl_resolver_results(
-                self.handle,
-                buffer.as_mut_ptr(),
-                buffer.len() as u32,
-            ))? as usize;
-            let results: Vec<_> = buffer[0..num_resolved]
-                .iter()
-                .map(|x| StreamInfo::from_handle(*x))
-                .collect();
-            Ok(results)
+        // the buffer starts small and the query is repeated with a larger one while it is filled,
+        // which is cheap, since it only reads the resolver's current state
+        let mut capacity = RESOLVE_CAPACITY;
+        loop {
+            let resolved = collect_resolved(capacity, |buffer, len| unsafe {
+                lsl_resolver_results(self.handle, buffer, len)
+            })?;
+            if !resolved.truncated || capacity >= i32::MAX as usize {
+                return Ok(filter_hosts(resolved.streams, &self.host_filters));
+            }
+            capacity = capacity.saturating_mul(2);
+        }
+    }
+
+    /**
+    Restrict the results to the streams of the given hosts (see `HostFilter`).
+
+    A stream is reported if it matches any of the filters; an empty list removes the restriction.
+    */
+    pub fn with_host_filters(mut self, filters: vec::Vec<HostFilter>) -> ContinuousResolver {
+        self.host_filters = filters;
+        self
+    }
+
+    /**
+    Obtain the currently present streams along with when they were first and last seen, as well
+    as the streams that have disappeared since they were first seen.
+
+    The times (in agreement with `lsl::local_clock()`) are tracked by the wrapper: a stream is
+    seen whenever it is among the results of a call to this method, so their resolution is the
+    interval at which it is called (e.g., once per refresh of a monitoring display). Also note
+    that the resolver keeps reporting a stream for `forget_after` seconds after it has vanished.
+    A stream that disappears and reappears (e.g., due to an unreliable network connection) is
+    counted as a new appearance of the same stream.
+    */
+    pub fn sightings(&self) -> Result<vec::Vec<Sighting>> {
+        let results = self.results()?;
+        let now = local_clock();
+        let mut sightings = self.sightings.lock().unwrap_or_else(|e| e.into_inner());
+        let mut present = HashSet::new();
+        for info in results {
+            let uid = info.uid();
+            let sighting = sightings.entry(uid.clone()).or_insert_with(|| Sighting {
+                info: info.clone(),
+                first_seen: now,
+                last_seen: now,
+                appearances: 0,
+                present: false,
+            });
+            if !sighting.present {
+                sighting.appearances += 1;
+            }
+            sighting.info = info;
+            sighting.last_seen = now;
+            present.insert(uid);
+        }
+        for (uid, sighting) in sightings.iter_mut() {
+            sighting.present = present.contains(uid);
+        }
+        // in the order in which the streams were first seen
+        let mut result: vec::Vec<_> = sightings.values().cloned().collect();
+        result.sort_by(|a, b| a.first_seen.partial_cmp(&b.first_seen).unwrap());
+        Ok(result)
+    }
+
+    /**
+    Report the matching streams one by one as they are discovered, for up to `wait_time` seconds.
+
+    Unlike the one-shot resolve functions, which return all streams at the end of their waiting
+    time, this returns a blocking iterator that yields each stream as soon as the resolver has
+    found it (e.g., to fill the stream list of a GUI progressively during a scan). Each stream is
+    reported once; the iterator ends when the waiting time has expired, or after an error.
+
+    ```no_run
+    # fn main() -> Result<(), lsl::Error> {
+    let resolver = lsl::ContinuousResolver::new_with_prop("type", "EEG", 5.0)?;
+    for info in resolver.discover(5.0) {
+        println!("found {}", info?.stream_name());
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn discover(self, wait_time: f64) -> Discovery {
+        Discovery {
+            resolver: self,
+            deadline: local_clock() + wait_time,
+            seen: HashSet::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /**
+    Call the given closures whenever a matching stream appears or disappears.
+
+    The resolver is moved to a background thread (see `lsl::worker`), which checks it for changes
+    every few milliseconds, and calls `on_found` with each newly found stream and `on_lost` with
+    each stream that is no longer reported (i.e., after the resolver's `forget_after` time). The
+    streams that are already present are reported as found right away. The closures are called
+    on the background thread, so they should return quickly (e.g., by handing the stream over
+    to another thread, or by opening an inlet and passing it on).
+
+    The notifications stop when the returned `StreamNotifier` is dropped or stopped.
+
+    ```no_run
+    # fn main() -> Result<(), lsl::Error> {
+    let resolver = lsl::ContinuousResolver::new(5.0)?;
+    let _notifier = resolver.notify(
+        |info| println!("{} appeared", info.stream_name()),
+        |info| println!("{} disappeared", info.stream_name()),
+    );
+    std::thread::sleep(std::time::Duration::from_secs(60));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn notify<F, L>(self, on_found: F, on_lost: L) -> StreamNotifier
+    where
+        F: FnMut(StreamInfo) + Send + 'static,
+        L: FnMut(StreamInfo) + Send + 'static,
+    {
+        let task = NotifyTask {
+            resolver: self,
+            known: HashMap::new(),
+            on_found: Box::new(on_found),
+            on_lost: Box::new(on_lost),
+        };
+        StreamNotifier {
+            worker: worker::Worker::spawn(task),
         }
     }
 }
@@ -2104,18 +5801,298 @@ impl Drop for ContinuousResolver {
     }
 }
 
+// the native resolver runs in its own background thread and guards its results with a lock
+unsafe impl Send for ContinuousResolver {}
+unsafe impl Sync for ContinuousResolver {}
+
+/**
+Watch whether a stream matching the given predicate is currently present on the network.
+
+This returns a `Presence` handle, which is backed by a `ContinuousResolver` and can be queried
+at any time (e.g., once per frame by a GUI that shows an availability indicator). A stream that
+disappears is reported as absent after about 5 seconds.
+
+Arguments:
+* `pred`: The predicate string, e.g. `"name='BioSemi'"`, or a `lsl::query::Query` (see
+  `resolve_bypred()`).
+
+```no_run
+# fn main() -> Result<(), lsl::Error> {
+let eeg = lsl::presence("type='EEG'")?;
+loop {
+    if eeg.changed()? {
+        println!("EEG stream is {}", if eeg.is_present()? { "online" } else { "offline" });
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+}
+# }
+```
+*/
+pub fn presence(pred: impl AsRef<str>) -> Result<Presence> {
+    Ok(Presence {
+        resolver: ContinuousResolver::new_with_pred(pred, 5.0)?,
+        last: std::cell::Cell::new(false),
+    })
+}
+
+/// A handle that tells whether a matching stream is present, as created by `presence()`.
+#[derive(Debug)]
+pub struct Presence {
+    resolver: ContinuousResolver,
+    // the presence as of the last call to `changed()`
+    last: std::cell::Cell<bool>,
+}
+
+impl Presence {
+    /**
+    Whether a matching stream is currently present.
+
+    Note that the resolver needs some time (typically well below a second) after its creation to
+    discover the streams that are already present.
+    */
+    pub fn is_present(&self) -> Result<bool> {
+        Ok(!self.resolver.results()?.is_empty())
+    }
+
+    /**
+    Whether the presence has changed since the last call to `changed()` (or, on the first call,
+    whether a stream is present).
+    */
+    pub fn changed(&self) -> Result<bool> {
+        let present = self.is_present()?;
+        Ok(self.last.replace(present) != present)
+    }
+
+    /// The currently present matching streams (excluding their meta-data).
+    pub fn streams(&self) -> Result<vec::Vec<StreamInfo>> {
+        self.resolver.results()
+    }
+}
+
+/**
+A blocking iterator over the streams that a resolver discovers, which ends after a given time.
+
+Created by `ContinuousResolver::discover()`.
+*/
+#[derive(Debug)]
+pub struct Discovery {
+    resolver: ContinuousResolver,
+    deadline: f64,
+    // the UIDs of the streams that have been found so far
+    seen: HashSet<String>,
+    pending: VecDeque<StreamInfo>,
+    done: bool,
+}
+
+impl Iterator for Discovery {
+    type Item = Result<StreamInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(info) = self.pending.pop_front() {
+                return Some(Ok(info));
+            }
+            if self.done {
+                return None;
+            }
+            match self.resolver.results() {
+                Ok(results) => {
+                    let seen = &mut self.seen;
+                    let new = results.into_iter().filter(|info| seen.insert(info.uid()));
+                    self.pending.extend(new);
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+            if self.pending.is_empty() {
+                let remaining = self.deadline - local_clock();
+                if remaining <= 0.0 {
+                    self.done = true;
+                } else {
+                    let pause = RESOLVER_POLL.min(remaining);
+                    std::thread::sleep(std::time::Duration::from_secs_f64(pause));
+                }
+            }
+        }
+    }
+}
+
+/// When a stream was seen by a `ContinuousResolver` (see `ContinuousResolver::sightings()`).
+#[derive(Clone, Debug)]
+pub struct Sighting {
+    /// The stream (as last reported by the resolver).
+    pub info: StreamInfo,
+    /// When the stream was first seen.
+    pub first_seen: f64,
+    /// When the stream was last seen (the current time if it is present).
+    pub last_seen: f64,
+    /// How often the stream has (re)appeared, i.e., 1 if it has never disappeared.
+    pub appearances: u32,
+    /// Whether the stream is currently present.
+    pub present: bool,
+}
+
+// a callback of a `StreamNotifier`
+type StreamCallback = Box<dyn FnMut(StreamInfo) + Send>;
+
+/**
+Notifies about streams that appear or disappear, as created by `ContinuousResolver::notify()`.
+
+Dropping the notifier stops the notifications (and the background thread).
+*/
+#[derive(Debug)]
+pub struct StreamNotifier {
+    worker: worker::Worker<NotifyTask>,
+}
+
+impl StreamNotifier {
+    /// Whether the notifications have ended because the resolver failed.
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_finished()
+    }
+
+    /**
+    Stop the notifications, and return the resolver (or the error with which it failed).
+
+    No closure is called after this has returned.
+    */
+    pub fn stop(self) -> Result<ContinuousResolver> {
+        let (task, result) = self.worker.stop();
+        result.map(|_| task.resolver)
+    }
+}
+
+// the task of a `StreamNotifier`: comparing the results of the resolver with the known streams
+struct NotifyTask {
+    resolver: ContinuousResolver,
+    // the streams that have been reported as found, by UID
+    known: HashMap<String, StreamInfo>,
+    on_found: StreamCallback,
+    on_lost: StreamCallback,
+}
+
+// (closures are not `Debug`)
+impl fmt::Debug for NotifyTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotifyTask")
+            .field("resolver", &self.resolver)
+            .field("known", &self.known.keys())
+            .finish()
+    }
+}
+
+impl worker::Task for NotifyTask {
+    fn run_once(&mut self, handle: &worker::Handle) -> Result<()> {
+        let mut current: HashMap<_, _> = self
+            .resolver
+            .results()?
+            .into_iter()
+            .map(|info| (info.uid(), info))
+            .collect();
+        let lost: vec::Vec<_> = self
+            .known
+            .keys()
+            .filter(|uid| !current.contains_key(*uid))
+            .cloned()
+            .collect();
+        for uid in lost {
+            if let Some(info) = self.known.remove(&uid) {
+                (self.on_lost)(info);
+            }
+        }
+        current.retain(|uid, _| !self.known.contains_key(uid));
+        for (uid, info) in current {
+            self.known.insert(uid, info.clone());
+            (self.on_found)(info);
+        }
+        handle.wait(RESOLVER_POLL);
+        Ok(())
+    }
+}
+
 // ========================
 // === Internal Helpers ===
 // ========================
 
-// wrapper around a native streaminfo handle
+// the interval, in seconds, at which a `Discovery` or `StreamNotifier` checks its resolver for
+// changes
+const RESOLVER_POLL: f64 = 0.05;
+
+// a locked native streaminfo handle
+type InfoGuard<'a> = sync::MutexGuard<'a, lsl_streaminfo>;
+
+// the attributes of each element of a document, by its native handle
+type AttributeMap = HashMap<lsl_xml_ptr, vec::Vec<(String, String)>>;
+
+// wrapper around a native streaminfo handle, which is shared by a `StreamInfo` and the
+// `XMLElement`s obtained from it, and is locked during each native call
 #[derive(Debug)]
-struct StreamInfoHandle { handle: lsl_streaminfo }
+struct StreamInfoHandle {
+    handle: sync::Mutex<lsl_streaminfo>,
+    // the number of modifications of the meta-data (through `XMLElement`s)
+    generation: sync::atomic::AtomicU64,
+    // the attributes of the elements of the document (see `XMLElement::attributes()`), which are
+    // parsed from its XML form, and the generation of the document they were parsed from
+    attributes: sync::Mutex<Option<(u64, AttributeMap)>>,
+}
+
+impl StreamInfoHandle {
+    fn new(handle: lsl_streaminfo) -> StreamInfoHandle {
+        StreamInfoHandle {
+            handle: sync::Mutex::new(handle),
+            generation: sync::atomic::AtomicU64::new(0),
+            attributes: sync::Mutex::new(None),
+        }
+    }
+
+    fn lock(&self) -> sync::MutexGuard<'_, lsl_streaminfo> {
+        self.handle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Note a modification of the meta-data, which invalidates the parsed attributes.
+    fn modified(&self) {
+        self.generation
+            .fetch_add(1, sync::atomic::Ordering::Relaxed);
+    }
+}
 
 impl Drop for StreamInfoHandle {
     fn drop(&mut self) {
         unsafe {
-            lsl_destroy_streaminfo(self.handle);
+            lsl_destroy_streaminfo(*self.lock());
+        }
+    }
+}
+
+// the native streaminfo (including its XML document) is not tied to a thread, and all accesses to
+// it are serialized by the lock, which makes `StreamInfo` `Send` and `Sync`; note that
+// `XMLElement`s remain bound to their thread, since they hold raw cursors into the document
+unsafe impl Send for StreamInfoHandle {}
+unsafe impl Sync for StreamInfoHandle {}
+
+// string buffers handed out by the native library, which are freed when this goes out of scope
+struct NativeStrings {
+    ptrs: vec::Vec<*mut ::std::os::raw::c_char>,
+    lens: vec::Vec<u32>,
+}
+
+impl NativeStrings {
+    fn new(count: usize) -> NativeStrings {
+        NativeStrings {
+            ptrs: vec![std::ptr::null_mut(); count],
+            lens: vec![0; count],
+        }
+    }
+}
+
+impl Drop for NativeStrings {
+    fn drop(&mut self) {
+        for &ptr in self.ptrs.iter().filter(|p| !p.is_null()) {
+            unsafe {
+                lsl_destroy_string(ptr);
+            }
         }
     }
 }
@@ -2126,6 +6103,10 @@ type NativePushFunction<T> = unsafe extern "C" fn(lsl_outlet, *const T, f64, i32
 // internal signature of one of the lsl_pull_sample_* functions
 type NativePullFunction<T> = unsafe extern "C" fn(lsl_inlet, *mut T, i32, f64, *mut i32) -> f64;
 
+// internal signature of one of the lsl_pull_chunk_* functions
+type NativePullChunkFunction<T> =
+    unsafe extern "C" fn(lsl_inlet, *mut T, *mut f64, c_ulong, c_ulong, f64, *mut i32) -> c_ulong;
+
 // helper functions for interop with native data types in the lsl_sys module
 impl ChannelFormat {
     /// Convert to corresponding native data type.
@@ -2158,6 +6139,105 @@ impl ChannelFormat {
             _ => ChannelFormat::Undefined,
         }
     }
+
+    /**
+    Whether data of this format can be transmitted by the linked native library.
+
+    This is false for `ChannelFormat::Undefined`, and for `ChannelFormat::Int64` if the native
+    library was built for a 32-bit platform (or is a Windows build older than liblsl 1.14).
+    */
+    pub fn is_supported(&self) -> bool {
+        match self {
+            ChannelFormat::Undefined => false,
+            ChannelFormat::Int64 => capabilities().int64,
+            _ => true,
+        }
+    }
+
+    /// The size of one value of this format in bytes, or `None` for variable-length strings.
+    pub fn value_bytes(&self) -> Option<usize> {
+        match self {
+            ChannelFormat::Float32 | ChannelFormat::Int32 => Some(4),
+            ChannelFormat::Double64 | ChannelFormat::Int64 => Some(8),
+            ChannelFormat::Int16 => Some(2),
+            ChannelFormat::Int8 => Some(1),
+            ChannelFormat::String | ChannelFormat::Undefined => None,
+        }
+    }
+}
+
+impl ChunkSpec {
+    // Convert to the chunk_size value expected by the native library (negative if out of range).
+    fn to_native(self) -> i32 {
+        match self {
+            ChunkSpec::PerPush => 0,
+            ChunkSpec::Samples(n) => i32::try_from(n).unwrap_or(-1),
+        }
+    }
+}
+
+// chunk sizes given as a plain number of samples (0 meaning one chunk per push)
+impl From<u32> for ChunkSpec {
+    fn from(n: u32) -> ChunkSpec {
+        match n {
+            0 => ChunkSpec::PerPush,
+            n => ChunkSpec::Samples(n),
+        }
+    }
+}
+
+/**
+The amount of data that an outlet or inlet buffers (see `OutletBuilder::buffer()` and
+`InletBuilder::buffer()`).
+
+The native library takes buffer sizes in seconds for streams with a nominal sampling rate, but in
+hundreds of samples for streams with an irregular rate (see the `max_buffered` argument of
+`StreamOutlet::new()`), which is easily gotten wrong. A `BufferSpec` states the size in either
+unit, and is converted based on the nominal sampling rate of the stream (see `to_buflen()`).
+*/
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum BufferSpec {
+    /// Buffer the data of the given duration. For streams with an irregular rate, which have no
+    /// rate to convert by, this assumes 100 samples per second (as does the native library; use
+    /// `StreamInfo::buflen_for_duration()` to convert by another rate).
+    Duration(std::time::Duration),
+    /// Buffer the given number of samples.
+    Samples(u32),
+}
+
+impl BufferSpec {
+    /**
+    Convert to the units of the `max_buffered` argument of `StreamOutlet::new()` (and the
+    `max_buflen` argument of `StreamInlet::new()`), given the nominal sampling rate of the stream.
+
+    The result is rounded up, so that the buffer holds at least the requested data, and is at
+    least 1.
+    */
+    pub fn to_buflen(self, nominal_srate: f64) -> i32 {
+        let seconds = match (self, nominal_srate) {
+            (BufferSpec::Duration(duration), _) => duration.as_secs_f64(),
+            (BufferSpec::Samples(n), srate) if srate == IRREGULAR_RATE => {
+                n as f64 / ASSUMED_IRREGULAR_RATE
+            }
+            (BufferSpec::Samples(n), srate) => n as f64 / srate,
+        };
+        duration_to_buflen(seconds, nominal_srate, ASSUMED_IRREGULAR_RATE)
+    }
+}
+
+// the number of samples per second that the native library assumes for streams with an irregular
+// rate when it sizes their buffers
+const ASSUMED_IRREGULAR_RATE: f64 = 100.0;
+
+// Convert a duration into the units of `max_buflen` (see `StreamInfo::buflen_for_duration()`),
+// given the nominal sampling rate of the stream and the expected rate if it is irregular.
+fn duration_to_buflen(seconds: f64, nominal_srate: f64, irregular_rate: f64) -> i32 {
+    let units = match nominal_srate {
+        srate if srate == IRREGULAR_RATE => seconds * irregular_rate / 100.0,
+        _ => seconds,
+    };
+    // (the conversion saturates at the range of i32)
+    (units.ceil() as i32).max(1)
 }
 
 impl fmt::Display for ChannelFormat {
@@ -2198,6 +6278,24 @@ impl fmt::Display for Error {
             Error::ResourceCreation => "resource creation failed.",
             Error::Internal => "internal error in native library",
             Error::Unknown => "unknown error",
+            Error::UnsupportedFormat => "channel format not supported by the native library",
+            Error::InvalidUtf8 => "string data is not valid UTF-8",
+            Error::FormatMismatch => "value type does not match the stream's channel format",
+            Error::InvalidElement => "meta-data element does not exist",
+            Error::Io(kind) => return write!(f, "I/O error: {:?}", kind),
+            Error::LengthMismatch { expected, actual } => {
+                return write!(f, "data has length {} instead of {}", actual, expected)
+            }
+            Error::Parse { line, reason } => {
+                return write!(f, "parse error in line {}: {}", line, reason)
+            }
+            Error::InvalidValue { field, text } => {
+                return write!(
+                    f,
+                    "meta-data value {:?} of {} cannot be converted to the requested type",
+                    text, field
+                )
+            }
         };
         write!(f, "{}", msg)
     }
@@ -2207,6 +6305,12 @@ impl fmt::Display for Error {
 /// Since no further source information is available, this is omitted.
 impl std::error::Error for Error {}
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::Io(e.kind())
+    }
+}
+
 // Internal function that creates a CString from a well-formed utf8-encoded &str. This function
 // *panics* if a null byte is contained in s, therefore this should only be used in APIs that do
 // not return error values.
@@ -2218,6 +6322,15 @@ fn make_cstring(s: &str) -> ffi::CString {
     )
 }
 
+// Internal function that returns an `Error::BadArgument` if any of the strings could not be passed
+// to the library (see `make_cstring()`).
+fn check_strings(strings: &[&str]) -> Result<()> {
+    match strings.iter().any(|s| s.contains('\0')) {
+        true => Err(Error::BadArgument),
+        false => Ok(()),
+    }
+}
+
 // Internal function that creates a String from a const char* returned by a trusted C routine.
 // Replaces invalid bytes by placeholder UTF8 characters. This function *panics* if a null pointer
 // is given it it, and therefore it should only be used with API return values where that's
@@ -2232,6 +6345,12 @@ unsafe fn make_string(s: *const ::std::os::raw::c_char) -> String {
     ffi::CStr::from_ptr(s).to_string_lossy().into_owned()
 }
 
+// Lock a mutex, also if another thread has panicked while holding it (the data behind the locks of
+// this crate are consistent between operations).
+fn lock<T>(mutex: &sync::Mutex<T>) -> sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 // check whether a given value that may be an error code signals an error,
 // and convert to the correct Err() type or Ok(value) otherwise
 fn errcode_to_result(ec: i32) -> Result<i32> {
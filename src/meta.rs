@@ -0,0 +1,827 @@
+/*!
+Typed access to the common fields of a stream's meta-data.
+
+The [XDF meta-data recommendations](https://github.com/sccn/xdf/wiki/Meta-Data) define a few
+fields that many streams share, such as the manufacturer of the acquisition device or the id of
+the subject. Rather than navigating the `desc()` tree of a `StreamInfo` with an `XMLElement`
+cursor, these fields can be read and written through the views that are returned by
+`StreamInfo::acquisition()`, `StreamInfo::cap()`, `StreamInfo::subject()` and
+`StreamInfo::session()`:
+
+```no_run
+use lsl::{ChannelFormat, StreamInfo};
+
+fn main() -> Result<(), lsl::Error> {
+    let mut info = StreamInfo::new("BioSemi", "EEG", 8, 512.0, ChannelFormat::Float32, "bs-123")?;
+    info.acquisition().set_manufacturer("BioSemi").set_model("ActiveTwo");
+    info.subject().set_id("P07");
+    println!("recorded with a {} amplifier", info.acquisition().model());
+    Ok(())
+}
+```
+
+The getters return an empty string for fields that are not declared. A section (e.g.,
+`<acquisition>`) is created when its first field is set.
+
+Where a section is handled as a whole (e.g., taken from a configuration file, or passed to the
+code that opens the outlet), the value types `AcquisitionInfo`, `SubjectInfo` and `SessionInfo`
+hold all of its fields, and read them from or write them into a `StreamInfo` in one go.
+
+To reconcile two declarations of the same stream (e.g., a device template and the info that was
+resolved at runtime), `StreamInfo::diff()` lists the fields in which they differ, and
+`StreamInfo::merge_desc()` copies the meta-data of one into the other.
+
+Meta-data that deviate from the recommendations (e.g., abbreviated units, or a `<name>` instead
+of a `<label>` element) are not understood by all consumers. `StreamInfo::validate_xdf_metadata()`
+reports such problems as `MetadataIssue`s, so that they can be caught during development (e.g.,
+in a unit test of the code that declares a stream).
+*/
+
+use crate::{ChannelFormat, StreamInfo, XMLElement, IRREGULAR_RATE};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// A section of the meta-data (i.e., a child element of `desc`).
+#[derive(Clone, Debug)]
+pub(crate) struct Section {
+    desc: XMLElement,
+    name: &'static str,
+}
+
+impl Section {
+    pub(crate) fn new(desc: XMLElement, name: &'static str) -> Section {
+        Section { desc, name }
+    }
+
+    pub(crate) fn get(&self, field: &str) -> String {
+        self.desc.child(self.name).child_value_named(field)
+    }
+
+    pub(crate) fn set(&mut self, field: &str, value: &str) {
+        let mut section = self.desc.child(self.name);
+        if section.empty() {
+            section = self.desc.append_child(self.name);
+        }
+        section.set_child_value_as(field, value.to_string());
+    }
+
+    pub(crate) fn remove(&mut self, field: &str) {
+        let mut section = self.desc.child(self.name);
+        if !section.empty() {
+            section.remove_child_named(field);
+        }
+    }
+}
+
+/// The `<acquisition>` section of the meta-data, which describes the recording device.
+#[derive(Clone, Debug)]
+pub struct Acquisition {
+    section: Section,
+}
+
+impl Acquisition {
+    pub(crate) fn new(desc: XMLElement) -> Acquisition {
+        Acquisition {
+            section: Section::new(desc, "acquisition"),
+        }
+    }
+
+    /// The manufacturer of the device (e.g., "BioSemi").
+    pub fn manufacturer(&self) -> String {
+        self.section.get("manufacturer")
+    }
+
+    /// Set the manufacturer of the device.
+    pub fn set_manufacturer(&mut self, manufacturer: &str) -> &mut Acquisition {
+        self.section.set("manufacturer", manufacturer);
+        self
+    }
+
+    /// The model of the device (e.g., "ActiveTwo").
+    pub fn model(&self) -> String {
+        self.section.get("model")
+    }
+
+    /// Set the model of the device.
+    pub fn set_model(&mut self, model: &str) -> &mut Acquisition {
+        self.section.set("model", model);
+        self
+    }
+
+    /// The serial number of the device.
+    pub fn serial_number(&self) -> String {
+        self.section.get("serial_number")
+    }
+
+    /// Set the serial number of the device.
+    pub fn set_serial_number(&mut self, serial_number: &str) -> &mut Acquisition {
+        self.section.set("serial_number", serial_number);
+        self
+    }
+
+    /// The number of bits per sample of the device's A/D converter, if declared.
+    pub fn precision(&self) -> Option<u32> {
+        self.section.get("precision").trim().parse().ok()
+    }
+
+    /// Set the number of bits per sample of the device's A/D converter.
+    pub fn set_precision(&mut self, precision: u32) -> &mut Acquisition {
+        self.section.set("precision", &precision.to_string());
+        self
+    }
+
+    /**
+    The lag, in seconds, by which the time stamps have already been corrected for the delay of
+    the device (i.e., that a recorder should not subtract again), if declared.
+    */
+    pub fn compensated_lag(&self) -> Option<f64> {
+        self.section.get("compensated_lag").trim().parse().ok()
+    }
+
+    /// Set the lag by which the time stamps have been corrected (see `compensated_lag()`).
+    pub fn set_compensated_lag(&mut self, lag: f64) -> &mut Acquisition {
+        self.section.set("compensated_lag", &lag.to_string());
+        self
+    }
+}
+
+/// The fields of the `<acquisition>` section of the meta-data, as a value (see `Acquisition`).
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct AcquisitionInfo {
+    /// The manufacturer of the device (e.g., "BioSemi"); empty if not known.
+    pub manufacturer: String,
+    /// The model of the device (e.g., "ActiveTwo"); empty if not known.
+    pub model: String,
+    /// The number of bits per sample of the device's A/D converter, if known.
+    pub precision: Option<u32>,
+    /// The lag, in seconds, by which the time stamps have been corrected for the delay of the
+    /// device (see `Acquisition::compensated_lag()`), if any.
+    pub compensated_lag: Option<f64>,
+}
+
+impl AcquisitionInfo {
+    /**
+    Read the fields from the meta-data of a stream.
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`).
+    */
+    pub fn from_info(info: &StreamInfo) -> AcquisitionInfo {
+        let acquisition = Acquisition::new(info.desc());
+        AcquisitionInfo {
+            manufacturer: acquisition.manufacturer(),
+            model: acquisition.model(),
+            precision: acquisition.precision(),
+            compensated_lag: acquisition.compensated_lag(),
+        }
+    }
+
+    /**
+    Write the fields into the meta-data of a stream (replacing previously declared ones).
+
+    Empty fields are not written, so that fields which are declared elsewhere (e.g., by the
+    device driver) are kept.
+    */
+    pub fn describe(&self, info: &mut StreamInfo) {
+        let mut acquisition = info.acquisition();
+        if !self.manufacturer.is_empty() {
+            acquisition.set_manufacturer(&self.manufacturer);
+        }
+        if !self.model.is_empty() {
+            acquisition.set_model(&self.model);
+        }
+        if let Some(precision) = self.precision {
+            acquisition.set_precision(precision);
+        }
+        if let Some(lag) = self.compensated_lag {
+            acquisition.set_compensated_lag(lag);
+        }
+    }
+}
+
+/// The `<cap>` section of the meta-data, which describes the EEG cap (or a similar headset).
+#[derive(Clone, Debug)]
+pub struct Cap {
+    section: Section,
+}
+
+impl Cap {
+    pub(crate) fn new(desc: XMLElement) -> Cap {
+        Cap {
+            section: Section::new(desc, "cap"),
+        }
+    }
+
+    /// The name of the cap (e.g., "EasyCap M1").
+    pub fn name(&self) -> String {
+        self.section.get("name")
+    }
+
+    /// Set the name of the cap.
+    pub fn set_name(&mut self, name: &str) -> &mut Cap {
+        self.section.set("name", name);
+        self
+    }
+
+    /// The size of the cap (e.g., "56" for the head circumference in cm).
+    pub fn size(&self) -> String {
+        self.section.get("size")
+    }
+
+    /// Set the size of the cap.
+    pub fn set_size(&mut self, size: &str) -> &mut Cap {
+        self.section.set("size", size);
+        self
+    }
+
+    /// The scheme of the channel labels (e.g., "10-20").
+    pub fn label_scheme(&self) -> String {
+        self.section.get("labelscheme")
+    }
+
+    /// Set the scheme of the channel labels.
+    pub fn set_label_scheme(&mut self, label_scheme: &str) -> &mut Cap {
+        self.section.set("labelscheme", label_scheme);
+        self
+    }
+}
+
+/// The `<subject>` section of the meta-data, which describes the person being recorded.
+#[derive(Clone, Debug)]
+pub struct Subject {
+    section: Section,
+}
+
+impl Subject {
+    pub(crate) fn new(desc: XMLElement) -> Subject {
+        Subject {
+            section: Section::new(desc, "subject"),
+        }
+    }
+
+    /// The (pseudonymous) id of the subject.
+    pub fn id(&self) -> String {
+        self.section.get("id")
+    }
+
+    /// Set the id of the subject.
+    pub fn set_id(&mut self, id: &str) -> &mut Subject {
+        self.section.set("id", id);
+        self
+    }
+
+    /// The group of the subject in the study design (e.g., "control").
+    pub fn group(&self) -> String {
+        self.section.get("group")
+    }
+
+    /// Set the group of the subject.
+    pub fn set_group(&mut self, group: &str) -> &mut Subject {
+        self.section.set("group", group);
+        self
+    }
+
+    /// The age of the subject, in years, if declared.
+    pub fn age(&self) -> Option<u32> {
+        self.section.get("age").trim().parse().ok()
+    }
+
+    /// Set the age of the subject, in years.
+    pub fn set_age(&mut self, age: u32) -> &mut Subject {
+        self.section.set("age", &age.to_string());
+        self
+    }
+
+    /// The gender of the subject (e.g., "female").
+    pub fn gender(&self) -> String {
+        self.section.get("gender")
+    }
+
+    /// Set the gender of the subject.
+    pub fn set_gender(&mut self, gender: &str) -> &mut Subject {
+        self.section.set("gender", gender);
+        self
+    }
+
+    /// The handedness of the subject (e.g., "right").
+    pub fn handedness(&self) -> String {
+        self.section.get("handedness")
+    }
+
+    /// Set the handedness of the subject.
+    pub fn set_handedness(&mut self, handedness: &str) -> &mut Subject {
+        self.section.set("handedness", handedness);
+        self
+    }
+}
+
+/// The fields of the `<subject>` section of the meta-data, as a value (see `Subject`).
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct SubjectInfo {
+    /// The (pseudonymous) id of the subject; empty if not known.
+    pub id: String,
+    /// The group of the subject in the study design (e.g., "control"); empty if not known.
+    pub group: String,
+    /// The age of the subject, in years, if known.
+    pub age: Option<u32>,
+    /// The gender of the subject; empty if not known.
+    pub gender: String,
+    /// The handedness of the subject (e.g., "right"); empty if not known.
+    pub handedness: String,
+}
+
+impl SubjectInfo {
+    /**
+    Read the fields from the meta-data of a stream.
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`).
+    */
+    pub fn from_info(info: &StreamInfo) -> SubjectInfo {
+        let subject = Subject::new(info.desc());
+        SubjectInfo {
+            id: subject.id(),
+            group: subject.group(),
+            age: subject.age(),
+            gender: subject.gender(),
+            handedness: subject.handedness(),
+        }
+    }
+
+    /// Write the fields into the meta-data of a stream (see `AcquisitionInfo::describe()`).
+    pub fn describe(&self, info: &mut StreamInfo) {
+        let mut subject = info.subject();
+        let fields = [
+            ("id", &self.id),
+            ("group", &self.group),
+            ("gender", &self.gender),
+            ("handedness", &self.handedness),
+        ];
+        for (field, value) in fields.iter() {
+            if !value.is_empty() {
+                subject.section.set(field, value);
+            }
+        }
+        if let Some(age) = self.age {
+            subject.set_age(age);
+        }
+    }
+}
+
+/// The `<session>` section of the meta-data, which describes the recording session.
+#[derive(Clone, Debug)]
+pub struct Session {
+    section: Section,
+}
+
+impl Session {
+    pub(crate) fn new(desc: XMLElement) -> Session {
+        Session {
+            section: Section::new(desc, "session"),
+        }
+    }
+
+    /// The id of the session (e.g., "pre"; see also `lsl::bids`).
+    pub fn id(&self) -> String {
+        self.section.get("id")
+    }
+
+    /// Set the id of the session.
+    pub fn set_id(&mut self, id: &str) -> &mut Session {
+        self.section.set("id", id);
+        self
+    }
+
+    /// The date of the session (preferably as in ISO 8601, e.g., "2024-03-14").
+    pub fn date(&self) -> String {
+        self.section.get("date")
+    }
+
+    /// Set the date of the session.
+    pub fn set_date(&mut self, date: &str) -> &mut Session {
+        self.section.set("date", date);
+        self
+    }
+
+    /// The experimenter who ran the session.
+    pub fn experimenter(&self) -> String {
+        self.section.get("experimenter")
+    }
+
+    /// Set the experimenter who ran the session.
+    pub fn set_experimenter(&mut self, experimenter: &str) -> &mut Session {
+        self.section.set("experimenter", experimenter);
+        self
+    }
+
+    /// Free-form notes on the session.
+    pub fn notes(&self) -> String {
+        self.section.get("notes")
+    }
+
+    /// Set the notes on the session.
+    pub fn set_notes(&mut self, notes: &str) -> &mut Session {
+        self.section.set("notes", notes);
+        self
+    }
+}
+
+/// The fields of the `<session>` section of the meta-data, as a value (see `Session`).
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct SessionInfo {
+    /// The id of the session; empty if not known.
+    pub id: String,
+    /// The date of the session; empty if not known.
+    pub date: String,
+    /// The experimenter who ran the session; empty if not known.
+    pub experimenter: String,
+    /// Free-form notes on the session; empty if there are none.
+    pub notes: String,
+}
+
+impl SessionInfo {
+    /**
+    Read the fields from the meta-data of a stream.
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`).
+    */
+    pub fn from_info(info: &StreamInfo) -> SessionInfo {
+        let session = Session::new(info.desc());
+        SessionInfo {
+            id: session.id(),
+            date: session.date(),
+            experimenter: session.experimenter(),
+            notes: session.notes(),
+        }
+    }
+
+    /// Write the fields into the meta-data of a stream (see `AcquisitionInfo::describe()`).
+    pub fn describe(&self, info: &mut StreamInfo) {
+        let mut session = info.session();
+        let fields = [
+            ("id", &self.id),
+            ("date", &self.date),
+            ("experimenter", &self.experimenter),
+            ("notes", &self.notes),
+        ];
+        for (field, value) in fields.iter() {
+            if !value.is_empty() {
+                session.section.set(field, value);
+            }
+        }
+    }
+}
+
+// the content types for which the XDF recommendations declare the channels (with their labels)
+const TYPES_WITH_CHANNELS: [&str; 8] =
+    ["EEG", "EMG", "ECG", "EOG", "NIRS", "MoCap", "Gaze", "Audio"];
+
+// the content types whose channels are recommended to have a unit
+const TYPES_WITH_UNITS: [&str; 4] = ["EEG", "EMG", "ECG", "EOG"];
+
+// abbreviated units, and how they are spelled out in the recommendations
+const ABBREVIATED_UNITS: [(&str, &str); 9] = [
+    ("uV", "microvolts"),
+    ("\u{b5}V", "microvolts"),
+    ("\u{3bc}V", "microvolts"),
+    ("mV", "millivolts"),
+    ("V", "volts"),
+    ("px", "pixels"),
+    ("mm", "millimeters"),
+    ("cm", "centimeters"),
+    ("m", "meters"),
+];
+
+// misnamed elements below a `<channel>`, and their recommended names
+const CHANNEL_FIELD_NAMES: [(&str, &str); 5] = [
+    ("name", "label"),
+    ("Label", "label"),
+    ("units", "unit"),
+    ("Unit", "unit"),
+    ("Type", "type"),
+];
+
+// misnamed elements below `<desc>`, and their recommended names
+const DESC_FIELD_NAMES: [(&str, &str); 5] = [
+    ("channel", "channels"),
+    ("Channels", "channels"),
+    ("Acquisition", "acquisition"),
+    ("Subject", "subject"),
+    ("Session", "session"),
+];
+
+/// A deviation of a stream's meta-data from the XDF recommendations (see `lsl::meta`).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum MetadataIssue {
+    /// The stream has no content type.
+    MissingType,
+    /// The channels are not declared, although the recommendations for the content type call for
+    /// it.
+    MissingChannels,
+    /// The number of declared channels differs from the channel count of the stream.
+    ChannelCountMismatch {
+        /// The number of declared channels.
+        declared: usize,
+        /// The channel count of the stream.
+        expected: usize,
+    },
+    /// A channel has no label.
+    MissingLabel {
+        /// The index of the channel.
+        channel: usize,
+    },
+    /// Several channels have the same label.
+    DuplicateLabel {
+        /// The label.
+        label: String,
+    },
+    /// A channel has no unit, although the recommendations for the content type call for it.
+    MissingUnit {
+        /// The index of the channel.
+        channel: usize,
+    },
+    /// A unit is abbreviated rather than spelled out (e.g., "uV" instead of "microvolts").
+    AbbreviatedUnit {
+        /// The index of the channel.
+        channel: usize,
+        /// The declared unit.
+        unit: String,
+        /// The recommended spelling.
+        expected: &'static str,
+    },
+    /// An element has a name that deviates from the recommended one (e.g., `units`).
+    MisnamedElement {
+        /// The path of the element (e.g., "desc/channels/channel[2]/units").
+        path: String,
+        /// The recommended name.
+        expected: &'static str,
+    },
+    /// A stream of type "Markers" does not have a single string channel with an irregular rate.
+    NonstandardMarkers,
+}
+
+impl fmt::Display for MetadataIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataIssue::MissingType => write!(f, "the stream has no content type"),
+            MetadataIssue::MissingChannels => write!(f, "the channels are not declared"),
+            MetadataIssue::ChannelCountMismatch { declared, expected } => write!(
+                f,
+                "{} channels are declared, but the stream has {}",
+                declared, expected
+            ),
+            MetadataIssue::MissingLabel { channel } => {
+                write!(f, "channel {} has no label", channel)
+            }
+            MetadataIssue::DuplicateLabel { label } => {
+                write!(f, "several channels are labeled '{}'", label)
+            }
+            MetadataIssue::MissingUnit { channel } => write!(f, "channel {} has no unit", channel),
+            MetadataIssue::AbbreviatedUnit {
+                channel,
+                unit,
+                expected,
+            } => write!(
+                f,
+                "the unit of channel {} is '{}' rather than '{}'",
+                channel, unit, expected
+            ),
+            MetadataIssue::MisnamedElement { path, expected } => {
+                write!(f, "the element {} should be named '{}'", path, expected)
+            }
+            MetadataIssue::NonstandardMarkers => write!(
+                f,
+                "a marker stream should have a single string channel with an irregular rate"
+            ),
+        }
+    }
+}
+
+// Check the meta-data of a stream (see `StreamInfo::validate_xdf_metadata()`).
+pub(crate) fn validate(info: &StreamInfo) -> Vec<MetadataIssue> {
+    let mut issues = vec![];
+    let type_ = info.stream_type();
+    if type_.is_empty() {
+        issues.push(MetadataIssue::MissingType);
+    }
+    if type_ == "Markers"
+        && (info.channel_count() != 1
+            || info.channel_format() != ChannelFormat::String
+            || info.nominal_srate() != IRREGULAR_RATE)
+    {
+        issues.push(MetadataIssue::NonstandardMarkers);
+    }
+    let desc = info.desc();
+    for (name, expected) in misnamed(&desc, &DESC_FIELD_NAMES) {
+        issues.push(MetadataIssue::MisnamedElement {
+            path: format!("desc/{}", name),
+            expected,
+        });
+    }
+    let channels = info.channels();
+    if channels.is_empty() {
+        if TYPES_WITH_CHANNELS.contains(&type_.as_str()) {
+            issues.push(MetadataIssue::MissingChannels);
+        }
+        return issues;
+    }
+    if channels.len() != info.channel_count() as usize {
+        issues.push(MetadataIssue::ChannelCountMismatch {
+            declared: channels.len(),
+            expected: info.channel_count() as usize,
+        });
+    }
+    let mut element = desc.child("channels").child("channel");
+    let mut labels = HashSet::new();
+    for (k, channel) in channels.iter().enumerate() {
+        for (name, expected) in misnamed(&element, &CHANNEL_FIELD_NAMES) {
+            issues.push(MetadataIssue::MisnamedElement {
+                path: format!("desc/channels/channel[{}]/{}", k, name),
+                expected,
+            });
+        }
+        element = element.next_sibling_named("channel");
+        if channel.label.is_empty() {
+            issues.push(MetadataIssue::MissingLabel { channel: k });
+        } else if !labels.insert(&channel.label) {
+            issues.push(MetadataIssue::DuplicateLabel {
+                label: channel.label.clone(),
+            });
+        }
+        if channel.unit.is_empty() && TYPES_WITH_UNITS.contains(&type_.as_str()) {
+            issues.push(MetadataIssue::MissingUnit { channel: k });
+        }
+        let abbreviation = ABBREVIATED_UNITS
+            .iter()
+            .find(|(unit, _)| *unit == channel.unit);
+        if let Some(&(_, expected)) = abbreviation {
+            issues.push(MetadataIssue::AbbreviatedUnit {
+                channel: k,
+                unit: channel.unit.clone(),
+                expected,
+            });
+        }
+    }
+    issues
+}
+
+// The names of the child elements of an element that are listed as misnamed, along with their
+// recommended names.
+fn misnamed(element: &XMLElement, names: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    let mut result = vec![];
+    let mut child = element.first_child();
+    while child.is_valid() && !child.empty() {
+        let name = child.name();
+        if let Some(&(_, expected)) = names.iter().find(|(misnamed, _)| *misnamed == name) {
+            result.push((name, expected));
+        }
+        child = child.next_sibling();
+    }
+    result
+}
+
+/// A field in which the declarations of two streams differ (see `StreamInfo::diff()`).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Difference {
+    /// The path of the field, e.g., "nominal_srate" for a core field, or
+    /// "desc/channels/channel[1]/label" for an element of the meta-data (where `[k]` marks the
+    /// k-th element of that name, counting from 0, and is left out for the first one).
+    pub path: String,
+    /// The value in the first declaration, or `None` if it does not have the field.
+    pub left: Option<String>,
+    /// The value in the second declaration, or `None` if it does not have the field.
+    pub right: Option<String>,
+}
+
+/// How `StreamInfo::merge_desc()` resolves elements that are declared by both infos.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum MergePolicy {
+    /// Keep the element of the info that is merged into (e.g., to fill in a declaration from a
+    /// template without overriding what it already declares).
+    KeepExisting,
+    /// Replace the element by the one of the other info.
+    Overwrite,
+}
+
+// Compare two declarations (see `StreamInfo::diff()`).
+pub(crate) fn diff(left: &StreamInfo, right: &StreamInfo) -> Vec<Difference> {
+    let (left, right) = (fields(left), fields(right));
+    let lookup = |fields: &[(String, String)]| -> HashMap<String, String> {
+        fields.iter().cloned().collect()
+    };
+    let (left_values, right_values) = (lookup(&left), lookup(&right));
+    let mut result = vec![];
+    for (path, value) in &left {
+        let other = right_values.get(path);
+        if other != Some(value) {
+            result.push(Difference {
+                path: path.clone(),
+                left: Some(value.clone()),
+                right: other.cloned(),
+            });
+        }
+    }
+    for (path, value) in right {
+        if !left_values.contains_key(&path) {
+            result.push(Difference {
+                path,
+                left: None,
+                right: Some(value),
+            });
+        }
+    }
+    result
+}
+
+// The core fields and the leaves of the meta-data of a stream, with their paths and values.
+fn fields(info: &StreamInfo) -> Vec<(String, String)> {
+    let core = [
+        ("name", info.stream_name()),
+        ("type", info.stream_type()),
+        ("channel_count", info.channel_count().to_string()),
+        ("nominal_srate", info.nominal_srate().to_string()),
+        ("channel_format", info.channel_format().to_string()),
+        ("source_id", info.source_id()),
+    ];
+    let mut fields: Vec<_> = core
+        .iter()
+        .map(|(path, value)| (path.to_string(), value.clone()))
+        .collect();
+    leaves(&info.desc(), "desc", &mut fields);
+    fields
+}
+
+// Collect the elements below an element that have no child elements, with their paths and text.
+fn leaves(element: &XMLElement, path: &str, result: &mut Vec<(String, String)>) {
+    for (path, child) in children(element, path) {
+        if children(&child, "").is_empty() {
+            result.push((path, child.child_value()));
+        } else {
+            leaves(&child, &path, result);
+        }
+    }
+}
+
+// The child elements of an element, with their paths (see `Difference`) below the given one.
+fn children(element: &XMLElement, path: &str) -> Vec<(String, XMLElement)> {
+    let mut result = vec![];
+    let mut occurrences = HashMap::new();
+    let mut child = element.first_child();
+    while child.is_valid() && !child.empty() {
+        if !child.is_text() {
+            let name = child.name();
+            let k = occurrences.entry(name.clone()).or_insert(0);
+            let path = match *k {
+                0 => format!("{}/{}", path, name),
+                k => format!("{}/{}[{}]", path, name, k),
+            };
+            *k += 1;
+            result.push((path, child.clone()));
+        }
+        child = child.next_sibling();
+    }
+    result
+}
+
+// Merge the child elements of `source` into `target` (see `StreamInfo::merge_desc()`).
+pub(crate) fn merge(target: &mut XMLElement, source: &XMLElement, policy: MergePolicy) {
+    for (path, child) in children(source, "") {
+        // (looked up anew, since replacing an element moves its siblings)
+        let existing = children(target, "");
+        let current = match existing.iter().find(|(other, _)| *other == path) {
+            Some((_, current)) => current.clone(),
+            None => {
+                target.append_copy(child);
+                continue;
+            }
+        };
+        let nested = !children(&child, "").is_empty();
+        if nested && !children(&current, "").is_empty() {
+            merge(&mut current.clone(), &child, policy);
+        } else if policy == MergePolicy::Overwrite {
+            let mut text = current.first_child();
+            if !nested && text.is_valid() && text.is_text() {
+                // (keep the element in place, so that the paths of its siblings do not change)
+                text.set_value(&child.child_value());
+            } else {
+                replace_child(target, current, child);
+            }
+        }
+    }
+}
+
+// Replace a child element of `parent` by a copy of `replacement`, in place. The native library
+// cannot insert an element before another one, so the siblings that follow the replaced element
+// are moved behind the copy instead (keeping their order).
+fn replace_child(parent: &mut XMLElement, current: XMLElement, replacement: XMLElement) {
+    let mut following = vec![];
+    let mut sibling = current.next_sibling();
+    while sibling.is_valid() && !sibling.empty() {
+        following.push(sibling.clone());
+        sibling = sibling.next_sibling();
+    }
+    parent.remove_child(current);
+    parent.append_copy(replacement);
+    for sibling in following {
+        parent.append_copy(sibling.clone());
+        parent.remove_child(sibling);
+    }
+}
@@ -0,0 +1,235 @@
+/*!
+Playback of CSV files as streams.
+
+This is a lightweight way to serve pre-recorded data (e.g., for demos or as regression data)
+without requiring XDF files. The `CsvPlayer` publishes the rows of a simple CSV file as the
+samples of a stream, paced according to their time stamps (or the declared sampling rate).
+
+Two layouts are supported:
+* With a header row, which holds the channel labels (these are written to the stream's meta-data
+  as `<channels><channel><label>`). If the first column is named `timestamp` or `time`, it holds
+  the time stamps of the samples, in seconds.
+* Without a header row, in which case all columns are channels, and a sampling rate has to be
+  declared to pace the data.
+
+```no_run
+use lsl::playback::{CsvData, CsvPlayer};
+
+fn main() -> Result<(), lsl::Error> {
+    let data = CsvData::open("recording.csv", true)?;
+    let player = CsvPlayer::new(data, "Replay", "EEG", 250.0)?;
+    player.play()?;
+    Ok(())
+}
+```
+*/
+
+use crate::clock::{Clock, RealClock};
+use crate::{ChannelFormat, Error, ExPushable, Result, StreamInfo, StreamOutlet};
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::path::Path;
+
+/// The contents of a CSV file (see module documentation).
+#[derive(PartialEq, Clone, Debug)]
+pub struct CsvData {
+    /// Channel labels, as given in the header row (empty for headerless files).
+    pub labels: Vec<String>,
+    /// Time stamps of the samples, in seconds, if the file has a time stamp column.
+    pub timestamps: Option<Vec<f64>>,
+    /// The samples (rows), each holding one value per channel.
+    pub samples: Vec<Vec<f64>>,
+}
+
+impl CsvData {
+    /**
+    Read CSV data from a reader.
+
+    Arguments:
+    * `reader`: The source of the data.
+    * `has_header`: Whether the first (non-empty) line is a header row with column names.
+
+    Returns an `Error::Parse` if the data are malformed, e.g., if a value is not a number or if
+    the rows have differing numbers of columns.
+    */
+    pub fn read<R: BufRead>(reader: R, has_header: bool) -> Result<CsvData> {
+        let mut labels: Vec<String> = vec![];
+        let mut has_timestamps = false;
+        let mut columns = None;
+        let mut timestamps = vec![];
+        let mut samples = vec![];
+        for (k, line) in reader.lines().enumerate() {
+            let line = line?;
+            let number = k + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|x| x.trim()).collect();
+            if columns.is_none() && has_header {
+                columns = Some(fields.len());
+                labels = fields.iter().map(|x| x.to_string()).collect();
+                if let Some(first) = labels.first() {
+                    let first = first.to_lowercase();
+                    has_timestamps = first == "timestamp" || first == "time";
+                }
+                if has_timestamps {
+                    labels.remove(0);
+                }
+                continue;
+            }
+            if *columns.get_or_insert(fields.len()) != fields.len() {
+                return Err(parse_error(number, "inconsistent number of columns"));
+            }
+            let mut values = fields
+                .iter()
+                .map(|x| x.parse::<f64>())
+                .collect::<std::result::Result<Vec<f64>, _>>()
+                .map_err(|_| parse_error(number, "value is not a number"))?;
+            if has_timestamps {
+                let ts = values.remove(0);
+                if matches!(timestamps.last(), Some(&last) if ts < last) {
+                    return Err(parse_error(
+                        number,
+                        "time stamps are not in ascending order",
+                    ));
+                }
+                timestamps.push(ts);
+            }
+            samples.push(values);
+        }
+        if samples.is_empty() || samples[0].is_empty() {
+            return Err(parse_error(1, "file holds no channel data"));
+        }
+        Ok(CsvData {
+            labels,
+            timestamps: if has_timestamps {
+                Some(timestamps)
+            } else {
+                None
+            },
+            samples,
+        })
+    }
+
+    /**
+    Read CSV data from a file.
+
+    Arguments:
+    * `path`: The path of the file.
+    * `has_header`: Whether the first (non-empty) line is a header row with column names.
+
+    Returns an `Error::Io` if the file cannot be read, or an `Error::Parse` if it is malformed.
+    */
+    pub fn open<P: AsRef<Path>>(path: P, has_header: bool) -> Result<CsvData> {
+        let file = fs::File::open(path)?;
+        CsvData::read(io::BufReader::new(file), has_header)
+    }
+
+    /// The number of channels.
+    pub fn channel_count(&self) -> usize {
+        self.samples.first().map_or(0, |x| x.len())
+    }
+}
+
+/**
+Publishes the contents of a CSV file as a stream, with the original pacing.
+
+The samples are sent as `ChannelFormat::Double64` values.
+*/
+#[derive(Debug)]
+pub struct CsvPlayer {
+    outlet: StreamOutlet,
+    data: CsvData,
+    srate: f64,
+    clock: Box<dyn Clock>,
+}
+
+impl CsvPlayer {
+    /**
+    Create a new player and make its stream discoverable.
+
+    Arguments:
+    * `data`: The data to play back.
+    * `name`: Name of the stream.
+    * `type_`: Content type of the stream (e.g., "EEG").
+    * `srate`: The sampling rate of the data, which is declared as the nominal rate of the stream.
+      If the data have time stamps, this can also be `lsl::IRREGULAR_RATE` (in which case the
+      samples are paced by their time stamps only); otherwise, a positive rate is required.
+    */
+    pub fn new(data: CsvData, name: &str, type_: &str, srate: f64) -> Result<CsvPlayer> {
+        if srate < 0.0 || (srate == 0.0 && data.timestamps.is_none()) {
+            return Err(Error::BadArgument);
+        }
+        let mut info = StreamInfo::new(
+            name,
+            type_,
+            data.channel_count() as u32,
+            srate,
+            ChannelFormat::Double64,
+            "",
+        )?;
+        if !data.labels.is_empty() {
+            let mut channels = info.desc_mut().append_child("channels");
+            for label in &data.labels {
+                channels
+                    .append_child("channel")
+                    .append_child_value("label", label);
+            }
+        }
+        Ok(CsvPlayer {
+            outlet: StreamOutlet::new(&info, 0, 360)?,
+            data,
+            srate,
+            clock: Box::new(RealClock),
+        })
+    }
+
+    /**
+    Play back all samples, in real time.
+
+    The first sample is sent immediately, and each subsequent one at the same time offset
+    (relative to the first one) that it has in the data; the samples are time-stamped
+    accordingly. This function blocks until all samples have been sent.
+    */
+    pub fn play(&self) -> Result<()> {
+        // offset that maps the time of a sample in the data to the player's clock
+        let offset = self.clock.now() - self.time(0);
+        for (k, sample) in self.data.samples.iter().enumerate() {
+            let timestamp = self.time(k) + offset;
+            self.clock.sleep_until(timestamp);
+            self.outlet.push_sample_ex(sample, timestamp, true)?;
+        }
+        Ok(())
+    }
+
+    /**
+    Set the clock that paces the playback and provides the time stamps (by default, the
+    `RealClock`); see `lsl::clock`.
+    */
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.clock = Box::new(clock);
+    }
+
+    /// The data that is played back.
+    pub fn data(&self) -> &CsvData {
+        &self.data
+    }
+
+    /// The outlet through which the data are sent.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+
+    // Time of the k'th sample in the data, in seconds.
+    fn time(&self, k: usize) -> f64 {
+        match &self.data.timestamps {
+            Some(timestamps) => timestamps[k],
+            None => k as f64 / self.srate,
+        }
+    }
+}
+
+fn parse_error(line: usize, reason: &'static str) -> Error {
+    Error::Parse { line, reason }
+}
@@ -0,0 +1,249 @@
+/*!
+Building XPath predicates for resolving streams.
+
+`lsl::resolve_bypred()` and related functions select streams via an XPath 1.0 predicate over
+their XML declaration. Writing these by hand is error-prone (e.g., a misspelled field, or a
+value with a quote in it), so the `Query` builder renders them from typed conditions, with the
+values quoted correctly. A `Query` can be passed wherever a predicate string is accepted:
+
+```no_run
+use lsl::query::Query;
+
+fn main() -> Result<(), lsl::Error> {
+    let query = Query::new().type_is("EEG").name_starts_with("BioSemi").channel_count(32);
+    let streams = lsl::resolve_bypred(&query, 1, 5.0)?;
+    println!("found {} streams matching {}", streams.len(), query);
+    Ok(())
+}
+```
+
+
+For one-liners (e.g., in experiment scripts), the `lsl::resolve!` macro builds such a query from
+conditions of the form `property == value` (for equality) or `property ~= prefix` (for names
+and other text properties that start with a prefix), joined by `&&`, and resolves it right away:
+
+```no_run
+fn main() -> Result<(), lsl::Error> {
+    let streams = lsl::resolve!(type == "EEG" && name ~= "BioSemi", min = 1, timeout = 2.0)?;
+    let name = "Markers";
+    let markers = lsl::resolve!(name == (name))?;
+    println!("found {} EEG and {} marker streams", streams.len(), markers.len());
+    Ok(())
+}
+```
+*/
+
+use crate::{Error, ResolveProp, Result};
+use std::fmt;
+
+/**
+Resolve the streams that match a set of conditions (see the `lsl::query` module documentation).
+
+The conditions are joined by `&&`, and each has the form `property == value` or
+`property ~= prefix`, where the property is a name such as `name`, `type`, `source_id`,
+`hostname` or `channel_count`, and the value is a literal or a parenthesized expression. They
+can be followed by `min = ...` (the minimum number of streams to wait for, by default 1) and
+`timeout = ...` (in seconds, by default `lsl::FOREVER`). The macro expands into a `Query` that
+is passed to `lsl::resolve_bypred()`, and evaluates to the result of that call.
+*/
+#[macro_export]
+macro_rules! resolve {
+    (@munch [$q:expr] $prop:ident == $value:tt && $($rest:tt)+) => {
+        $crate::resolve!(@munch [$q.prop_is(stringify!($prop), $value)] $($rest)+)
+    };
+    (@munch [$q:expr] $prop:ident ~ = $value:tt && $($rest:tt)+) => {
+        $crate::resolve!(@munch [$q.prop_starts_with(stringify!($prop), $value)] $($rest)+)
+    };
+    (@munch [$q:expr] $prop:ident == $value:tt $(, $($options:tt)*)?) => {
+        $crate::resolve!(@finish [$q.prop_is(stringify!($prop), $value)] $($($options)*)?)
+    };
+    (@munch [$q:expr] $prop:ident ~ = $value:tt $(, $($options:tt)*)?) => {
+        $crate::resolve!(@finish [$q.prop_starts_with(stringify!($prop), $value)] $($($options)*)?)
+    };
+    (@finish [$q:expr]) => {
+        $crate::resolve_bypred(&$q, 1, $crate::FOREVER)
+    };
+    (@finish [$q:expr] min = $min:expr) => {
+        $crate::resolve_bypred(&$q, $min, $crate::FOREVER)
+    };
+    (@finish [$q:expr] timeout = $timeout:expr) => {
+        $crate::resolve_bypred(&$q, 1, $timeout)
+    };
+    (@finish [$q:expr] min = $min:expr, timeout = $timeout:expr) => {
+        $crate::resolve_bypred(&$q, $min, $timeout)
+    };
+    (@finish [$q:expr] timeout = $timeout:expr, min = $min:expr) => {
+        $crate::resolve_bypred(&$q, $min, $timeout)
+    };
+    ($($conditions:tt)+) => {
+        $crate::resolve!(@munch [$crate::query::Query::new()] $($conditions)+)
+    };
+}
+
+/**
+A predicate over stream declarations, built from conditions that must all hold.
+
+An empty query matches every stream.
+*/
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Query {
+    // the rendered predicate (empty for no conditions)
+    xpath: String,
+}
+
+impl Query {
+    /// Create a query without conditions.
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Require the stream's name to equal the given one.
+    pub fn name_is(self, name: &str) -> Query {
+        self.prop_is(ResolveProp::Name, name)
+    }
+
+    /// Require the stream's content type to equal the given one (e.g., "EEG").
+    pub fn type_is(self, type_: &str) -> Query {
+        self.prop_is(ResolveProp::Type, type_)
+    }
+
+    /// Require the stream's source id to equal the given one.
+    pub fn source_id_is(self, source_id: &str) -> Query {
+        self.prop_is(ResolveProp::SourceId, source_id)
+    }
+
+    /// Require the stream to be provided by the given host.
+    pub fn hostname_is(self, hostname: &str) -> Query {
+        self.prop_is(ResolveProp::Hostname, hostname)
+    }
+
+    /// Require the stream's name to start with the given prefix.
+    pub fn name_starts_with(self, prefix: &str) -> Query {
+        self.prop_starts_with(ResolveProp::Name, prefix)
+    }
+
+    /// Require the stream to have the given number of channels.
+    pub fn channel_count(self, count: u32) -> Query {
+        self.and_raw(&format!("channel_count={}", count))
+    }
+
+    /**
+    Require the stream to have the given nominal sampling rate (0 for irregular streams).
+
+    Returns an `Error::BadArgument` if the rate is not finite, which XPath cannot express.
+    */
+    pub fn nominal_srate(self, srate: f64) -> Result<Query> {
+        if !srate.is_finite() {
+            return Err(Error::BadArgument);
+        }
+        Ok(self.and_raw(&format!("nominal_srate={}", srate)))
+    }
+
+    /// Require a property of the stream to equal the given value (a string or a number).
+    pub fn prop_is<'a>(self, prop: impl Into<ResolveProp<'a>>, value: impl QueryValue) -> Query {
+        let condition = format!("{}={}", prop.into().name(), value.to_xpath());
+        self.and_raw(&condition)
+    }
+
+    /// Require a property of the stream to start with the given prefix.
+    pub fn prop_starts_with<'a>(self, prop: impl Into<ResolveProp<'a>>, prefix: &str) -> Query {
+        let condition = format!("starts-with({},{})", prop.into().name(), literal(prefix));
+        self.and_raw(&condition)
+    }
+
+    /// Require a property of the stream to contain the given text.
+    pub fn prop_contains<'a>(self, prop: impl Into<ResolveProp<'a>>, text: &str) -> Query {
+        let condition = format!("contains({},{})", prop.into().name(), literal(text));
+        self.and_raw(&condition)
+    }
+
+    /**
+    Add a condition given as a raw XPath 1.0 predicate (e.g., for conditions that the builder
+    does not cover). The predicate is not checked.
+    */
+    pub fn and_raw(mut self, predicate: &str) -> Query {
+        if self.xpath.is_empty() {
+            self.xpath = format!("({})", predicate);
+        } else {
+            self.xpath = format!("{} and ({})", self.xpath, predicate);
+        }
+        self
+    }
+
+    /// Combine this query with another one, such that streams that match either one match.
+    pub fn or(self, other: Query) -> Query {
+        if self.xpath.is_empty() || other.xpath.is_empty() {
+            return Query::new();
+        }
+        Query {
+            xpath: format!("({} or {})", self.xpath, other.xpath),
+        }
+    }
+
+    /// The rendered XPath predicate.
+    pub fn as_str(&self) -> &str {
+        if self.xpath.is_empty() {
+            "true()"
+        } else {
+            &self.xpath
+        }
+    }
+}
+
+impl AsRef<str> for Query {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A value that a stream property can be compared with in a `Query` (a string or a number).
+pub trait QueryValue {
+    /// The value as an XPath 1.0 expression (e.g., a quoted string literal).
+    fn to_xpath(&self) -> String;
+}
+
+impl QueryValue for &str {
+    fn to_xpath(&self) -> String {
+        literal(self)
+    }
+}
+
+impl QueryValue for String {
+    fn to_xpath(&self) -> String {
+        literal(self)
+    }
+}
+
+macro_rules! impl_query_value_number {
+    ($($t:ty),*) => {
+        $(impl QueryValue for $t {
+            fn to_xpath(&self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+impl_query_value_number!(i32, u32, i64, u64, usize, f32, f64);
+
+// Quote a string as an XPath 1.0 literal (which has no escape sequences, so strings with both
+// kinds of quotes are assembled via concat()).
+fn literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<_> = value
+            .split('\'')
+            .map(|part| format!("'{}'", part))
+            .collect();
+        format!("concat({})", parts.join(",\"'\","))
+    }
+}
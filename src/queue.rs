@@ -0,0 +1,218 @@
+/*!
+Bounded queues with a configurable policy for when the consumer stalls.
+
+Components that hand samples over from one thread to another (such as the `Broadcaster` in
+`lsl::broadcast`) need to decide what happens when the consumer falls behind and the queue in
+between fills up: the data can be kept complete at the cost of latency (by blocking the producer),
+or the latency can be kept low at the cost of completeness (by dropping samples). A `DropPolicy`
+selects between these trade-offs, and the queue counts the samples that it dropped, so that the
+consequences can be monitored.
+
+With the `parking_lot` feature, the queues use the locks of the `parking_lot` crate instead of
+those of the standard library (as do the workers in `lsl::worker`).
+*/
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "parking_lot")]
+use parking_lot::{Condvar, Mutex, MutexGuard};
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// What a bounded queue does with a new item when it is full.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum DropPolicy {
+    /// Drop the oldest queued item to make room (favors low latency).
+    DropOldest,
+    /// Drop the new item (favors the data that was produced first).
+    DropNewest,
+    /// Block the producer until there is room (favors completeness; no data is dropped).
+    Block,
+}
+
+/// A bounded, thread-safe FIFO queue that applies a `DropPolicy` when it is full.
+#[derive(Debug)]
+pub struct SampleQueue<T> {
+    state: Mutex<State<T>>,
+    // signaled when an item was added or the queue was closed
+    not_empty: Condvar,
+    // signaled when an item was removed or the queue was closed
+    not_full: Condvar,
+    capacity: usize,
+    policy: DropPolicy,
+    dropped: AtomicU64,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+impl<T> SampleQueue<T> {
+    /**
+    Create a new queue.
+
+    Arguments:
+    * `capacity`: The maximum number of queued items (at least 1).
+    * `policy`: What to do with a new item when the queue is full.
+    */
+    pub fn new(capacity: usize, policy: DropPolicy) -> SampleQueue<T> {
+        let capacity = capacity.max(1);
+        SampleQueue {
+            state: Mutex::new(State {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            policy,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /**
+    Add an item to the queue, applying the drop policy if the queue is full.
+
+    Returns false if the item was not queued, i.e., if it was dropped under the `DropNewest`
+    policy or if the queue has been closed.
+    */
+    pub fn push(&self, item: T) -> bool {
+        let mut state = self.lock();
+        if state.items.len() >= self.capacity {
+            match self.policy {
+                DropPolicy::DropOldest => {
+                    state.items.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                DropPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return false;
+                }
+                DropPolicy::Block => {
+                    while state.items.len() >= self.capacity && !state.closed {
+                        state = wait(&self.not_full, state, None);
+                    }
+                }
+            }
+        }
+        if state.closed {
+            return false;
+        }
+        state.items.push_back(item);
+        self.not_empty.notify_one();
+        true
+    }
+
+    /**
+    Remove the oldest item from the queue, waiting up to `timeout` seconds for one to arrive.
+
+    Returns `None` if no item arrived in time, or if the queue is empty and closed.
+    */
+    pub fn pop(&self, timeout: f64) -> Option<T> {
+        let deadline = Instant::now() + Duration::from_secs_f64(timeout.clamp(0.0, 1e9));
+        let mut state = self.lock();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                self.not_full.notify_one();
+                return Some(item);
+            }
+            let now = Instant::now();
+            if state.closed || now >= deadline {
+                return None;
+            }
+            state = wait(&self.not_empty, state, Some(deadline - now));
+        }
+    }
+
+    /// Remove all items that are currently queued.
+    pub fn drain(&self) -> Vec<T> {
+        let items = self.lock().items.drain(..).collect();
+        self.not_full.notify_all();
+        items
+    }
+
+    /**
+    Close the queue: subsequent pushes are rejected, and blocked producers and consumers are
+    woken up (consumers can still pop the items that remain queued).
+    */
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Whether the queue has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// The number of items that have been dropped so far due to the drop policy.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// The number of currently queued items.
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    /// Whether the queue is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of queued items.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The drop policy of the queue.
+    pub fn policy(&self) -> DropPolicy {
+        self.policy
+    }
+
+    #[cfg(feature = "parking_lot")]
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock()
+    }
+
+    #[cfg(not(feature = "parking_lot"))]
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// Wait on a condition variable, for at most the given time if there is one.
+#[cfg(feature = "parking_lot")]
+fn wait<'a, T>(
+    cond: &Condvar,
+    mut guard: MutexGuard<'a, T>,
+    timeout: Option<Duration>,
+) -> MutexGuard<'a, T> {
+    match timeout {
+        Some(timeout) => {
+            cond.wait_for(&mut guard, timeout);
+        }
+        None => cond.wait(&mut guard),
+    }
+    guard
+}
+
+#[cfg(not(feature = "parking_lot"))]
+fn wait<'a, T>(
+    cond: &Condvar,
+    guard: MutexGuard<'a, T>,
+    timeout: Option<Duration>,
+) -> MutexGuard<'a, T> {
+    match timeout {
+        Some(timeout) => match cond.wait_timeout(guard, timeout) {
+            Ok((guard, _)) => guard,
+            Err(e) => e.into_inner().0,
+        },
+        None => cond.wait(guard).unwrap_or_else(|e| e.into_inner()),
+    }
+}
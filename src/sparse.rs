@@ -0,0 +1,138 @@
+/*!
+Sparse (event-sampled) numeric streams.
+
+Some numeric signals are not sampled at a fixed rate, but only when something happens, e.g., a
+button press along with the measured force, or a response time. Such streams must be declared
+with an irregular rate (`lsl::IRREGULAR_RATE`), and each value must be time-stamped when it
+occurs; declaring them with a nominal rate instead would make consumers (e.g., recorders)
+reconstruct wrong time stamps. The `SparseOutlet` and `SparseInlet` in this module take care of
+this for single-channel streams, and hand the values to the consumer as `(value, time)` pairs.
+
+```no_run
+use lsl::sparse::SparseOutlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let outlet = SparseOutlet::<f32>::new("ButtonForce", "Force", "button-1")?;
+    // ... whenever the button is pressed:
+    outlet.push(4.2)?;
+    Ok(())
+}
+```
+*/
+
+use crate::{
+    Error, ExPushable, LslSample, Pullable, Result, StreamInfo, StreamInlet, StreamOutlet,
+    IRREGULAR_RATE,
+};
+use std::marker::PhantomData;
+
+/// Create the declaration of a single-channel, irregular-rate stream of values of type `T`.
+pub fn stream_info<T: LslSample>(name: &str, type_: &str, source_id: &str) -> Result<StreamInfo> {
+    StreamInfo::new(name, type_, 1, IRREGULAR_RATE, T::CHANNEL_FORMAT, source_id)
+}
+
+/// An outlet that sends individual values, each time-stamped when it is pushed.
+#[derive(Debug)]
+pub struct SparseOutlet<T> {
+    outlet: StreamOutlet,
+    _marker: PhantomData<T>,
+}
+
+impl<T: LslSample> SparseOutlet<T> {
+    /**
+    Create a new sparse stream and make it discoverable.
+
+    Arguments:
+    * `name`: Name of the stream.
+    * `type_`: Content type of the stream (e.g., "Force" or "ReactionTime").
+    * `source_id`: Unique identifier of the source (see `StreamInfo::new()`), or an empty str.
+    */
+    pub fn new(name: &str, type_: &str, source_id: &str) -> Result<SparseOutlet<T>> {
+        let info = stream_info::<T>(name, type_, source_id)?;
+        Ok(SparseOutlet {
+            outlet: StreamOutlet::new(&info, 0, 360)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Send a value, time-stamped with the current time.
+    pub fn push(&self, value: T) -> Result<()> {
+        self.push_at(value, 0.0)
+    }
+
+    /// Send a value with a given time stamp (in agreement with `lsl::local_clock()`).
+    pub fn push_at(&self, value: T, timestamp: f64) -> Result<()> {
+        self.outlet.push_sample_ex(&[value], timestamp, true)
+    }
+
+    /// The underlying outlet.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+/// An inlet that receives the values of a sparse stream as `(value, time)` pairs.
+#[derive(Debug)]
+pub struct SparseInlet<T> {
+    inlet: StreamInlet,
+    _marker: PhantomData<T>,
+}
+
+impl<T: LslSample> SparseInlet<T> {
+    /**
+    Open a sparse stream from a resolved stream info.
+
+    Arguments:
+    * `info`: A resolved stream info object (as coming from one of the resolver functions).
+    * `max_buflen`: The maximum amount of data to buffer (see `StreamInlet::new()`).
+
+    Returns an `Error::BadArgument` if the stream has a nominal sampling rate, an
+    `Error::LengthMismatch` if it has more than one channel, and an `Error::FormatMismatch` if
+    its channel format does not match `T`.
+    */
+    pub fn new(info: &StreamInfo, max_buflen: i32) -> Result<SparseInlet<T>> {
+        if info.nominal_srate() != IRREGULAR_RATE {
+            return Err(Error::BadArgument);
+        }
+        if info.channel_count() != 1 {
+            return Err(Error::LengthMismatch {
+                expected: 1,
+                actual: info.channel_count() as usize,
+            });
+        }
+        if info.channel_format() != T::CHANNEL_FORMAT {
+            return Err(Error::FormatMismatch);
+        }
+        Ok(SparseInlet {
+            inlet: StreamInlet::new(info, max_buflen, 0, true)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /**
+    Receive the next value.
+
+    Returns the value and its time stamp, or `None` if no new value was available within the
+    given timeout.
+    */
+    pub fn pull(&self, timeout: f64) -> Result<Option<(T, f64)>> {
+        let (sample, ts): (Vec<T>, f64) = self.inlet.pull_sample(timeout)?;
+        match sample.first() {
+            Some(&value) if ts != 0.0 => Ok(Some((value, ts))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Receive all values that are currently available.
+    pub fn pull_available(&self) -> Result<Vec<(T, f64)>> {
+        self.inlet
+            .iter_available::<T>()
+            .map(|sample| sample.map(|s| (s.data[0], s.timestamp)))
+            .collect()
+    }
+
+    /// The underlying inlet.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+}
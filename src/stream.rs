@@ -0,0 +1,151 @@
+/*!
+Inlets as asynchronous streams of samples (requires the `futures` feature).
+
+`StreamInlet::into_stream()` turns an inlet into a `SampleStream`, which implements the
+`futures::Stream` trait, so that LSL data can be processed with the usual stream combinators
+(e.g., `map()`, `chunks()` or `forward()` of the `futures` crate) on any async runtime. The
+samples are pulled by a background thread, which wakes up the consuming task whenever new data
+has arrived.
+
+```ignore
+use futures::StreamExt;
+use lsl::InletBuilder;
+
+async fn print_chunks() -> Result<(), lsl::Error> {
+    let inlet = InletBuilder::resolve("type", "EEG").open()?;
+    let mut chunks = inlet.into_stream::<f32>().chunks(100);
+    while let Some(chunk) = chunks.next().await {
+        println!("got {} samples", chunk.len());
+    }
+    Ok(())
+}
+```
+*/
+
+use crate::worker::MAX_BLOCK;
+use crate::{Pullable, Result, Sample, StreamInlet};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+// the maximum number of samples that are pulled ahead of the consumer (further samples remain
+// buffered in the inlet)
+const CAPACITY: usize = 256;
+
+/**
+An asynchronous stream of the samples of an inlet, created by `StreamInlet::into_stream()`.
+
+After an error has been yielded, the stream ends. Dropping the stream stops the background
+thread (within `lsl::worker::MAX_BLOCK` seconds) and closes the inlet.
+*/
+#[derive(Debug)]
+pub struct SampleStream<T> {
+    shared: Arc<Shared<T>>,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    // signaled when the consumer has taken samples out of the queue, or has gone away
+    not_full: Condvar,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    queue: VecDeque<Result<Sample<T>>>,
+    waker: Option<Waker>,
+    // the background thread has finished (after an error)
+    finished: bool,
+    // the stream has been dropped
+    closed: bool,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T: Send + 'static> SampleStream<T>
+where
+    StreamInlet: Pullable<T>,
+{
+    pub(crate) fn new(inlet: StreamInlet) -> SampleStream<T> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                waker: None,
+                finished: false,
+                closed: false,
+            }),
+            not_full: Condvar::new(),
+        });
+        let poller = shared.clone();
+        thread::spawn(move || poll_inlet(inlet, &poller));
+        SampleStream { shared }
+    }
+}
+
+// The background thread: pull samples and hand them to the stream until it is dropped.
+fn poll_inlet<T>(inlet: StreamInlet, shared: &Shared<T>)
+where
+    StreamInlet: Pullable<T>,
+{
+    loop {
+        {
+            // wait for room in the queue (the inlet keeps buffering in the meantime)
+            let mut state = shared.lock();
+            while state.queue.len() >= CAPACITY && !state.closed {
+                state = shared
+                    .not_full
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+            if state.closed {
+                return;
+            }
+        }
+        let item = match inlet.pull_sample(MAX_BLOCK) {
+            Ok((data, timestamp)) if timestamp != 0.0 => Ok(Sample { data, timestamp }),
+            Ok(_) => continue,
+            Err(e) => Err(e),
+        };
+        let failed = item.is_err();
+        let mut state = shared.lock();
+        state.queue.push_back(item);
+        state.finished = failed;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        if failed {
+            return;
+        }
+    }
+}
+
+impl<T> Stream for SampleStream<T> {
+    type Item = Result<Sample<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.shared.lock();
+        if let Some(item) = state.queue.pop_front() {
+            self.shared.not_full.notify_one();
+            return Poll::Ready(Some(item));
+        }
+        if state.finished {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for SampleStream<T> {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.not_full.notify_one();
+    }
+}
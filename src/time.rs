@@ -0,0 +1,170 @@
+/*!
+Converting between LSL time stamps and wall-clock time.
+
+The time stamps of LSL (see `lsl::local_clock()`) count seconds on a monotonic clock with an
+arbitrary origin (typically the boot time of the computer), which keeps them free of jumps, but
+makes them meaningless to humans. The functions in this module convert them into wall-clock time
+(`std::time::SystemTime`, or `chrono::DateTime` with the `chrono` feature) and back, e.g., to
+show when a marker was received, or to match a recording with externally logged events:
+
+```no_run
+use lsl::{Pullable, StreamInlet};
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "Markers", 1, 5.0)?;
+    let inlet = StreamInlet::new(&res[0], 360, 0, true)?;
+    let (marker, ts): (Vec<String>, _) = inlet.pull_sample(lsl::FOREVER)?;
+    if let Some(time) = lsl::time::lsl_to_system(ts) {
+        println!("{} at {:?}", marker[0], time);
+    }
+    Ok(())
+}
+```
+
+The conversions are based on the offset between the two clocks (see `unix_offset()`), which is
+sampled anew on each call, so they follow adjustments of the wall clock (e.g., by NTP), and are
+accurate to roughly the time that it takes to read both clocks (typically a few microseconds).
+Note that time stamps of remote streams are in the clock of their sender, so they should be
+converted only after clock synchronization (see `StreamInlet::time_correction()`).
+
+Where the accuracy of the offset matters (e.g., when LSL data are correlated with the time stamps
+of cameras or audit logs), `estimate_unix_offset()` takes it from many readings over a period of
+time, and reports how far the readings spread.
+*/
+
+use crate::{local_clock, Error, Result};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// the number of readings of both clocks from which the offset is taken
+const OFFSET_READINGS: usize = 5;
+
+/**
+The current offset, in seconds, between the wall clock (as seconds since the Unix epoch) and
+`lsl::local_clock()`, i.e., the Unix time of the LSL time stamp 0.
+
+The offset is taken from the reading of the wall clock that was most tightly bracketed by two
+readings of the LSL clock, out of several.
+*/
+pub fn unix_offset() -> f64 {
+    let mut best = (0.0, f64::INFINITY);
+    for _ in 0..OFFSET_READINGS {
+        let (offset, bracket) = read_offset();
+        if bracket < best.1 {
+            best = (offset, bracket);
+        }
+    }
+    best.0
+}
+
+/// An estimate of the offset between the wall clock and the LSL clock (see
+/// `estimate_unix_offset()`).
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct OffsetEstimate {
+    /// The offset, in seconds (see `unix_offset()`), as the median of the readings.
+    pub offset: f64,
+    /// The lowest offset that is consistent with any of the readings, in seconds.
+    pub lower: f64,
+    /// The highest offset that is consistent with any of the readings, in seconds.
+    pub upper: f64,
+    /// The number of readings.
+    pub samples: usize,
+}
+
+impl OffsetEstimate {
+    /// The width of the bounds, in seconds, i.e., the jitter of the readings.
+    pub fn jitter(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
+
+/**
+Estimate the offset between the wall clock and `lsl::local_clock()` from repeated readings of both
+clocks.
+
+Arguments:
+* `samples`: The number of readings, which are spread evenly over the duration.
+* `duration`: The time over which the clocks are read; the call blocks for this long.
+
+Each reading brackets the wall clock by two readings of the LSL clock, so that it is consistent
+with a range of offsets; the bounds of the estimate cover these ranges for all readings, and so
+also reflect adjustments of the wall clock during the measurement (e.g., by NTP), as well as
+readings that were delayed by the scheduler. Returns an `Error::BadArgument` if `samples` is 0.
+*/
+pub fn estimate_unix_offset(samples: usize, duration: Duration) -> Result<OffsetEstimate> {
+    if samples == 0 {
+        return Err(Error::BadArgument);
+    }
+    let interval = duration / (samples.max(2) - 1) as u32;
+    let mut offsets = Vec::with_capacity(samples);
+    let (mut lower, mut upper) = (f64::INFINITY, f64::NEG_INFINITY);
+    for k in 0..samples {
+        if k > 0 {
+            thread::sleep(interval);
+        }
+        let (offset, bracket) = read_offset();
+        offsets.push(offset);
+        lower = lower.min(offset - bracket / 2.0);
+        upper = upper.max(offset + bracket / 2.0);
+    }
+    offsets.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(OffsetEstimate {
+        offset: offsets[samples / 2],
+        lower,
+        upper,
+        samples,
+    })
+}
+
+/// Convert an LSL time stamp (in the local clock) into wall-clock time, or `None` if the time
+/// stamp is not finite or beyond the range of `SystemTime`.
+pub fn lsl_to_system(ts: f64) -> Option<SystemTime> {
+    let unix = ts + unix_offset();
+    match unix >= 0.0 {
+        true => UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(unix).ok()?),
+        false => UNIX_EPOCH.checked_sub(Duration::try_from_secs_f64(-unix).ok()?),
+    }
+}
+
+/// Convert a wall-clock time into an LSL time stamp (in the local clock).
+pub fn system_to_lsl(t: SystemTime) -> f64 {
+    unix_seconds(t) - unix_offset()
+}
+
+/// Convert an LSL time stamp (in the local clock) into a UTC date and time, or `None` if the time
+/// stamp is not finite or beyond the range of `chrono::DateTime` (requires the `chrono` feature).
+#[cfg(feature = "chrono")]
+pub fn lsl_to_datetime(ts: f64) -> Option<chrono::DateTime<chrono::Utc>> {
+    let unix = ts + unix_offset();
+    if !unix.is_finite() {
+        return None;
+    }
+    let secs = unix.floor();
+    // (out-of-range seconds saturate, and are then rejected by chrono)
+    let nanos = ((unix - secs) * 1e9).min(999_999_999.0) as u32;
+    chrono::DateTime::from_timestamp(secs as i64, nanos)
+}
+
+/// Convert a date and time (in any time zone) into an LSL time stamp (in the local clock;
+/// requires the `chrono` feature).
+#[cfg(feature = "chrono")]
+pub fn datetime_to_lsl<Tz: chrono::TimeZone>(t: &chrono::DateTime<Tz>) -> f64 {
+    system_to_lsl(t.with_timezone(&chrono::Utc).into())
+}
+
+// Read the wall clock between two readings of the LSL clock, and return the offset between them
+// and the time between the readings of the LSL clock (which bounds the error of the offset).
+fn read_offset() -> (f64, f64) {
+    let before = local_clock();
+    let now = unix_seconds(SystemTime::now());
+    let after = local_clock();
+    (now - (before + after) / 2.0, after - before)
+}
+
+// The seconds since the Unix epoch (negative for earlier times).
+fn unix_seconds(t: SystemTime) -> f64 {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs_f64(),
+        Err(e) => -e.duration().as_secs_f64(),
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+Mapping the time stamps of several inlets into the time domain of a reference stream.
+
+Each inlet can estimate the offset between its source's clock and the local clock (see
+`StreamInlet::time_correction()`). A `Timebase` combines these pairwise corrections so that the
+time stamps of all of its inlets are expressed in the clock of one designated reference stream,
+which makes them directly comparable across devices (e.g., for a stimulation device that
+should be aligned with the EEG amplifier's clock rather than with the local computer's clock).
+
+```no_run
+use lsl::timebase::Timebase;
+use lsl::StreamInlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let eeg = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let markers = lsl::resolve_byprop("type", "Markers", 1, 5.0)?;
+    let mut timebase = Timebase::new(StreamInlet::new(&eeg[0], 360, 0, true)?, 5.0);
+    let markers = timebase.add(StreamInlet::new(&markers[0], 360, 0, true)?);
+    let (marker, ts) = timebase.pull_sample::<String>(markers, lsl::FOREVER)?;
+    println!("got {} at {} (in the EEG amplifier's clock)", marker[0], ts);
+    Ok(())
+}
+```
+
+The mapping is `t + correction(inlet) - correction(reference)`, where the corrections are those
+most recently estimated by the native library (which refreshes them periodically in the
+background, so clock drift is accounted for as well). Therefore, the inlets must deliver the
+original time stamps, i.e., they must not have the `ProcessingOption::ClockSync` post-processing
+enabled.
+
+For consumers that process several streams in lock-step (e.g., closed-loop stimulation), the
+function `pull_synchronized()` returns the samples of several inlets that fall within the same
+(corrected) time window.
+*/
+
+use crate::{local_clock, Error, Pullable, Result, Sample, StreamInlet, TimeWindows};
+
+/// A group of inlets whose time stamps are mapped into the clock of a reference stream.
+#[derive(Debug)]
+pub struct Timebase {
+    // the reference comes first
+    inlets: Vec<StreamInlet>,
+    timeout: f64,
+}
+
+impl Timebase {
+    /**
+    Create a new timebase with the given reference stream, which has index 0.
+
+    Arguments:
+    * `reference`: The inlet whose clock is the timebase.
+    * `timeout`: The timeout for estimating a time correction (see
+      `StreamInlet::time_correction()`); only the first estimate for an inlet takes noticeable
+      time, later calls return the most recent background estimate.
+    */
+    pub fn new(reference: StreamInlet, timeout: f64) -> Timebase {
+        Timebase {
+            inlets: vec![reference],
+            timeout,
+        }
+    }
+
+    /// Add an inlet to the timebase, returning its index.
+    pub fn add(&mut self, inlet: StreamInlet) -> usize {
+        self.inlets.push(inlet);
+        self.inlets.len() - 1
+    }
+
+    /**
+    The offset that is currently added to the time stamps of the inlet with the given index.
+
+    Returns an `Error::BadArgument` if there is no such inlet, and an `Error::Timeout` if a time
+    correction could not be estimated in time.
+    */
+    pub fn offset(&self, index: usize) -> Result<f64> {
+        let inlet = self.inlet(index)?;
+        if index == 0 {
+            return Ok(0.0);
+        }
+        Ok(inlet.time_correction(self.timeout)? - self.inlets[0].time_correction(self.timeout)?)
+    }
+
+    /// Map a time stamp of the inlet with the given index into the reference clock.
+    pub fn map(&self, index: usize, timestamp: f64) -> Result<f64> {
+        Ok(timestamp + self.offset(index)?)
+    }
+
+    /**
+    Pull the next sample from the inlet with the given index (see `Pullable::pull_sample()`),
+    with its time stamp mapped into the reference clock.
+
+    As with `pull_sample()`, a time stamp of 0.0 means that no new sample was available.
+    */
+    pub fn pull_sample<T>(&self, index: usize, timeout: f64) -> Result<(Vec<T>, f64)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        let (sample, ts) = self.inlet(index)?.pull_sample(timeout)?;
+        if ts == 0.0 {
+            return Ok((sample, ts));
+        }
+        Ok((sample, self.map(index, ts)?))
+    }
+
+    /**
+    Pull a chunk of samples from the inlet with the given index (see `Pullable::pull_chunk()`),
+    with their time stamps mapped into the reference clock.
+    */
+    pub fn pull_chunk<T>(&self, index: usize) -> Result<(Vec<Vec<T>>, Vec<f64>)>
+    where
+        StreamInlet: Pullable<T>,
+    {
+        let (samples, mut stamps) = self.inlet(index)?.pull_chunk()?;
+        if !stamps.is_empty() {
+            let offset = self.offset(index)?;
+            stamps.iter_mut().for_each(|ts| *ts += offset);
+        }
+        Ok((samples, stamps))
+    }
+
+    /// The inlet with the given index (0 is the reference).
+    pub fn inlet(&self, index: usize) -> Result<&StreamInlet> {
+        self.inlets.get(index).ok_or(Error::BadArgument)
+    }
+}
+
+/**
+Pull, from each of several inlets, the samples that fall within the same recent time window.
+
+The window covers the last `window` seconds up to the current local time (`lsl::local_clock()`).
+The time stamps of each inlet are mapped into the local clock via its time correction, and
+returned that way, so that the samples of all streams are directly comparable. Samples older than
+the window are discarded, and samples that are newer are returned by the next call (see
+`TimeWindows::pull_chunk_until()`). Since samples arrive with some transmission delay, the most
+recent part of the window may not be complete yet for all streams; lock-step consumers should
+choose a window that is somewhat longer than the interval at which they call this function.
+
+The inlets are passed as `TimeWindows` (see `StreamInlet::time_windows()`), which must be kept
+between calls, and must not have the `ProcessingOption::ClockSync` post-processing enabled.
+Returns the samples of each inlet, in the order of the inlets.
+
+Arguments:
+* `inlets`: The inlets from which to pull.
+* `window`: The length of the time window, in seconds.
+* `timeout`: The timeout for estimating the time correction of each inlet (see
+  `StreamInlet::time_correction()`); only the first estimate for an inlet takes noticeable time,
+  later calls return the most recent background estimate.
+*/
+pub fn pull_synchronized<T>(
+    inlets: &mut [TimeWindows<'_, T>],
+    window: f64,
+    timeout: f64,
+) -> Result<Vec<Vec<Sample<T>>>>
+where
+    StreamInlet: Pullable<T>,
+{
+    if window <= 0.0 {
+        return Err(Error::BadArgument);
+    }
+    let corrections = inlets
+        .iter()
+        .map(|windows| windows.inlet().time_correction(timeout))
+        .collect::<Result<Vec<f64>>>()?;
+    let end = local_clock();
+    let start = end - window;
+    let mut result = vec![];
+    for (windows, correction) in inlets.iter_mut().zip(corrections) {
+        let (samples, stamps) = windows.pull_chunk_until(end - correction)?;
+        let samples = samples
+            .into_iter()
+            .zip(stamps)
+            .map(|(data, ts)| Sample {
+                data,
+                timestamp: ts + correction,
+            })
+            .filter(|sample| sample.timestamp >= start)
+            .collect();
+        result.push(samples);
+    }
+    Ok(result)
+}
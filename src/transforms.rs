@@ -0,0 +1,818 @@
+/*!
+Online transforms of numeric sample chunks.
+
+A `Transform` processes successive chunks of samples pulled from an inlet (as `f64` values) and
+describes how it changes the layout of the stream (channel labels, sampling rate, meta-data).
+Transforms can be chained with `Transform::then()`, and a `Relay` applies a transform to the data
+of an inlet and republishes the result as a derived stream with updated meta-data. Similarly,
+a `DetectorRelay` runs a `ThresholdDetector` on an inlet and publishes the detected threshold
+crossings as markers (e.g., for EMG onsets or photodiode-based stimulus timing), and a
+`ClippingDetector` flags values that reach the channel ranges declared by the sender.
+
+```no_run
+use lsl::transforms::{Montage, Relay, Rereference};
+use lsl::StreamInlet;
+
+fn main() -> Result<(), lsl::Error> {
+    let res = lsl::resolve_byprop("type", "EEG", 1, 5.0)?;
+    let inlet = StreamInlet::new(&res[0], 360, 0, true)?;
+    let montage = Montage::LinkedMastoids("M1".into(), "M2".into());
+    let reref = Rereference::new(montage, &inlet.info(5.0)?)?;
+    let mut relay = Relay::new(inlet, reref, "EEG-reref", 5.0)?;
+    loop {
+        relay.update()?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+```
+*/
+
+use crate::channels;
+use crate::events::{Event, EventOutlet};
+use crate::worker::{Action, ErrorContext, ErrorHandler};
+use crate::{
+    ChannelFormat, Error, ExPushable, Pullable, Result, Sample, StreamInfo, StreamInlet,
+    StreamOutlet, XMLElement,
+};
+use std::collections::VecDeque;
+
+#[cfg(feature = "filters")]
+pub mod filters;
+
+/// A transform of successive chunks of numeric samples (see module documentation).
+pub trait Transform {
+    /**
+    Process the next chunk of samples, and return the resulting samples.
+
+    Transforms that keep state across chunks (e.g., filters) treat the chunks as one continuous
+    series. The number of returned samples can differ from the number of input samples (e.g.,
+    for transforms that reduce the sampling rate).
+    */
+    fn process(&mut self, chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>>;
+
+    /**
+    The labels of the output channels, given those of the input channels (unlabeled channels
+    have an empty label). This also determines the number of output channels.
+    */
+    fn output_labels(&self, input: &[String]) -> Vec<String> {
+        input.to_vec()
+    }
+
+    /// The nominal sampling rate of the output, given that of the input.
+    fn output_rate(&self, input: f64) -> f64 {
+        input
+    }
+
+    /// Add a description of the transform to the meta-data (`desc()` field) of a derived stream.
+    fn describe(&self, _desc: &mut XMLElement) {}
+
+    /// Chain this transform with another one, which processes the output of this one.
+    fn then<U: Transform>(self, next: U) -> Chain<Self, U>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+/// Two transforms applied in sequence (see `Transform::then()`).
+#[derive(Debug)]
+pub struct Chain<T, U> {
+    first: T,
+    second: U,
+}
+
+impl<T: Transform, U: Transform> Transform for Chain<T, U> {
+    fn process(&mut self, chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        let chunk = self.first.process(chunk)?;
+        self.second.process(chunk)
+    }
+
+    fn output_labels(&self, input: &[String]) -> Vec<String> {
+        self.second.output_labels(&self.first.output_labels(input))
+    }
+
+    fn output_rate(&self, input: f64) -> f64 {
+        self.second.output_rate(self.first.output_rate(input))
+    }
+
+    fn describe(&self, desc: &mut XMLElement) {
+        self.first.describe(desc);
+        self.second.describe(desc);
+    }
+}
+
+/**
+Create the declaration of a stream derived from another one by a transform.
+
+The derived stream has the given name, the content type of the source stream, and the channel
+labels, sampling rate and description given by the transform. Its values are `Float32` if the
+source stream is, and `Double64` otherwise.
+
+Arguments:
+* `source`: The (fully retrieved) declaration of the source stream.
+* `transform`: The transform that derives the new stream from the source stream.
+* `name`: Name of the derived stream.
+*/
+pub fn derived_info<T: Transform>(
+    source: &StreamInfo,
+    transform: &T,
+    name: &str,
+) -> Result<StreamInfo> {
+    let mut input = channels::labels(source);
+    input.resize(source.channel_count() as usize, String::new());
+    let labels = transform.output_labels(&input);
+    let format = match source.channel_format() {
+        ChannelFormat::Float32 => ChannelFormat::Float32,
+        _ => ChannelFormat::Double64,
+    };
+    let mut info = StreamInfo::new(
+        name,
+        &source.stream_type(),
+        labels.len() as u32,
+        transform.output_rate(source.nominal_srate()),
+        format,
+        "",
+    )?;
+    let mut desc = info.desc_mut();
+    let mut chns = desc.append_child("channels");
+    for label in &labels {
+        chns.append_child("channel")
+            .append_child_value("label", label);
+    }
+    desc.append_child("derived_from")
+        .append_child_value("name", &source.stream_name())
+        .append_child_value("uid", &source.uid());
+    transform.describe(&mut desc);
+    Ok(info)
+}
+
+/**
+Applies a transform to the data of an inlet and republishes the result as a derived stream.
+
+The relay does not run on its own; call `update()` periodically (e.g., in the main loop of the
+application) to move the data that has arrived in the meantime.
+*/
+#[derive(Debug)]
+pub struct Relay<T> {
+    inlet: StreamInlet,
+    outlet: StreamOutlet,
+    transform: T,
+    pub(crate) error_handler: ErrorHandler,
+}
+
+impl<T: Transform> Relay<T> {
+    /**
+    Create a new relay and make the derived stream discoverable.
+
+    Arguments:
+    * `inlet`: The inlet to read the source data from.
+    * `transform`: The transform to apply.
+    * `name`: Name of the derived stream (see `derived_info()`).
+    * `timeout`: The timeout for retrieving the source stream's meta-data.
+    */
+    pub fn new(inlet: StreamInlet, transform: T, name: &str, timeout: f64) -> Result<Relay<T>> {
+        let info = derived_info(&inlet.info(timeout)?, &transform, name)?;
+        Ok(Relay {
+            inlet,
+            outlet: StreamOutlet::new(&info, 0, 360)?,
+            transform,
+            error_handler: ErrorHandler::default(),
+        })
+    }
+
+    /**
+    Set the handler that decides what happens when the relay fails while it is run by a
+    `Worker` (see `Worker::spawn_with()`), e.g., to keep it running while the source stream is
+    temporarily lost; without one, the first error ends the worker.
+    */
+    pub fn on_error<F>(mut self, handler: F) -> Relay<T>
+    where
+        F: FnMut(&Error, &ErrorContext) -> Action + Send + 'static,
+    {
+        self.error_handler = ErrorHandler::new(handler);
+        self
+    }
+
+    /// Process the data that has arrived since the last call; returns the number of samples sent.
+    pub fn update(&mut self) -> Result<usize> {
+        self.update_wait(0.0)
+    }
+
+    /**
+    Like `update()`, but if no data has arrived yet, wait up to `timeout` seconds for new data
+    (without consuming CPU time) instead of returning immediately.
+    */
+    pub fn update_wait(&mut self, timeout: f64) -> Result<usize> {
+        let chunk = next_chunk(&self.inlet, timeout)?;
+        if chunk.is_empty() {
+            return Ok(0);
+        }
+        let output = self.transform.process(chunk)?;
+        if output.is_empty() {
+            return Ok(0);
+        }
+        let stamps: Vec<f64> = output.iter().map(|x| x.timestamp).collect();
+        let samples: Vec<Vec<f64>> = output.into_iter().map(|x| x.data).collect();
+        self.outlet.push_chunk_stamped_ex(&samples, &stamps, true)?;
+        Ok(samples.len())
+    }
+
+    /// The transform that is applied.
+    pub fn transform(&self) -> &T {
+        &self.transform
+    }
+
+    /// The inlet from which the source data is read.
+    pub fn inlet(&self) -> &StreamInlet {
+        &self.inlet
+    }
+
+    /// The outlet through which the derived data are sent.
+    pub fn outlet(&self) -> &StreamOutlet {
+        &self.outlet
+    }
+}
+
+/// A re-referencing scheme for EEG data, with channels identified by their labels.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Montage {
+    /// Common average reference: the mean over all channels is subtracted from each channel.
+    Average,
+    /// Linked mastoids: the mean of the two given (mastoid) channels is subtracted from each
+    /// channel.
+    LinkedMastoids(String, String),
+    /// Bipolar derivations: each output channel is the difference of a pair of channels
+    /// (the first minus the second).
+    Bipolar(Vec<(String, String)>),
+}
+
+/// A transform that re-references EEG data according to a `Montage`.
+#[derive(Debug)]
+pub struct Rereference {
+    montage: Montage,
+    // indices of the reference channels (for LinkedMastoids), or of the channel pairs (Bipolar)
+    refs: Vec<usize>,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl Rereference {
+    /**
+    Create a re-referencing transform for a given stream.
+
+    Arguments:
+    * `montage`: The re-referencing scheme.
+    * `info`: The (fully retrieved) declaration of the stream whose data will be processed, which
+      is used to look up the channel labels.
+
+    Returns an `Error::BadArgument` if a channel named in the montage is not declared by the
+    stream.
+    */
+    pub fn new(montage: Montage, info: &StreamInfo) -> Result<Rereference> {
+        let mut refs = vec![];
+        let mut pairs = vec![];
+        match &montage {
+            Montage::Average => {}
+            Montage::LinkedMastoids(left, right) => {
+                refs = channels::indices_of(info, &[left, right])?;
+            }
+            Montage::Bipolar(pairs_by_label) => {
+                for (a, b) in pairs_by_label {
+                    let k = channels::indices_of(info, &[a, b])?;
+                    pairs.push((k[0], k[1]));
+                }
+            }
+        }
+        Ok(Rereference {
+            montage,
+            refs,
+            pairs,
+        })
+    }
+
+    /// The re-referencing scheme.
+    pub fn montage(&self) -> &Montage {
+        &self.montage
+    }
+
+    // Re-reference a single sample.
+    fn apply(&self, data: &[f64]) -> Result<Vec<f64>> {
+        let get = |k: usize| data.get(k).copied().ok_or(Error::BadArgument);
+        let reference = match self.montage {
+            Montage::Average => data.iter().sum::<f64>() / data.len().max(1) as f64,
+            Montage::LinkedMastoids(..) => (get(self.refs[0])? + get(self.refs[1])?) / 2.0,
+            Montage::Bipolar(_) => {
+                return self
+                    .pairs
+                    .iter()
+                    .map(|&(a, b)| Ok(get(a)? - get(b)?))
+                    .collect();
+            }
+        };
+        Ok(data.iter().map(|x| x - reference).collect())
+    }
+}
+
+impl Transform for Rereference {
+    fn process(&mut self, mut chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        for sample in chunk.iter_mut() {
+            sample.data = self.apply(&sample.data)?;
+        }
+        Ok(chunk)
+    }
+
+    fn output_labels(&self, input: &[String]) -> Vec<String> {
+        match &self.montage {
+            Montage::Bipolar(pairs) => pairs.iter().map(|(a, b)| format!("{}-{}", a, b)).collect(),
+            _ => input.to_vec(),
+        }
+    }
+
+    fn describe(&self, desc: &mut XMLElement) {
+        let scheme = match &self.montage {
+            Montage::Average => "average".to_string(),
+            Montage::LinkedMastoids(l, r) => format!("linked mastoids ({}, {})", l, r),
+            Montage::Bipolar(_) => "bipolar".to_string(),
+        };
+        desc.append_child("reference")
+            .append_child_value("scheme", &scheme);
+    }
+}
+
+/// The measure computed over each window by a `WindowedPower` transform.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Measure {
+    /// The mean of the squared values (i.e., the power of a band-limited signal).
+    Power,
+    /// The mean of the absolute values (i.e., the rectified and smoothed envelope).
+    Envelope,
+}
+
+/**
+A transform that computes a measure (power or envelope) per channel over a sliding window, and
+outputs it at a lower rate.
+
+An output sample is produced once the window has filled up and then every `hop` input samples,
+time-stamped with the time of the most recent input sample. To compute the power in a frequency
+band, chain this after a bandpass filter (see `filters::band_power()`, which requires the
+`filters` feature).
+*/
+#[derive(Debug)]
+pub struct WindowedPower {
+    measure: Measure,
+    window: usize,
+    hop: usize,
+    // measure values of the samples in the current window, and their sums per channel
+    history: VecDeque<Vec<f64>>,
+    sums: Vec<f64>,
+    since_output: usize,
+}
+
+impl WindowedPower {
+    /**
+    Create a new windowed power (or envelope) transform.
+
+    Arguments:
+    * `measure`: The measure to compute.
+    * `window`: The length of the window, in samples.
+    * `hop`: The number of input samples per output sample.
+    */
+    pub fn new(measure: Measure, window: usize, hop: usize) -> Result<WindowedPower> {
+        if window == 0 || hop == 0 {
+            return Err(Error::BadArgument);
+        }
+        Ok(WindowedPower {
+            measure,
+            window,
+            hop,
+            history: VecDeque::with_capacity(window + 1),
+            sums: vec![],
+            since_output: 0,
+        })
+    }
+
+    /**
+    Create a new windowed power (or envelope) transform, given durations and rates.
+
+    Arguments:
+    * `measure`: The measure to compute.
+    * `srate`: The sampling rate of the input, in Hz.
+    * `window`: The length of the window, in seconds.
+    * `output_rate`: The desired rate of the output, in Hz (rounded to an integer fraction of
+      the input rate).
+    */
+    pub fn with_rates(
+        measure: Measure,
+        srate: f64,
+        window: f64,
+        output_rate: f64,
+    ) -> Result<WindowedPower> {
+        if !(srate > 0.0 && window > 0.0 && output_rate > 0.0) {
+            return Err(Error::BadArgument);
+        }
+        let hop = (srate / output_rate).round().max(1.0) as usize;
+        WindowedPower::new(measure, (window * srate).round() as usize, hop)
+    }
+}
+
+impl Transform for WindowedPower {
+    fn process(&mut self, chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        let mut output = vec![];
+        for sample in chunk {
+            if self.sums.len() != sample.data.len() {
+                self.history.clear();
+                self.sums = vec![0.0; sample.data.len()];
+            }
+            let values: Vec<f64> = match self.measure {
+                Measure::Power => sample.data.iter().map(|x| x * x).collect(),
+                Measure::Envelope => sample.data.iter().map(|x| x.abs()).collect(),
+            };
+            for (sum, value) in self.sums.iter_mut().zip(&values) {
+                *sum += value;
+            }
+            self.history.push_back(values);
+            if self.history.len() > self.window {
+                let oldest = self.history.pop_front().unwrap();
+                for (sum, value) in self.sums.iter_mut().zip(&oldest) {
+                    *sum -= value;
+                }
+            }
+            self.since_output += 1;
+            if self.history.len() == self.window && self.since_output >= self.hop {
+                self.since_output = 0;
+                output.push(Sample {
+                    data: self.sums.iter().map(|x| x / self.window as f64).collect(),
+                    timestamp: sample.timestamp,
+                });
+            }
+        }
+        Ok(output)
+    }
+
+    fn output_rate(&self, input: f64) -> f64 {
+        input / self.hop as f64
+    }
+
+    fn describe(&self, desc: &mut XMLElement) {
+        let measure = match self.measure {
+            Measure::Power => "power",
+            Measure::Envelope => "envelope",
+        };
+        desc.append_child("windowed_measure")
+            .append_child_value("measure", measure)
+            .append_child_value("window_samples", &self.window.to_string())
+            .append_child_value("hop_samples", &self.hop.to_string());
+    }
+}
+
+/// The direction of a threshold crossing.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Edge {
+    /// The value rose to or above the threshold (e.g., a muscle onset).
+    Rising,
+    /// The value fell below the threshold minus the hysteresis (e.g., a muscle offset).
+    Falling,
+}
+
+/// A threshold crossing detected by a `ThresholdDetector`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Crossing {
+    /// Index of the channel.
+    pub channel: usize,
+    /// Direction of the crossing.
+    pub edge: Edge,
+    /// Time stamp of the first sample beyond the threshold.
+    pub timestamp: f64,
+}
+
+/**
+Detects threshold crossings in selected channels, with hysteresis and a refractory period.
+
+A rising crossing is detected when a channel's value reaches the threshold, unless the previous
+rising crossing of that channel is more recent than the refractory period (in which case it is
+ignored, along with the subsequent fall). Once risen, a falling crossing is detected when the
+value drops below the threshold minus the hysteresis.
+
+As a `Transform`, the detector passes the data through unchanged and collects the crossings,
+which can be retrieved via `take_crossings()`; `DetectorRelay` publishes them as markers.
+*/
+#[derive(Debug)]
+pub struct ThresholdDetector {
+    channels: Vec<usize>,
+    level: f64,
+    hysteresis: f64,
+    refractory: f64,
+    // per selected channel: whether it is above threshold, and the time of the last onset
+    active: Vec<bool>,
+    last_onset: Vec<f64>,
+    crossings: Vec<Crossing>,
+}
+
+impl ThresholdDetector {
+    /**
+    Create a new detector.
+
+    Arguments:
+    * `channels`: The indices of the channels to monitor.
+    * `level`: The threshold.
+    * `hysteresis`: How far the value must fall below the threshold to count as a falling
+      crossing (e.g., to avoid repeated detections due to noise).
+    * `refractory`: The minimum time, in seconds, between two rising crossings of a channel.
+    */
+    pub fn new(
+        channels: Vec<usize>,
+        level: f64,
+        hysteresis: f64,
+        refractory: f64,
+    ) -> Result<ThresholdDetector> {
+        if hysteresis < 0.0 || refractory < 0.0 {
+            return Err(Error::BadArgument);
+        }
+        let n = channels.len();
+        Ok(ThresholdDetector {
+            channels,
+            level,
+            hysteresis,
+            refractory,
+            active: vec![false; n],
+            last_onset: vec![f64::NEG_INFINITY; n],
+            crossings: vec![],
+        })
+    }
+
+    /// Process a chunk of samples and return the crossings that were detected in it.
+    pub fn detect(&mut self, chunk: &[Sample<f64>]) -> Result<Vec<Crossing>> {
+        let mut result = vec![];
+        for sample in chunk {
+            for (k, &channel) in self.channels.iter().enumerate() {
+                let value = *sample.data.get(channel).ok_or(Error::BadArgument)?;
+                let edge = if !self.active[k] && value >= self.level {
+                    // onsets within the refractory period are ignored entirely
+                    if sample.timestamp - self.last_onset[k] < self.refractory {
+                        continue;
+                    }
+                    self.active[k] = true;
+                    self.last_onset[k] = sample.timestamp;
+                    Edge::Rising
+                } else if self.active[k] && value < self.level - self.hysteresis {
+                    self.active[k] = false;
+                    Edge::Falling
+                } else {
+                    continue;
+                };
+                result.push(Crossing {
+                    channel,
+                    edge,
+                    timestamp: sample.timestamp,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Take the crossings that were collected while processing data as a `Transform`.
+    pub fn take_crossings(&mut self) -> Vec<Crossing> {
+        std::mem::take(&mut self.crossings)
+    }
+}
+
+impl Transform for ThresholdDetector {
+    fn process(&mut self, chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        let crossings = self.detect(&chunk)?;
+        self.crossings.extend(crossings);
+        Ok(chunk)
+    }
+}
+
+/**
+Runs a `ThresholdDetector` on the data of an inlet and publishes the crossings as markers.
+
+The markers are sent through an event stream (see `lsl::events`), as `threshold` events with a
+`channel` field that holds the channel's label (or index, if the channel is unlabeled); rising
+crossings are `Begin` events and falling crossings are `End` events. Call `update()` periodically
+to process the data that has arrived in the meantime.
+*/
+#[derive(Debug)]
+pub struct DetectorRelay {
+    inlet: StreamInlet,
+    outlet: EventOutlet,
+    detector: ThresholdDetector,
+    labels: Vec<String>,
+    pub(crate) error_handler: ErrorHandler,
+}
+
+impl DetectorRelay {
+    /**
+    Create a new detector relay and make its marker stream discoverable.
+
+    Arguments:
+    * `inlet`: The inlet to read the data from.
+    * `detector`: The detector to run.
+    * `name`: Name of the marker stream.
+    * `timeout`: The timeout for retrieving the data stream's meta-data (for channel labels).
+    */
+    pub fn new(
+        inlet: StreamInlet,
+        detector: ThresholdDetector,
+        name: &str,
+        timeout: f64,
+    ) -> Result<DetectorRelay> {
+        let labels = channels::labels(&inlet.info(timeout)?);
+        Ok(DetectorRelay {
+            inlet,
+            outlet: EventOutlet::new(name, "")?,
+            detector,
+            labels,
+            error_handler: ErrorHandler::default(),
+        })
+    }
+
+    /// Set the handler that decides what happens when the relay fails while it is run by a
+    /// `Worker` (see `Relay::on_error()`).
+    pub fn on_error<F>(mut self, handler: F) -> DetectorRelay
+    where
+        F: FnMut(&Error, &ErrorContext) -> Action + Send + 'static,
+    {
+        self.error_handler = ErrorHandler::new(handler);
+        self
+    }
+
+    /// Process the data that has arrived since the last call; returns the number of markers sent.
+    pub fn update(&mut self) -> Result<usize> {
+        self.update_wait(0.0)
+    }
+
+    /// Like `update()`, but wait up to `timeout` seconds for new data if none has arrived yet.
+    pub fn update_wait(&mut self, timeout: f64) -> Result<usize> {
+        let chunk = next_chunk(&self.inlet, timeout)?;
+        let crossings = self.detector.detect(&chunk)?;
+        for crossing in &crossings {
+            let channel = match self.labels.get(crossing.channel) {
+                Some(label) if !label.is_empty() => label.clone(),
+                _ => crossing.channel.to_string(),
+            };
+            let event = match crossing.edge {
+                Edge::Rising => Event::begin("threshold"),
+                Edge::Falling => Event::end("threshold"),
+            };
+            self.outlet
+                .push_at(&event.with("channel", &channel), crossing.timestamp)?;
+        }
+        Ok(crossings.len())
+    }
+
+    /// The detector that is run.
+    pub fn detector(&self) -> &ThresholdDetector {
+        &self.detector
+    }
+
+    /// The outlet through which the markers are sent.
+    pub fn outlet(&self) -> &EventOutlet {
+        &self.outlet
+    }
+}
+
+/// An episode of a channel reaching or exceeding its range, detected by a `ClippingDetector`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Clip {
+    /// Index of the channel.
+    pub channel: usize,
+    /// Time stamp of the first sample at or beyond the range limits.
+    pub timestamp: f64,
+    /// The value of that sample.
+    pub value: f64,
+    /// Whether the value lies beyond the range (rather than exactly at one of its limits, as is
+    /// typical for a saturated amplifier).
+    pub exceeded: bool,
+}
+
+/**
+Detects when the values of a stream clip at, or exceed, the expected range of their channel.
+
+The ranges are usually those that the sender declared in the stream's meta-data (see
+`lsl::channels::set_ranges()`), which makes it possible to notice amplifier saturation during a
+session rather than after it. A clip is reported once per episode, i.e., when a channel first
+reaches a limit; the next one is reported after the channel has returned into its range. In
+addition, the detector counts the affected samples of each channel.
+
+As a `Transform`, the detector passes the data through unchanged and collects the clips, which
+can be retrieved via `take_clips()`.
+*/
+#[derive(Debug)]
+pub struct ClippingDetector {
+    ranges: Vec<Option<(f64, f64)>>,
+    // per channel: whether it is currently clipping, and the number of affected samples
+    clipping: Vec<bool>,
+    counts: Vec<u64>,
+    clips: Vec<Clip>,
+}
+
+impl ClippingDetector {
+    /**
+    Create a new detector for the given per-channel ranges (`None` for unmonitored channels).
+
+    Returns an `Error::BadArgument` if a range has its minimum above its maximum (or is NaN).
+    */
+    pub fn new(ranges: Vec<Option<(f64, f64)>>) -> Result<ClippingDetector> {
+        let invalid = |&(min, max): &(f64, f64)| min > max || min.is_nan() || max.is_nan();
+        if ranges.iter().flatten().any(invalid) {
+            return Err(Error::BadArgument);
+        }
+        let n = ranges.len();
+        Ok(ClippingDetector {
+            ranges,
+            clipping: vec![false; n],
+            counts: vec![0; n],
+            clips: vec![],
+        })
+    }
+
+    /**
+    Create a detector for the ranges that are declared in the meta-data of a stream.
+
+    The info must have been fully retrieved (e.g., via `StreamInlet::info()`). Returns an
+    `Error::BadArgument` if the stream declares no ranges at all.
+    */
+    pub fn from_info(info: &StreamInfo) -> Result<ClippingDetector> {
+        let ranges = channels::ranges(info);
+        if ranges.iter().all(Option::is_none) {
+            return Err(Error::BadArgument);
+        }
+        ClippingDetector::new(ranges)
+    }
+
+    /**
+    Process a chunk of samples and return the clips that began in it.
+
+    Returns an `Error::BadArgument` if a sample has fewer channels than there are ranges.
+    */
+    pub fn detect(&mut self, chunk: &[Sample<f64>]) -> Result<Vec<Clip>> {
+        let mut result = vec![];
+        for sample in chunk {
+            if sample.data.len() < self.ranges.len() {
+                return Err(Error::BadArgument);
+            }
+            for (channel, range) in self.ranges.iter().enumerate() {
+                let (min, max) = match *range {
+                    Some(range) => range,
+                    None => continue,
+                };
+                let value = sample.data[channel];
+                if value > min && value < max {
+                    self.clipping[channel] = false;
+                    continue;
+                }
+                self.counts[channel] += 1;
+                if !self.clipping[channel] {
+                    self.clipping[channel] = true;
+                    result.push(Clip {
+                        channel,
+                        timestamp: sample.timestamp,
+                        value,
+                        exceeded: value < min || value > max,
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Take the clips that were collected while processing data as a `Transform`.
+    pub fn take_clips(&mut self) -> Vec<Clip> {
+        std::mem::take(&mut self.clips)
+    }
+
+    /// The number of samples of each channel that were at or beyond the range so far.
+    pub fn clipped_samples(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Whether the given channel is currently at or beyond its range.
+    pub fn is_clipping(&self, channel: usize) -> bool {
+        self.clipping.get(channel).copied().unwrap_or(false)
+    }
+}
+
+impl Transform for ClippingDetector {
+    fn process(&mut self, chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        let clips = self.detect(&chunk)?;
+        self.clips.extend(clips);
+        Ok(chunk)
+    }
+}
+
+// Pull the samples that are available in an inlet, waiting up to `timeout` for the first one.
+fn next_chunk(inlet: &StreamInlet, timeout: f64) -> Result<Vec<Sample<f64>>> {
+    let (data, timestamp) = inlet.pull_sample(timeout)?;
+    if timestamp == 0.0 {
+        return Ok(vec![]);
+    }
+    let mut chunk = vec![Sample { data, timestamp }];
+    for sample in inlet.iter_available() {
+        chunk.push(sample?);
+    }
+    Ok(chunk)
+}
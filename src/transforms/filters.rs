@@ -0,0 +1,253 @@
+/*!
+Streaming IIR filters (requires the `filters` feature).
+
+The filters are cascades of biquad sections (designed after the well-known "Audio EQ Cookbook"
+formulas), which keep a separate state for each channel across chunks, so that successive chunks
+are filtered as one continuous signal. Butterworth highpass, lowpass and bandpass filters of even
+order are built from several sections.
+
+```no_run
+use lsl::transforms::filters::Filter;
+use lsl::transforms::Transform;
+
+fn main() -> Result<(), lsl::Error> {
+    // 1 Hz highpass and 50 Hz notch at 250 Hz sampling rate
+    let filter = Filter::highpass(250.0, 1.0, 4)?.then(Filter::notch(250.0, 50.0, 30.0)?);
+    Ok(())
+}
+```
+*/
+
+use super::{Chain, Measure, Transform, WindowedPower};
+use crate::{Error, Result, Sample, XMLElement};
+use std::f64::consts::PI;
+
+/// The coefficients of a single biquad (second-order IIR) section, normalized such that a0 = 1.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Biquad {
+    /// Feed-forward coefficients b0, b1, b2.
+    pub b: [f64; 3],
+    /// Feedback coefficients a1, a2.
+    pub a: [f64; 2],
+}
+
+impl Biquad {
+    /// A second-order lowpass section with the given cutoff frequency (Hz) and quality factor.
+    pub fn lowpass(srate: f64, cutoff: f64, q: f64) -> Result<Biquad> {
+        let (cos, _, a) = prewarp(srate, cutoff, q)?;
+        let b = [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0];
+        Ok(Biquad::normalized(b, a))
+    }
+
+    /// A second-order highpass section with the given cutoff frequency (Hz) and quality factor.
+    pub fn highpass(srate: f64, cutoff: f64, q: f64) -> Result<Biquad> {
+        let (cos, _, a) = prewarp(srate, cutoff, q)?;
+        let b = [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0];
+        Ok(Biquad::normalized(b, a))
+    }
+
+    /// A bandpass section (with 0 dB peak gain) around the given center frequency (Hz).
+    pub fn bandpass(srate: f64, center: f64, q: f64) -> Result<Biquad> {
+        let (_, alpha, a) = prewarp(srate, center, q)?;
+        let b = [alpha, 0.0, -alpha];
+        Ok(Biquad::normalized(b, a))
+    }
+
+    /// A notch section that removes the given frequency (Hz); higher `q` means a narrower notch.
+    pub fn notch(srate: f64, freq: f64, q: f64) -> Result<Biquad> {
+        let (cos, _, a) = prewarp(srate, freq, q)?;
+        let b = [1.0, -2.0 * cos, 1.0];
+        Ok(Biquad::normalized(b, a))
+    }
+
+    fn normalized(b: [f64; 3], a: [f64; 3]) -> Biquad {
+        Biquad {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+        }
+    }
+
+    // Filter one value, given the section's state (transposed direct form II).
+    fn step(&self, x: f64, state: &mut [f64; 2]) -> f64 {
+        let y = self.b[0] * x + state[0];
+        state[0] = self.b[1] * x - self.a[0] * y + state[1];
+        state[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/**
+A filter made up of a cascade of biquad sections, with per-channel state.
+
+The filter adapts to the number of channels of the first chunk that it processes.
+*/
+#[derive(Clone, Debug)]
+pub struct Filter {
+    sections: Vec<Biquad>,
+    // state of each section, per channel
+    state: Vec<Vec<[f64; 2]>>,
+    description: String,
+}
+
+impl Filter {
+    /**
+    Create a filter from a cascade of biquad sections.
+
+    Arguments:
+    * `sections`: The sections, in the order in which they are applied.
+    * `description`: A short description of the filter (e.g., "highpass 1 Hz"), which is
+      written to the meta-data of derived streams.
+    */
+    pub fn new(sections: Vec<Biquad>, description: &str) -> Filter {
+        Filter {
+            sections,
+            state: vec![],
+            description: description.to_string(),
+        }
+    }
+
+    /**
+    A Butterworth highpass filter.
+
+    Arguments:
+    * `srate`: The sampling rate of the data, in Hz.
+    * `cutoff`: The cutoff frequency, in Hz (must be below the Nyquist frequency).
+    * `order`: The order of the filter, which must be a positive even number.
+    */
+    pub fn highpass(srate: f64, cutoff: f64, order: usize) -> Result<Filter> {
+        let sections = butterworth_qs(order)?
+            .map(|q| Biquad::highpass(srate, cutoff, q))
+            .collect::<Result<_>>()?;
+        let description = format!("highpass {} Hz (Butterworth, order {})", cutoff, order);
+        Ok(Filter::new(sections, &description))
+    }
+
+    /// A Butterworth lowpass filter (see `highpass()` for the arguments).
+    pub fn lowpass(srate: f64, cutoff: f64, order: usize) -> Result<Filter> {
+        let sections = butterworth_qs(order)?
+            .map(|q| Biquad::lowpass(srate, cutoff, q))
+            .collect::<Result<_>>()?;
+        let description = format!("lowpass {} Hz (Butterworth, order {})", cutoff, order);
+        Ok(Filter::new(sections, &description))
+    }
+
+    /**
+    A bandpass filter, made up of a Butterworth highpass and lowpass filter of the given order.
+
+    Arguments:
+    * `srate`: The sampling rate of the data, in Hz.
+    * `low`, `high`: The edges of the pass band, in Hz.
+    * `order`: The order of each of the two filters, which must be a positive even number.
+    */
+    pub fn bandpass(srate: f64, low: f64, high: f64, order: usize) -> Result<Filter> {
+        if low >= high {
+            return Err(Error::BadArgument);
+        }
+        let mut sections = Filter::highpass(srate, low, order)?.sections;
+        sections.extend(Filter::lowpass(srate, high, order)?.sections);
+        let description = format!(
+            "bandpass {}-{} Hz (Butterworth, order {})",
+            low, high, order
+        );
+        Ok(Filter::new(sections, &description))
+    }
+
+    /**
+    A notch filter, e.g., to remove power line noise.
+
+    Arguments:
+    * `srate`: The sampling rate of the data, in Hz.
+    * `freq`: The frequency to remove, in Hz.
+    * `q`: The quality factor, i.e., the ratio of `freq` and the width of the notch (e.g., 30).
+    */
+    pub fn notch(srate: f64, freq: f64, q: f64) -> Result<Filter> {
+        let description = format!("notch {} Hz (Q={})", freq, q);
+        Ok(Filter::new(
+            vec![Biquad::notch(srate, freq, q)?],
+            &description,
+        ))
+    }
+
+    /// Filter a single sample in place.
+    pub fn step(&mut self, data: &mut [f64]) {
+        if self.state.len() != data.len() {
+            self.state = vec![vec![[0.0; 2]; self.sections.len()]; data.len()];
+        }
+        for (x, state) in data.iter_mut().zip(self.state.iter_mut()) {
+            for (section, s) in self.sections.iter().zip(state.iter_mut()) {
+                *x = section.step(*x, s);
+            }
+        }
+    }
+
+    /// Clear the filter state (e.g., after a gap in the data).
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+
+    /// The biquad sections of the filter.
+    pub fn sections(&self) -> &[Biquad] {
+        &self.sections
+    }
+}
+
+impl Transform for Filter {
+    fn process(&mut self, mut chunk: Vec<Sample<f64>>) -> Result<Vec<Sample<f64>>> {
+        for sample in chunk.iter_mut() {
+            self.step(&mut sample.data);
+        }
+        Ok(chunk)
+    }
+
+    fn describe(&self, desc: &mut XMLElement) {
+        let mut filters = desc.child("filters");
+        if filters.empty() {
+            filters = desc.append_child("filters");
+        }
+        filters.append_child_value("filter", &self.description);
+    }
+}
+
+// Compute cos(w0), alpha and the (unnormalized) feedback coefficients for a section at the given
+// frequency, checking the arguments.
+fn prewarp(srate: f64, freq: f64, q: f64) -> Result<(f64, f64, [f64; 3])> {
+    if !(srate > 0.0 && freq > 0.0 && freq < srate / 2.0 && q > 0.0) {
+        return Err(Error::BadArgument);
+    }
+    let w0 = 2.0 * PI * freq / srate;
+    let (cos, alpha) = (w0.cos(), w0.sin() / (2.0 * q));
+    Ok((cos, alpha, [1.0 + alpha, -2.0 * cos, 1.0 - alpha]))
+}
+
+// The quality factors of the biquad sections of a Butterworth filter of the given (even) order.
+fn butterworth_qs(order: usize) -> Result<impl Iterator<Item = f64>> {
+    if order == 0 || order % 2 == 1 {
+        return Err(Error::BadArgument);
+    }
+    let n = order as f64;
+    Ok((0..order / 2).map(move |k| 1.0 / (2.0 * (PI * (2 * k + 1) as f64 / (2.0 * n)).cos())))
+}
+
+/**
+Create a transform that computes the power in a frequency band over a sliding window, i.e., a
+bandpass filter followed by a `WindowedPower` transform.
+
+The result can be republished as a derived stream via `Relay`, e.g., as a neurofeedback signal.
+
+Arguments:
+* `srate`: The sampling rate of the input, in Hz.
+* `low`, `high`: The edges of the frequency band, in Hz.
+* `window`: The length of the window, in seconds.
+* `output_rate`: The desired rate of the output, in Hz.
+*/
+pub fn band_power(
+    srate: f64,
+    low: f64,
+    high: f64,
+    window: f64,
+    output_rate: f64,
+) -> Result<Chain<Filter, WindowedPower>> {
+    let filter = Filter::bandpass(srate, low, high, 4)?;
+    let power = WindowedPower::with_rates(Measure::Power, srate, window, output_rate)?;
+    Ok(filter.then(power))
+}
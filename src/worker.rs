@@ -0,0 +1,367 @@
+/*!
+Background workers for long-running components.
+
+Components such as the `Relay` or `DetectorRelay` (in `lsl::transforms`) need to be updated
+continuously. Rather than calling their `update()` methods in a sleep-polling loop, they can be
+run by a `Worker` on a background thread, which is event-driven: while waiting for input, the
+thread blocks inside the native library until new data arrives, so that idle streams cost next to
+no CPU time. Tasks that do not wait on inlets can instead block on the worker's `Handle`, which
+can be used to wake them up or to stop them from another thread.
+
+```no_run
+use lsl::transforms::{Montage, Relay, Rereference};
+use lsl::worker::Worker;
+use lsl::InletBuilder;
+
+fn main() -> Result<(), lsl::Error> {
+    let inlet = InletBuilder::resolve("type", "EEG").timeout(5.0).open()?;
+    let reref = Rereference::new(Montage::Average, &inlet.info(5.0)?)?;
+    let worker = Worker::spawn(Relay::new(inlet, reref, "EEG-reref", 5.0)?);
+    // ... later
+    let (_relay, result) = worker.stop();
+    result
+}
+```
+
+By default, an error ends the worker. What happens instead can be decided per error by a handler
+that is registered via `Worker::spawn_with()` (e.g., to keep a relay running while its source
+stream is temporarily lost):
+
+```no_run
+use lsl::worker::{Action, Worker};
+# fn f(relay: lsl::transforms::DetectorRelay) {
+let worker = Worker::spawn_with(relay, |err, ctx| {
+    eprintln!("{} failed ({} times in a row): {}", ctx.task, ctx.failures, err);
+    match err {
+        lsl::Error::StreamLost if ctx.failures < 100 => Action::Retry,
+        _ => Action::Abort,
+    }
+});
+# }
+```
+
+The components that can be run by a worker can also bring their own handler (e.g., via
+`Relay::on_error()`), which `Worker::spawn()` uses.
+
+With the `parking_lot` feature, the synchronization primitives of the `parking_lot` crate are
+used instead of those of the standard library.
+*/
+
+use crate::transforms::{DetectorRelay, Relay, Transform};
+use crate::{Error, Result};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "parking_lot")]
+use parking_lot::{Condvar, Mutex, MutexGuard};
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/**
+The maximum time, in seconds, for which a task should block in a native call (e.g., when waiting
+for data on an inlet), since such calls cannot be interrupted; this is also the maximum delay
+until a stopped worker notices that it has been stopped.
+*/
+pub const MAX_BLOCK: f64 = 0.25;
+
+/// A unit of work that is performed repeatedly by a `Worker`.
+pub trait Task: Send + 'static {
+    /**
+    Perform the work that is currently due, waiting for input if there is none.
+
+    Implementations should wait by blocking in the native library (for at most `MAX_BLOCK`
+    seconds at a time) or via `Handle::wait()`, rather than by sleeping. An error ends the worker.
+    */
+    fn run_once(&mut self, handle: &Handle) -> Result<()>;
+
+    /**
+    Decide what happens after `run_once()` has failed, when the task is run via
+    `Worker::spawn()` (a handler that is passed to `Worker::spawn_with()` takes precedence).
+
+    The default ends the worker; components such as the `Relay` take a handler via their own
+    `on_error()` method.
+    */
+    fn on_error(&mut self, _err: &Error, _ctx: &ErrorContext) -> Action {
+        Action::Abort
+    }
+}
+
+impl<T: Transform + Send + 'static> Task for Relay<T> {
+    fn run_once(&mut self, _handle: &Handle) -> Result<()> {
+        self.update_wait(MAX_BLOCK).map(|_| ())
+    }
+
+    fn on_error(&mut self, err: &Error, ctx: &ErrorContext) -> Action {
+        self.error_handler.handle(err, ctx)
+    }
+}
+
+impl Task for DetectorRelay {
+    fn run_once(&mut self, _handle: &Handle) -> Result<()> {
+        self.update_wait(MAX_BLOCK).map(|_| ())
+    }
+
+    fn on_error(&mut self, err: &Error, ctx: &ErrorContext) -> Action {
+        self.error_handler.handle(err, ctx)
+    }
+}
+
+/// What a worker does after its task has failed (see `Worker::spawn_with()`).
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Action {
+    /// Run the task again after a pause of `MAX_BLOCK` seconds (e.g., to give a lost stream time
+    /// to recover).
+    Retry,
+    /// Ignore the error and continue with the next unit of work. Consecutive skipped failures
+    /// are spaced out by a pause that doubles from 1 ms up to `MAX_BLOCK` seconds, so that a
+    /// persistent error does not keep the thread busy.
+    Skip,
+    /// End the worker with the error (the default).
+    Abort,
+}
+
+/// Information about a failure of a worker's task, which is passed to its error handler.
+#[derive(Debug)]
+pub struct ErrorContext {
+    /// The type name of the task (e.g., for logging).
+    pub task: &'static str,
+    /// The number of consecutive failures, including this one.
+    pub failures: u32,
+}
+
+// the pause, in seconds, after the first of several consecutive skipped failures (see
+// `Action::Skip`)
+const SKIP_PAUSE: f64 = 0.001;
+
+/**
+An optional error handler of a component that can be run by a `Worker` (see `Task::on_error()`);
+without a handler, an error ends the worker.
+*/
+#[derive(Default)]
+pub(crate) struct ErrorHandler(Option<Box<HandlerFn>>);
+
+// an error handler (see `Worker::spawn_with()`)
+type HandlerFn = dyn FnMut(&Error, &ErrorContext) -> Action + Send;
+
+impl ErrorHandler {
+    pub(crate) fn new<F>(handler: F) -> ErrorHandler
+    where
+        F: FnMut(&Error, &ErrorContext) -> Action + Send + 'static,
+    {
+        ErrorHandler(Some(Box::new(handler)))
+    }
+
+    pub(crate) fn handle(&mut self, err: &Error, ctx: &ErrorContext) -> Action {
+        match &mut self.0 {
+            Some(handler) => handler(err, ctx),
+            None => Action::Abort,
+        }
+    }
+}
+
+// (closures are not `Debug`)
+impl std::fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("ErrorHandler"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+// state that is shared between a worker and its handles
+#[derive(Debug, Default)]
+struct State {
+    stopped: bool,
+    woken: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+impl Shared {
+    #[cfg(feature = "parking_lot")]
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock()
+    }
+
+    #[cfg(not(feature = "parking_lot"))]
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(feature = "parking_lot")]
+    fn wait<'a>(
+        &self,
+        mut guard: MutexGuard<'a, State>,
+        timeout: Duration,
+    ) -> MutexGuard<'a, State> {
+        self.cond.wait_for(&mut guard, timeout);
+        guard
+    }
+
+    #[cfg(not(feature = "parking_lot"))]
+    fn wait<'a>(&self, guard: MutexGuard<'a, State>, timeout: Duration) -> MutexGuard<'a, State> {
+        match self.cond.wait_timeout(guard, timeout) {
+            Ok((guard, _)) => guard,
+            Err(e) => e.into_inner().0,
+        }
+    }
+}
+
+/// A handle to wake up or stop a worker, which can be cloned and sent to other threads.
+#[derive(Debug, Clone, Default)]
+pub struct Handle {
+    shared: Arc<Shared>,
+}
+
+impl Handle {
+    /// Create a new handle (for use outside of a `Worker`, e.g., for a custom thread).
+    pub fn new() -> Handle {
+        Handle::default()
+    }
+
+    /// Ask the worker to stop (it stops after its current unit of work).
+    pub fn stop(&self) {
+        self.shared.lock().stopped = true;
+        self.shared.cond.notify_all();
+    }
+
+    /// Wake up the worker if it is waiting in `wait()`.
+    pub fn wake(&self) {
+        self.shared.lock().woken = true;
+        self.shared.cond.notify_all();
+    }
+
+    /// Whether the worker has been asked to stop.
+    pub fn is_stopped(&self) -> bool {
+        self.shared.lock().stopped
+    }
+
+    /**
+    Block until the handle is woken or stopped, or until the timeout (in seconds) expires.
+
+    Returns false if the worker has been asked to stop, and true otherwise.
+    */
+    pub fn wait(&self, timeout: f64) -> bool {
+        let deadline = Instant::now() + Duration::from_secs_f64(timeout.max(0.0));
+        let mut state = self.shared.lock();
+        while !state.stopped && !state.woken {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = self.shared.wait(state, deadline - now);
+        }
+        state.woken = false;
+        !state.stopped
+    }
+}
+
+/// Runs a task repeatedly on a background thread until it is stopped or fails.
+#[derive(Debug)]
+pub struct Worker<T: Task> {
+    handle: Handle,
+    thread: Option<thread::JoinHandle<(T, Result<()>)>>,
+}
+
+impl<T: Task> Worker<T> {
+    /**
+    Start running the task on a new thread; what happens when it fails is decided by the task
+    (see `Task::on_error()`), which by default ends the worker on the first error.
+    */
+    pub fn spawn(task: T) -> Worker<T> {
+        Worker::start(task, |task, err, ctx| task.on_error(err, ctx))
+    }
+
+    /**
+    Start running the task on a new thread, with a handler that decides what happens when the
+    task fails.
+
+    The handler is called on the worker's thread with the error and an `ErrorContext`, and
+    returns the `Action` to take.
+    */
+    pub fn spawn_with<F>(task: T, mut on_error: F) -> Worker<T>
+    where
+        F: FnMut(&Error, &ErrorContext) -> Action + Send + 'static,
+    {
+        Worker::start(task, move |_, err, ctx| on_error(err, ctx))
+    }
+
+    fn start<F>(mut task: T, mut on_error: F) -> Worker<T>
+    where
+        F: FnMut(&mut T, &Error, &ErrorContext) -> Action + Send + 'static,
+    {
+        let handle = Handle::new();
+        let thread_handle = handle.clone();
+        let thread = thread::spawn(move || {
+            let mut failures = 0;
+            while !thread_handle.is_stopped() {
+                let e = match task.run_once(&thread_handle) {
+                    Ok(()) => {
+                        failures = 0;
+                        continue;
+                    }
+                    Err(e) => e,
+                };
+                failures += 1;
+                let context = ErrorContext {
+                    task: std::any::type_name::<T>(),
+                    failures,
+                };
+                match on_error(&mut task, &e, &context) {
+                    Action::Retry => {
+                        thread_handle.wait(MAX_BLOCK);
+                    }
+                    Action::Skip => {
+                        let doublings = failures.saturating_sub(1).min(16) as i32;
+                        thread_handle.wait((SKIP_PAUSE * 2f64.powi(doublings)).min(MAX_BLOCK));
+                    }
+                    Action::Abort => return (task, Err(e)),
+                }
+            }
+            (task, Ok(()))
+        });
+        Worker {
+            handle,
+            thread: Some(thread),
+        }
+    }
+
+    /// A handle to wake up or stop the worker from another thread.
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Whether the worker has finished (i.e., it has been stopped or its task failed).
+    pub fn is_finished(&self) -> bool {
+        !matches!(&self.thread, Some(t) if !t.is_finished())
+    }
+
+    /**
+    Stop the worker and wait until it has finished.
+
+    Returns the task, along with the error that ended the worker, if any. If the task panicked,
+    the panic is propagated.
+    */
+    pub fn stop(mut self) -> (T, Result<()>) {
+        self.handle.stop();
+        let thread = self.thread.take().unwrap();
+        thread
+            .join()
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+    }
+}
+
+impl<T: Task> Drop for Worker<T> {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.handle.stop();
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,424 @@
+// Checking stream declarations in XML form before they are handed to the native library, which
+// does not report what is wrong with a document (see `StreamInfo::from_xml()`), formatting them
+// for humans (see `StreamInfo::to_xml_pretty()`), and reading what the native library does not
+// give access to (see `XMLElement::attributes()`).
+
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::ops::Range;
+
+// the fields that a declaration must have, and the reasons that are reported if they are missing
+const REQUIRED_FIELDS: [(&str, &str); 4] = [
+    ("name", "missing <name> element"),
+    ("channel_count", "missing <channel_count> element"),
+    ("nominal_srate", "missing <nominal_srate> element"),
+    ("channel_format", "missing <channel_format> element"),
+];
+
+// the names of the channel formats in the XML form (see `ChannelFormat`'s `Display`)
+const CHANNEL_FORMATS: [&str; 8] = [
+    "float32",
+    "double64",
+    "string",
+    "int32",
+    "int16",
+    "int8",
+    "int64",
+    "undefined",
+];
+
+// the delimiters of the markup that is skipped over: declarations, comments, CDATA sections and
+// doctypes
+const SKIPPED: [(&str, &str); 4] = [
+    ("<?", "?>"),
+    ("<!--", "-->"),
+    ("<![CDATA[", "]]>"),
+    ("<!", ">"),
+];
+
+// a child element of the root: its text, the line where it starts, and the range of the whole
+// element in the document
+struct Field<'a> {
+    text: &'a str,
+    line: usize,
+    range: Range<usize>,
+}
+
+// a child element of the root, as returned by `root_fields()`
+pub(crate) struct RootField {
+    // the text content (with references replaced by the characters)
+    pub(crate) text: String,
+    // the range of the whole element (from its start tag to its end tag) in the document
+    pub(crate) range: Range<usize>,
+}
+
+// the lines at which the `<info>` element of a checked document and its fields start
+pub(crate) struct Lines<'a> {
+    info: usize,
+    fields: HashMap<&'a str, usize>,
+}
+
+impl Lines<'_> {
+    // The line of the field with the given name, or of the `<info>` element if there is none.
+    pub(crate) fn of(&self, field: &str) -> usize {
+        self.fields.get(field).copied().unwrap_or(self.info)
+    }
+}
+
+/*
+Check that a document is well-formed, has an `<info>` root with the required fields, and that the
+numeric fields and the channel format are valid.
+
+Returns the lines of the elements (e.g., to report problems that are found later), or an
+`Error::Parse` with the 1-based line of the first problem.
+*/
+pub(crate) fn check(xml: &str) -> Result<Lines<'_>> {
+    let (root, fields) = scan(xml)?;
+    let info_at = match root {
+        Some(("info", at)) => at,
+        _ => return Err(parse_error(xml, 0, "missing <info> element")),
+    };
+    for &(name, reason) in REQUIRED_FIELDS.iter() {
+        if !fields.contains_key(name) {
+            return Err(parse_error(xml, info_at, reason));
+        }
+    }
+    let invalid = |name: &str, reason| Error::Parse {
+        line: fields[name].line,
+        reason,
+    };
+    if !matches!(fields["channel_count"].text.trim().parse::<i32>(), Ok(count) if count >= 0) {
+        return Err(invalid("channel_count", "invalid channel count"));
+    }
+    let srate = fields["nominal_srate"].text.trim().parse::<f64>();
+    if !matches!(srate, Ok(srate) if srate >= 0.0 && srate.is_finite()) {
+        return Err(invalid("nominal_srate", "invalid nominal sampling rate"));
+    }
+    if !CHANNEL_FORMATS.contains(&fields["channel_format"].text.trim()) {
+        return Err(invalid("channel_format", "invalid channel format"));
+    }
+    Ok(Lines {
+        info: line_at(xml, info_at),
+        fields: fields
+            .iter()
+            .map(|(&name, field)| (name, field.line))
+            .collect(),
+    })
+}
+
+/*
+Find the children of the root element of a well-formed document, by name (the first element of
+each name), e.g., the fields of a stream declaration, which precede its meta-data (that may use
+the same names, but is nested deeper).
+
+Returns their text contents and positions, or an `Error::Parse` if the document is not
+well-formed.
+*/
+pub(crate) fn root_fields(xml: &str) -> Result<HashMap<&str, RootField>> {
+    let (_, fields) = scan(xml)?;
+    Ok(fields
+        .into_iter()
+        .map(|(name, field)| {
+            let root_field = RootField {
+                text: unescape(field.text.trim()),
+                range: field.range,
+            };
+            (name, root_field)
+        })
+        .collect())
+}
+
+// the root element of a document (its name and position), if any, and the children of the root
+type Scanned<'a> = (Option<(&'a str, usize)>, HashMap<&'a str, Field<'a>>);
+
+// Check that a document is well-formed, and find its root element and the children of the root.
+fn scan(xml: &str) -> Result<Scanned<'_>> {
+    let error = |at: usize, reason| parse_error(xml, at, reason);
+    // the open elements, with their names and the positions of and after their start tags
+    let mut open: Vec<(&str, usize, usize)> = vec![];
+    let mut root = None;
+    let mut fields = HashMap::new();
+    let mut pos = 0;
+    while let Some(offset) = xml[pos..].find('<') {
+        let at = pos + offset;
+        let rest = &xml[at..];
+        let skipped = SKIPPED.iter().find(|(start, _)| rest.starts_with(start));
+        if let Some((start, end)) = skipped {
+            let len = rest[start.len()..]
+                .find(end)
+                .ok_or_else(|| error(at, "unterminated markup"))?;
+            pos = at + start.len() + len + end.len();
+            continue;
+        }
+        let len = tag_len(rest).ok_or_else(|| error(at, "unterminated tag"))?;
+        let tag = &rest[1..len - 1];
+        pos = at + len;
+        if let Some(name) = tag.strip_prefix('/') {
+            let (expected, start_at, start) =
+                open.pop().ok_or_else(|| error(at, "unexpected end tag"))?;
+            if name.trim() != expected {
+                return Err(error(at, "mismatched end tag"));
+            }
+            if open.len() == 1 {
+                fields.entry(expected).or_insert(Field {
+                    text: &xml[start..at],
+                    line: line_at(xml, start),
+                    range: start_at..pos,
+                });
+            }
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name = tag.split_whitespace().next().unwrap_or("");
+        if name.is_empty() {
+            return Err(error(at, "element without a name"));
+        }
+        if open.is_empty() {
+            if root.is_some() {
+                return Err(error(at, "more than one root element"));
+            }
+            root = Some((name, at));
+        }
+        if self_closing {
+            if open.len() == 1 {
+                fields.entry(name).or_insert(Field {
+                    text: "",
+                    line: line_at(xml, at),
+                    range: at..pos,
+                });
+            }
+        } else {
+            open.push((name, at, pos));
+        }
+    }
+    if let Some(&(_, at, _)) = open.last() {
+        return Err(error(at, "unclosed element"));
+    }
+    Ok((root, fields))
+}
+
+// The length of the tag at the start of the given text (up to and including its closing `>`,
+// which may also occur in quoted attribute values), or `None` if it is unterminated.
+fn tag_len(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (at, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(at + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The 1-based line of a position in a document.
+fn line_at(xml: &str, at: usize) -> usize {
+    xml[..at].matches('\n').count() + 1
+}
+
+// An `Error::Parse` at a position in a document.
+fn parse_error(xml: &str, at: usize, reason: &'static str) -> Error {
+    Error::Parse {
+        line: line_at(xml, at),
+        reason,
+    }
+}
+
+/*
+Re-indent a well-formed document with one element per line, nested by the given number of spaces
+per level. Elements that only contain text are kept on one line (with their text unchanged), and
+whitespace between elements is replaced.
+*/
+pub(crate) fn pretty(xml: &str, indent: usize) -> String {
+    let tokens = tokens(xml);
+    let token = |k: usize| &xml[tokens[k].clone()];
+    let mut result = String::with_capacity(xml.len() * 2);
+    let mut depth: usize = 0;
+    let mut k = 0;
+    while k < tokens.len() {
+        let mut line = token(k).trim();
+        let mut next = k + 1;
+        let opens = line.starts_with('<')
+            && !line.ends_with("/>")
+            && !(line.starts_with("</") || line.starts_with("<?") || line.starts_with("<!"));
+        // an element that only contains text (or nothing) is kept on one line
+        let leaf_end = (k + 1..tokens.len().min(k + 3))
+            .find(|&j| token(j).starts_with("</"))
+            .filter(|&j| j == k + 1 || !token(k + 1).starts_with('<'));
+        if line.starts_with("</") {
+            depth = depth.saturating_sub(1);
+        }
+        if let (true, Some(end)) = (opens, leaf_end) {
+            line = &xml[tokens[k].start..tokens[end].end];
+            next = end + 1;
+        }
+        result.push_str(&" ".repeat(depth * indent));
+        result.push_str(line);
+        result.push('\n');
+        if opens && leaf_end.is_none() {
+            depth += 1;
+        }
+        k = next;
+    }
+    result
+}
+
+// Split a document into the ranges of its markup (tags, comments, etc.) and of the text between
+// the markup, leaving out text that is only whitespace.
+fn tokens(xml: &str) -> Vec<Range<usize>> {
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < xml.len() {
+        let rest = &xml[pos..];
+        let len = if rest.starts_with('<') {
+            match SKIPPED.iter().find(|(start, _)| rest.starts_with(start)) {
+                Some((_, end)) => rest.find(end).map_or(rest.len(), |at| at + end.len()),
+                // (tags may contain `>` in quoted attribute values)
+                None => tag_len(rest).unwrap_or(rest.len()),
+            }
+        } else {
+            rest.find('<').unwrap_or(rest.len())
+        };
+        if !rest[..len].trim().is_empty() {
+            tokens.push(pos..pos + len);
+        }
+        pos += len;
+    }
+    tokens
+}
+
+/*
+Find the names and attributes of the elements of a well-formed document, in document order (i.e.,
+in the order of the start tags).
+*/
+pub(crate) fn attributes(xml: &str) -> Vec<(String, Vec<(String, String)>)> {
+    start_tags(xml)
+        .into_iter()
+        .map(|range| {
+            let (element, rest) = split_start_tag(&xml[range]);
+            (element.to_string(), parse_attributes(rest))
+        })
+        .collect()
+}
+
+/*
+Set an attribute of the element with the given index in document order (see `attributes()`),
+replacing its previous value, if any, and return the changed document.
+
+Returns `None` if there is no such element, or if it has another name than the given one.
+*/
+pub(crate) fn set_attribute(
+    xml: &str,
+    index: usize,
+    element_name: &str,
+    name: &str,
+    value: &str,
+) -> Option<String> {
+    let range = start_tags(xml).into_iter().nth(index)?;
+    let token = &xml[range.clone()];
+    let (element, rest) = split_start_tag(token);
+    if element != element_name {
+        return None;
+    }
+    let mut attributes = parse_attributes(rest);
+    match attributes.iter_mut().find(|(key, _)| key == name) {
+        Some(attribute) => attribute.1 = value.to_string(),
+        None => attributes.push((name.to_string(), value.to_string())),
+    }
+    let mut tag = format!("<{}", element);
+    for (key, value) in attributes {
+        let value = value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('"', "&quot;");
+        tag.push_str(&format!(" {}=\"{}\"", key, value));
+    }
+    tag.push_str(if token.ends_with("/>") { "/>" } else { ">" });
+    Some(format!(
+        "{}{}{}",
+        &xml[..range.start],
+        tag,
+        &xml[range.end..]
+    ))
+}
+
+// The ranges of the start tags (including those of empty elements) of a document.
+fn start_tags(xml: &str) -> Vec<Range<usize>> {
+    tokens(xml)
+        .into_iter()
+        .filter(|range| {
+            let token = &xml[range.clone()];
+            token.starts_with('<')
+                && !token.starts_with("</")
+                && !token.starts_with("<?")
+                && !token.starts_with("<!")
+        })
+        .collect()
+}
+
+// Split a start tag into the element name and the remainder (with the attributes).
+fn split_start_tag(token: &str) -> (&str, &str) {
+    let tag = token[1..token.len() - 1].trim_end_matches('/');
+    let name = tag.split_whitespace().next().unwrap_or("");
+    (name, &tag[name.len()..])
+}
+
+// Parse the attributes in the remainder of a start tag (after the element name).
+fn parse_attributes(mut rest: &str) -> Vec<(String, String)> {
+    let mut result = vec![];
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => quote,
+            _ => break,
+        };
+        let end = match value[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        result.push((name.to_string(), unescape(&value[1..end])));
+        rest = &value[end + 1..];
+    }
+    result
+}
+
+// Replace the character and entity references of XML (e.g., `&amp;`) by the characters.
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        result.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let character = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            reference => match reference.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => reference.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+            }
+            .and_then(std::char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                result.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
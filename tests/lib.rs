@@ -24,7 +24,7 @@ fn streaminfo_basic() {
 fn streaminfo_xml() {
     let mut info = lsl::StreamInfo::new("MyStream", "EEG", 8, 100.0, lsl::ChannelFormat::Float32, "12345").unwrap();
 
-    let mut channels = info.desc().append_child("channels");
+    let mut channels = info.desc_mut().append_child("channels");
     let mut chn = channels.append_child("channel");
     chn.append_child_value("label", "MyChannel");
     assert_eq!(channels.child("channel").child_value_named("label"), "MyChannel");
@@ -33,3 +33,1723 @@ fn streaminfo_xml() {
     assert!(xml.contains("<name>MyStream</name>"));
     assert!(xml.contains("<label>MyChannel</label>"));
 }
+
+#[test]
+fn capabilities_match_formats() {
+    let caps = lsl::capabilities();
+    assert_eq!(lsl::ChannelFormat::Int64.is_supported(), caps.int64);
+    assert!(lsl::ChannelFormat::Float32.is_supported());
+    assert!(!lsl::ChannelFormat::Undefined.is_supported());
+}
+
+#[test]
+fn event_roundtrip() {
+    use lsl::events::{Event, Phase};
+    let event = Event::begin("trial|1").with("cond=x", "100%");
+    let decoded = Event::decode(&event.encode()).unwrap();
+    assert_eq!(decoded, event);
+    assert_eq!(decoded.phase, Phase::Begin);
+    assert_eq!(decoded.get("cond=x"), Some("100%"));
+    assert_eq!(
+        Event::decode("free-form marker"),
+        Err(lsl::Error::BadArgument)
+    );
+}
+
+#[test]
+fn csv_parsing() {
+    use lsl::playback::CsvData;
+    let data = CsvData::read("time, Fz, Cz\n0.0, 1, 2\n0.5, 3, 4\n".as_bytes(), true).unwrap();
+    assert_eq!(data.labels, vec!["Fz", "Cz"]);
+    assert_eq!(data.timestamps, Some(vec![0.0, 0.5]));
+    assert_eq!(data.samples, vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+    let bad = CsvData::read("1, 2\n3\n".as_bytes(), false);
+    assert_eq!(
+        bad,
+        Err(lsl::Error::Parse {
+            line: 2,
+            reason: "inconsistent number of columns"
+        })
+    );
+}
+
+#[test]
+fn virtual_clock() {
+    use lsl::clock::{Clock, VirtualClock};
+    let clock = VirtualClock::new(10.0);
+    clock.sleep_until(12.5);
+    clock.sleep_until(11.0);
+    clock.advance(0.5);
+    assert_eq!(clock.now(), 13.0);
+}
+
+#[test]
+fn channel_indices_by_label() {
+    let mut info =
+        lsl::StreamInfo::new("MyStream", "EEG", 3, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let mut channels = info.desc_mut().append_child("channels");
+    for label in &["Cz", "Pz", "Fz"] {
+        channels
+            .append_child("channel")
+            .append_child_value("label", label);
+    }
+    assert_eq!(lsl::channels::labels(&info), vec!["Cz", "Pz", "Fz"]);
+    assert_eq!(
+        lsl::channels::indices_of(&info, &["Fz", "Cz"]),
+        Ok(vec![2, 0])
+    );
+    assert_eq!(
+        lsl::channels::indices_of(&info, &["Oz"]),
+        Err(lsl::Error::BadArgument)
+    );
+}
+
+#[test]
+fn rereference_bipolar() {
+    use lsl::transforms::{Montage, Rereference, Transform};
+    let mut info =
+        lsl::StreamInfo::new("MyStream", "EEG", 3, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let mut channels = info.desc_mut().append_child("channels");
+    for label in &["F3", "C3", "P3"] {
+        channels
+            .append_child("channel")
+            .append_child_value("label", label);
+    }
+    let montage = Montage::Bipolar(vec![("F3".into(), "C3".into()), ("C3".into(), "P3".into())]);
+    let mut reref = Rereference::new(montage, &info).unwrap();
+    let chunk = vec![lsl::Sample {
+        data: vec![5.0, 3.0, 1.0],
+        timestamp: 1.0,
+    }];
+    assert_eq!(reref.process(chunk).unwrap()[0].data, vec![2.0, 2.0]);
+    let labels = reref.output_labels(&["F3".into(), "C3".into(), "P3".into()]);
+    assert_eq!(labels, vec!["F3-C3", "C3-P3"]);
+}
+
+#[test]
+fn sample_type_formats() {
+    use lsl::LslSample;
+    fn format_of<T: LslSample>() -> lsl::ChannelFormat {
+        T::CHANNEL_FORMAT
+    }
+    assert_eq!(format_of::<f32>(), lsl::ChannelFormat::Float32);
+    assert_eq!(format_of::<i16>(), lsl::ChannelFormat::Int16);
+}
+
+#[cfg(feature = "filters")]
+#[test]
+fn highpass_removes_offset() {
+    use lsl::transforms::{filters::Filter, Transform};
+    let mut filter = Filter::highpass(100.0, 1.0, 2).unwrap();
+    let chunk: Vec<_> = (0..1000)
+        .map(|k| lsl::Sample {
+            data: vec![5.0],
+            timestamp: k as f64,
+        })
+        .collect();
+    let output = filter.process(chunk).unwrap();
+    assert!(output[999].data[0].abs() < 1e-3);
+    assert_eq!(
+        Filter::highpass(100.0, 60.0, 2).err(),
+        Some(lsl::Error::BadArgument)
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_push_layout() {
+    use lsl::{LslPush, SampleLayout};
+    #[derive(LslPush)]
+    struct Accelerometer {
+        #[lsl(label = "AccX")]
+        x: f32,
+        y: f32,
+    }
+    let sample = Accelerometer { x: 1.0, y: 2.0 };
+    assert_eq!(sample.x + sample.y, 3.0);
+    assert_eq!(Accelerometer::LABELS, &["AccX", "y"]);
+    let info = Accelerometer::stream_info("MyIMU", "Accelerometer", 100.0, "").unwrap();
+    assert_eq!(info.channel_format(), lsl::ChannelFormat::Float32);
+    assert_eq!(lsl::channels::labels(&info), vec!["AccX", "y"]);
+}
+
+#[test]
+fn windowed_power_decimates() {
+    use lsl::transforms::{Measure, Transform, WindowedPower};
+    let mut power = WindowedPower::new(Measure::Envelope, 4, 2).unwrap();
+    let chunk: Vec<_> = (0..10)
+        .map(|k| lsl::Sample {
+            data: vec![-2.0],
+            timestamp: k as f64,
+        })
+        .collect();
+    let output = power.process(chunk).unwrap();
+    let stamps: Vec<f64> = output.iter().map(|x| x.timestamp).collect();
+    assert_eq!(stamps, vec![3.0, 5.0, 7.0, 9.0]);
+    assert_eq!(output[0].data, vec![2.0]);
+    assert_eq!(power.output_rate(100.0), 50.0);
+}
+
+#[test]
+fn threshold_detector_hysteresis() {
+    use lsl::transforms::{Edge, ThresholdDetector};
+    let mut detector = ThresholdDetector::new(vec![1], 1.0, 0.5, 5.0).unwrap();
+    let values = [0.0, 1.2, 0.8, 1.1, 0.2, 1.5, 0.0, 1.0];
+    let chunk: Vec<_> = values
+        .iter()
+        .enumerate()
+        .map(|(k, &x)| lsl::Sample {
+            data: vec![0.0, x],
+            timestamp: k as f64,
+        })
+        .collect();
+    let crossings = detector.detect(&chunk).unwrap();
+    let edges: Vec<_> = crossings.iter().map(|c| (c.edge, c.timestamp)).collect();
+    // the onset at 5.0 falls into the refractory period of the one at 1.0 (5 s)
+    assert_eq!(
+        edges,
+        vec![
+            (Edge::Rising, 1.0),
+            (Edge::Falling, 4.0),
+            (Edge::Rising, 7.0)
+        ]
+    );
+}
+
+#[test]
+fn channel_ranges_and_clipping() {
+    use lsl::transforms::ClippingDetector;
+    let mut info =
+        lsl::StreamInfo::new("Amp", "EEG", 2, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    assert_eq!(
+        lsl::channels::set_ranges(&mut info, &[(1.0, 0.0), (0.0, 1.0)]),
+        Err(lsl::Error::BadArgument)
+    );
+    lsl::channels::set_ranges(&mut info, &[(-1.0, 1.0), (0.0, 5.5)]).unwrap();
+    assert_eq!(
+        lsl::channels::ranges(&info),
+        vec![Some((-1.0, 1.0)), Some((0.0, 5.5))]
+    );
+    let mut detector = ClippingDetector::from_info(&info).unwrap();
+    let values = [(0.0, 1.0), (1.0, 2.0), (1.0, 6.0), (0.5, 7.0), (2.0, 1.0)];
+    let chunk: Vec<_> = values
+        .iter()
+        .enumerate()
+        .map(|(k, &(a, b))| lsl::Sample {
+            data: vec![a, b],
+            timestamp: k as f64,
+        })
+        .collect();
+    let clips = detector.detect(&chunk).unwrap();
+    let found: Vec<_> = clips
+        .iter()
+        .map(|c| (c.channel, c.timestamp, c.exceeded))
+        .collect();
+    assert_eq!(found, vec![(0, 1.0, false), (1, 2.0, true), (0, 4.0, true)]);
+    assert_eq!(detector.clipped_samples(), &[3, 2]);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_pull_fields() {
+    use lsl::LslPull;
+    #[derive(LslPull, Debug, PartialEq)]
+    #[lsl(by_label)]
+    struct Gaze {
+        #[lsl(label = "GazeX")]
+        x: f64,
+        y: f64,
+    }
+    assert_eq!(Gaze::LABELS, &["GazeX", "y"]);
+    assert_eq!(
+        Gaze::from_channels(&[1.0, 2.0, 3.0], &[2, 0]),
+        Gaze { x: 3.0, y: 1.0 }
+    );
+}
+
+#[test]
+fn sample_value_conversion() {
+    use lsl::SampleValue;
+    let value = SampleValue::I16(vec![1, -2, 3]);
+    assert_eq!(value.len(), 3);
+    assert_eq!(value.channel_format(), lsl::ChannelFormat::Int16);
+    assert_eq!(value.to_f64(), Some(vec![1.0, -2.0, 3.0]));
+    assert_eq!(SampleValue::Str(vec!["a".into()]).to_f64(), None);
+}
+
+#[test]
+fn latency_estimate_pairs_onsets() {
+    let markers = [1.0, 2.0, 3.0, 4.0];
+    // the onset for the third marker is missing; 2.5 is a spurious early onset for no marker
+    let onsets = [1.02, 2.03, 2.5, 4.04];
+    let estimate = lsl::calibration::estimate_latency(&markers, &onsets, 0.1).unwrap();
+    assert_eq!(estimate.count, 3);
+    assert_eq!(estimate.missed, 1);
+    assert!((estimate.offset - 0.03).abs() < 1e-9);
+    assert!(estimate.ci95.0 < 0.03 && estimate.ci95.1 > 0.03);
+}
+
+#[test]
+fn push_dynamic_checks_format() {
+    use lsl::SampleValue;
+    let info = lsl::StreamInfo::new(
+        "Dynamic",
+        "EEG",
+        2,
+        100.0,
+        lsl::ChannelFormat::Float32,
+        "dyn",
+    )
+    .unwrap();
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    assert_eq!(outlet.channel_format(), lsl::ChannelFormat::Float32);
+    assert!(outlet
+        .push_sample_dynamic(&SampleValue::F32(vec![1.0, 2.0]), 0.0)
+        .is_ok());
+    let mismatch = outlet.push_sample_dynamic(&SampleValue::F64(vec![1.0, 2.0]), 0.0);
+    assert_eq!(mismatch, Err(lsl::Error::FormatMismatch));
+}
+
+#[test]
+fn worker_handle_wake_and_stop() {
+    use lsl::worker::Handle;
+    let handle = Handle::new();
+    let other = handle.clone();
+    let waiter = std::thread::spawn(move || other.wait(10.0));
+    handle.wake();
+    assert!(waiter.join().unwrap());
+    handle.stop();
+    assert!(handle.is_stopped());
+    assert!(!handle.wait(10.0));
+}
+
+#[test]
+fn queue_drop_policies() {
+    use lsl::queue::{DropPolicy, SampleQueue};
+    let oldest = SampleQueue::new(2, DropPolicy::DropOldest);
+    let newest = SampleQueue::new(2, DropPolicy::DropNewest);
+    for k in 0..4 {
+        oldest.push(k);
+        newest.push(k);
+    }
+    assert_eq!(oldest.drain(), vec![2, 3]);
+    assert_eq!(newest.drain(), vec![0, 1]);
+    assert_eq!((oldest.dropped(), newest.dropped()), (2, 2));
+    assert_eq!(newest.pop(0.0), None);
+}
+
+#[test]
+fn sparse_stream_declaration() {
+    let info = lsl::sparse::stream_info::<i16>("Button", "Force", "btn").unwrap();
+    assert_eq!(info.nominal_srate(), lsl::IRREGULAR_RATE);
+    assert_eq!(info.channel_count(), 1);
+    assert_eq!(info.channel_format(), lsl::ChannelFormat::Int16);
+}
+
+#[test]
+fn raw_layout_of_formats() {
+    let layout = lsl::RawLayout::new(lsl::ChannelFormat::Int16, 3).unwrap();
+    assert_eq!(layout.sample_bytes(), 6);
+    assert_eq!(layout.channel_range(2), 4..6);
+    assert_eq!(lsl::ChannelFormat::Double64.value_bytes(), Some(8));
+    assert!(matches!(
+        lsl::RawLayout::new(lsl::ChannelFormat::String, 1),
+        Err(lsl::Error::FormatMismatch)
+    ));
+}
+
+#[test]
+fn channel_list_macro() {
+    const EEG: lsl::channels::ChannelList<2> =
+        lsl::channels!["C3" => microvolts, "C4" => microvolts];
+    assert_eq!(EEG.labels, ["C3", "C4"]);
+    assert_eq!(EEG.units, ["microvolts", "microvolts"]);
+    let info = EEG
+        .stream_info("Test", "EEG", 100.0, lsl::ChannelFormat::Float32, "")
+        .unwrap();
+    assert_eq!(info.channel_count(), 2);
+    assert_eq!(lsl::channels::labels(&info), vec!["C3", "C4"]);
+}
+
+#[test]
+fn inlet_set_empty_poll() {
+    let set = lsl::inlets::InletSet::new();
+    assert!(set.is_empty());
+    assert!(set.poll(0.1).unwrap().is_empty());
+    assert!(set.inlet(0).is_none());
+}
+
+#[test]
+fn multi_reader_without_inlets() {
+    let (reader, samples) = lsl::inlets::MultiReader::with_channel(vec![], 4, 10);
+    assert_eq!(reader.running(), 0);
+    assert!(reader.take_failures().is_empty());
+    let (inlets, result) = reader.stop();
+    assert!(inlets.is_empty() && result.is_ok());
+    assert!(samples.recv().is_err());
+}
+
+#[test]
+fn resolve_with_zero_capacity() {
+    assert!(matches!(
+        lsl::resolve_streams_with_capacity(0.1, 0),
+        Err(lsl::Error::BadArgument)
+    ));
+    let result = lsl::resolve_bypred_with_capacity("name='x'", 0, 0.1, 0);
+    assert!(matches!(result, Err(lsl::Error::BadArgument)));
+}
+
+#[test]
+fn discovery_ends_after_wait_time() {
+    let resolver = lsl::ContinuousResolver::new_with_pred("name='NoSuchStream'", 1.0).unwrap();
+    let start = lsl::local_clock();
+    assert_eq!(resolver.discover(0.3).count(), 0);
+    assert!(lsl::local_clock() - start >= 0.3);
+}
+
+#[test]
+fn outlet_from_invalid_xml_file() {
+    let path =
+        std::env::temp_dir().join(format!("lsl-test-invalid-info-{}.xml", std::process::id()));
+    std::fs::write(&path, "<?xml version=\"1.0\"?>\n<stream>\n</stream>\n").unwrap();
+    let result = lsl::StreamOutlet::from_xml_file(&path, Default::default());
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(lsl::Error::Parse { line: 1, .. })));
+    let missing = lsl::StreamOutlet::from_xml_file(path.with_extension("none"), Default::default());
+    assert!(matches!(
+        missing,
+        Err(lsl::Error::Io(std::io::ErrorKind::NotFound))
+    ));
+}
+
+#[test]
+fn outlet_from_xml_file_reports_field_line() {
+    let path = std::env::temp_dir().join(format!("lsl-test-unnamed-{}.xml", std::process::id()));
+    let xml = "<info version=\"1.1\">\n<name></name>\n<channel_count>1</channel_count>\n\
+               <nominal_srate>0</nominal_srate>\n<channel_format>int32</channel_format>\n</info>\n";
+    std::fs::write(&path, xml).unwrap();
+    let result = lsl::StreamOutlet::from_xml_file(&path, Default::default());
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(lsl::Error::Parse { line: 2, .. })));
+}
+
+#[test]
+fn buffered_outlet_reports_push_errors() {
+    let info = lsl::StreamInfo::new(
+        "Buffered",
+        "EEG",
+        2,
+        100.0,
+        lsl::ChannelFormat::Float32,
+        "buf",
+    )
+    .unwrap();
+    let outlet = lsl::buffered::BufferedOutlet::<f32>::new(
+        lsl::StreamOutlet::new(&info, 0, 360).unwrap(),
+        10,
+    );
+    assert!(outlet.push(vec![1.0, 2.0], 0.0));
+    assert!(outlet.push(vec![1.0], 0.0));
+    assert_eq!(outlet.dropped(), 0);
+    let result = outlet.finish();
+    assert!(matches!(
+        result,
+        Err(lsl::Error::LengthMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+#[test]
+fn worker_error_handler() {
+    use lsl::worker::{Action, Handle, Task, Worker};
+    struct Failing(u32);
+    impl Task for Failing {
+        fn run_once(&mut self, _handle: &Handle) -> Result<(), lsl::Error> {
+            self.0 += 1;
+            Err(lsl::Error::StreamLost)
+        }
+    }
+    let worker = Worker::spawn_with(Failing(0), |_, ctx| match ctx.failures {
+        1..=2 => Action::Skip,
+        _ => Action::Abort,
+    });
+    while !worker.is_finished() {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let (task, result) = worker.stop();
+    assert_eq!((task.0, result), (3, Err(lsl::Error::StreamLost)));
+}
+
+#[test]
+fn worker_uses_task_error_handler() {
+    use lsl::worker::{Action, ErrorContext, Handle, Task, Worker};
+    struct Failing(u32);
+    impl Task for Failing {
+        fn run_once(&mut self, _handle: &Handle) -> Result<(), lsl::Error> {
+            self.0 += 1;
+            Err(lsl::Error::StreamLost)
+        }
+        fn on_error(&mut self, _err: &lsl::Error, ctx: &ErrorContext) -> Action {
+            match ctx.failures {
+                1..=19 => Action::Skip,
+                _ => Action::Abort,
+            }
+        }
+    }
+    let start = lsl::local_clock();
+    let worker = Worker::spawn(Failing(0));
+    while !worker.is_finished() {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let (task, result) = worker.stop();
+    assert_eq!((task.0, result), (20, Err(lsl::Error::StreamLost)));
+    // (the skipped failures are spaced out by growing pauses)
+    assert!(lsl::local_clock() - start > 0.25);
+}
+
+#[test]
+fn handles_cross_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    fn assert_send<T: Send>() {}
+    assert_send_sync::<lsl::StreamInfo>();
+    assert_send_sync::<lsl::StreamOutlet>();
+    assert_send_sync::<lsl::ContinuousResolver>();
+    assert_send::<lsl::StreamInlet>();
+    assert_send_sync::<lsl::inlets::SyncInlet>();
+
+    let mut info =
+        lsl::StreamInfo::new("Threads", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    info.desc_mut().append_child_value("manufacturer", "test");
+    let shared = std::sync::Arc::new(info);
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let info = shared.clone();
+            std::thread::spawn(move || info.to_xml().unwrap())
+        })
+        .collect();
+    for reader in readers {
+        assert!(reader
+            .join()
+            .unwrap()
+            .contains("<manufacturer>test</manufacturer>"));
+    }
+}
+
+#[test]
+fn cloned_outlets_share_stream() {
+    use lsl::Pushable;
+    let info = lsl::StreamInfo::new(
+        "Shared",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    assert_eq!(
+        outlet.clone().info().unwrap().uid(),
+        outlet.info().unwrap().uid()
+    );
+    let pushers: Vec<_> = (0..3)
+        .map(|k| {
+            let outlet = outlet.clone();
+            std::thread::spawn(move || outlet.push_sample(&vec![k]))
+        })
+        .collect();
+    drop(outlet);
+    for pusher in pushers {
+        assert!(pusher.join().unwrap().is_ok());
+    }
+}
+
+#[test]
+fn notifier_reports_new_stream() {
+    let info = lsl::StreamInfo::new(
+        "Notified",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    let resolver = lsl::ContinuousResolver::new_with_prop("name", "Notified", 1.0).unwrap();
+    let (found, events) = std::sync::mpsc::channel();
+    let notifier = resolver.notify(move |info| found.send(info.uid()).unwrap(), |_| {});
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let uid = events
+        .recv_timeout(std::time::Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(uid, outlet.info().unwrap().uid());
+    assert!(notifier.stop().is_ok());
+}
+
+#[test]
+fn resolver_tracks_sightings() {
+    let resolver = lsl::ContinuousResolver::new_with_prop("name", "Sighted", 1.0).unwrap();
+    assert!(resolver.sightings().unwrap().is_empty());
+    let info = lsl::StreamInfo::new(
+        "Sighted",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let start = lsl::local_clock();
+    let sighting = loop {
+        if let Some(s) = resolver.sightings().unwrap().pop() {
+            break s;
+        }
+        assert!(lsl::local_clock() - start < 5.0, "stream not found");
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    assert!(sighting.present && sighting.appearances == 1);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let again = resolver.sightings().unwrap().pop().unwrap();
+    assert_eq!(again.first_seen, sighting.first_seen);
+    assert!(again.last_seen > sighting.last_seen);
+}
+
+#[test]
+fn resolve_prop_names() {
+    use lsl::ResolveProp;
+    assert_eq!(ResolveProp::SourceId.name(), "source_id");
+    assert_eq!(
+        ResolveProp::DescPath("manufacturer").name(),
+        "desc/manufacturer"
+    );
+    assert_eq!(ResolveProp::from("type"), ResolveProp::Raw("type"));
+}
+
+#[test]
+fn query_builder_renders_xpath() {
+    use lsl::query::Query;
+    let query = Query::new()
+        .type_is("EEG")
+        .name_starts_with("Bio'Semi")
+        .channel_count(32);
+    assert_eq!(
+        query.as_str(),
+        "(type='EEG') and (starts-with(name,\"Bio'Semi\")) and (channel_count=32)"
+    );
+    let info = lsl::StreamInfo::new(
+        "Bio'Semi 1",
+        "EEG",
+        32,
+        100.0,
+        lsl::ChannelFormat::Float32,
+        "",
+    )
+    .unwrap();
+    assert!(info.matches_query(&query));
+    assert!(!info.matches_query(Query::new().name_is("a'b\"c")));
+    let srate = Query::new().nominal_srate(100.0).unwrap();
+    assert!(info.matches_query(Query::new().type_is("Audio").or(srate)));
+    assert!(info.matches_query(Query::new()));
+    assert_eq!(
+        Query::new().nominal_srate(f64::NAN),
+        Err(lsl::Error::BadArgument)
+    );
+    assert_eq!(
+        Query::new().nominal_srate(f64::INFINITY),
+        Err(lsl::Error::BadArgument)
+    );
+}
+
+#[test]
+fn resolve_macro_expands_to_query() {
+    let found = lsl::resolve!(name == "NoSuchStream" && channel_count == 3, timeout = 0.2);
+    assert!(found.unwrap().is_empty());
+    let prefix = "NoSuch";
+    assert!(lsl::resolve!(type ~= (prefix), min = 0, timeout = 0.1).is_ok());
+}
+
+#[test]
+fn dedup_streams_by_source_id() {
+    let make = |name: &str, source_id: &str| {
+        lsl::StreamInfo::new(
+            name,
+            "EEG",
+            1,
+            100.0,
+            lsl::ChannelFormat::Float32,
+            source_id,
+        )
+        .unwrap()
+    };
+    // streams that were never published have the same creation time, so the first one is kept
+    let streams = vec![
+        make("A", "amp-1"),
+        make("B", ""),
+        make("A2", "amp-1"),
+        make("C", ""),
+    ];
+    let names: Vec<_> = lsl::dedup_streams(streams)
+        .iter()
+        .map(|s| s.stream_name())
+        .collect();
+    assert_eq!(names, ["A", "B", "C"]);
+}
+
+#[test]
+fn host_filters() {
+    use lsl::HostFilter;
+    let info =
+        lsl::StreamInfo::new("Rig", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let host = info.hostname();
+    assert!(HostFilter::Hostname(host.to_uppercase()).matches(&info));
+    assert!(HostFilter::Pattern(format!("{}*", &host[..1])).matches(&info));
+    assert!(HostFilter::Pattern("*".into()).matches(&info));
+    assert!(!HostFilter::Pattern(format!("?{}", host)).matches(&info));
+    // a stream that was not resolved from the network has no addresses
+    assert!(!HostFilter::subnet("0.0.0.0/0").unwrap().matches(&info));
+    assert_eq!(
+        HostFilter::subnet("10.1.0.0/33"),
+        Err(lsl::Error::BadArgument)
+    );
+    assert!(HostFilter::subnet("fd00::/8").is_ok());
+}
+
+#[test]
+fn network_info_of_local_stream() {
+    let info =
+        lsl::StreamInfo::new("Net", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let net = info.network_info().unwrap();
+    assert_eq!(net.hostname, info.hostname());
+    assert_eq!(net.protocol_version, info.version());
+    // a stream that was not resolved from the network has no endpoints yet
+    assert_eq!(
+        (net.v4address, net.v4data_port, net.v6service_port),
+        (None, None, None)
+    );
+    assert!(info.addresses().is_empty());
+}
+
+#[test]
+fn stream_group_without_streams() {
+    use lsl::group::{GroupOptions, StreamGroup};
+    let query = lsl::query::Query::new().name_is("NoSuchStream-7f3a");
+    let group = StreamGroup::new(&query, GroupOptions::default()).unwrap();
+    assert_eq!(group.pull(0.3), Ok(None));
+    assert!(group.pull_available().unwrap().is_empty());
+    assert!(group.streams().is_empty());
+    assert!(group.info("no-such-uid").is_none());
+    assert_eq!(group.stop(), Ok(()));
+}
+
+#[test]
+fn resilient_inlet_pulls_samples() {
+    use lsl::Pushable;
+    let mut info = lsl::StreamInfo::new(
+        "Resilient",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    info.desc_mut().append_child_value("manufacturer", "Test");
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop(lsl::ResolveProp::Name, "Resilient", 1, 5.0).unwrap();
+    let mut inlet = lsl::inlets::ResilientInlet::new(&res[0], 360, 0, 5.0).unwrap();
+    assert_eq!(
+        inlet
+            .info()
+            .clone()
+            .desc()
+            .child_value_named("manufacturer"),
+        "Test"
+    );
+    outlet.push_sample(&vec![7i32]).unwrap();
+    let (sample, ts): (Vec<i32>, _) = inlet.pull_sample(5.0).unwrap();
+    assert_eq!((sample, ts != 0.0), (vec![7], true));
+    assert!(!inlet.is_lost() && inlet.take_resyncs().is_empty());
+}
+
+#[test]
+fn connection_events_report_connected() {
+    let info = lsl::StreamInfo::new(
+        "Connection",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "connection",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop(lsl::ResolveProp::Name, "Connection", 1, 5.0).unwrap();
+    let mut inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    assert!(inlet.take_connection_events().is_empty());
+    inlet.enable_connection_events(15.0);
+    inlet.open_stream(5.0).unwrap();
+    let start = lsl::local_clock();
+    let events = loop {
+        let events = inlet.take_connection_events();
+        if !events.is_empty() {
+            break events;
+        }
+        assert!(lsl::local_clock() - start < 10.0, "not connected");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    assert_eq!(events[0].state, lsl::ConnectionState::Connected);
+}
+
+#[test]
+fn retry_policy_backs_off_on_timeouts() {
+    let policy = lsl::RetryPolicy {
+        max_attempts: 3,
+        initial_delay: 0.01,
+        max_delay: 0.03,
+        ..Default::default()
+    };
+    assert_eq!(
+        (policy.delay(1), policy.delay(2), policy.delay(5)),
+        (0.01, 0.02, 0.03)
+    );
+    let mut attempts = 0;
+    let result: Result<(), _> = policy.retry(|| {
+        attempts += 1;
+        Err(lsl::Error::Timeout)
+    });
+    assert_eq!((result, attempts), (Err(lsl::Error::Timeout), 3));
+    attempts = 0;
+    let result: Result<(), _> = policy.retry(|| {
+        attempts += 1;
+        Err(lsl::Error::StreamLost)
+    });
+    assert_eq!((result, attempts), (Err(lsl::Error::StreamLost), 1));
+}
+
+#[test]
+fn inlet_rebinds_to_another_stream() {
+    use lsl::{Pullable, Pushable};
+    let make = |source_id| {
+        lsl::StreamInfo::new(
+            "Rebind",
+            "Markers",
+            1,
+            lsl::IRREGULAR_RATE,
+            lsl::ChannelFormat::Int32,
+            source_id,
+        )
+        .unwrap()
+    };
+    let _first = lsl::StreamOutlet::new(&make("rebind-a"), 0, 360).unwrap();
+    let second = lsl::StreamOutlet::new(&make("rebind-b"), 0, 360).unwrap();
+    let res = lsl::resolve_byprop(lsl::ResolveProp::SourceId, "rebind-a", 1, 5.0).unwrap();
+    let mut inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    inlet
+        .set_postprocessing(&[lsl::ProcessingOption::ClockSync])
+        .unwrap();
+    let other = lsl::StreamInfo::new(
+        "Other",
+        "Markers",
+        2,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    assert!(matches!(
+        inlet.rebind(&other),
+        Err(lsl::Error::LengthMismatch { .. })
+    ));
+    let res = lsl::resolve_byprop(lsl::ResolveProp::SourceId, "rebind-b", 1, 5.0).unwrap();
+    inlet.rebind(&res[0]).unwrap();
+    assert_eq!(inlet.core_info().source_id(), "rebind-b");
+    inlet.open_stream(5.0).unwrap();
+    second.push_sample(&vec![3i32]).unwrap();
+    let (sample, _): (Vec<i32>, _) = inlet.pull_sample(5.0).unwrap();
+    assert_eq!(sample, vec![3]);
+}
+
+#[test]
+fn channel_descriptors_roundtrip() {
+    use lsl::channels::ChannelDescriptor;
+    let mut info = lsl::StreamInfo::new(
+        "Described",
+        "EEG",
+        2,
+        100.0,
+        lsl::ChannelFormat::Float32,
+        "",
+    )
+    .unwrap();
+    let heog = ChannelDescriptor {
+        chan_type: "EOG".to_string(),
+        range: Some((-500.0, 500.0)),
+        ..ChannelDescriptor::new("HEOG", "microvolts")
+    };
+    let channels = vec![ChannelDescriptor::new("Cz", "microvolts"), heog];
+    assert!(matches!(
+        info.set_channels(&channels[..1]),
+        Err(lsl::Error::LengthMismatch { .. })
+    ));
+    info.set_channels(&channels).unwrap();
+    // declaring them again replaces the previous declaration
+    info.set_channels(&channels).unwrap();
+    let parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    assert_eq!(parsed.channels(), channels);
+    assert_eq!(lsl::channels::labels(&parsed), ["Cz", "HEOG"]);
+}
+
+#[test]
+fn typed_meta_data_fields() {
+    let mut info =
+        lsl::StreamInfo::new("Meta", "EEG", 8, 512.0, lsl::ChannelFormat::Float32, "").unwrap();
+    assert_eq!(info.acquisition().manufacturer(), "");
+    info.acquisition()
+        .set_manufacturer("BioSemi")
+        .set_model("ActiveTwo");
+    info.acquisition().set_manufacturer("BioSemi B.V.");
+    info.cap().set_name("EasyCap").set_label_scheme("10-20");
+    info.subject().set_id("P07");
+    let mut parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    assert_eq!(parsed.acquisition().manufacturer(), "BioSemi B.V.");
+    assert_eq!(parsed.acquisition().model(), "ActiveTwo");
+    assert_eq!(parsed.cap().label_scheme(), "10-20");
+    assert_eq!(parsed.subject().id(), "P07");
+    // setting a field again replaces its value
+    assert!(parsed
+        .desc()
+        .child("acquisition")
+        .child("manufacturer")
+        .next_sibling_named("manufacturer")
+        .empty());
+}
+
+#[test]
+fn meta_data_templates() {
+    let eeg = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "").unwrap();
+    assert_eq!(
+        (eeg.stream_type(), eeg.channel_count()),
+        ("EEG".to_string(), 2)
+    );
+    let channels = eeg.channels();
+    assert_eq!(
+        (channels[1].label.as_str(), channels[1].unit.as_str()),
+        ("C4", "microvolts")
+    );
+    let markers = lsl::StreamInfo::template_markers("Events", "").unwrap();
+    assert_eq!(markers.channel_format(), lsl::ChannelFormat::String);
+    assert_eq!(markers.nominal_srate(), lsl::IRREGULAR_RATE);
+    let gaze = lsl::StreamInfo::template_gaze("Tracker", 60.0, "").unwrap();
+    assert_eq!(
+        gaze.desc()
+            .child("channels")
+            .child("channel")
+            .child_value_named("eye"),
+        "both"
+    );
+    let audio = lsl::StreamInfo::template_audio("Mic", 2, 44100.0, "").unwrap();
+    let labels: Vec<_> = audio.channels().into_iter().map(|c| c.label).collect();
+    assert_eq!(labels, ["Left", "Right"]);
+}
+
+#[test]
+fn validate_xdf_metadata() {
+    use lsl::meta::MetadataIssue;
+    let good = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "").unwrap();
+    assert_eq!(good.validate_xdf_metadata(), []);
+    let mut bad =
+        lsl::StreamInfo::new("Amp", "EEG", 3, 256.0, lsl::ChannelFormat::Float32, "").unwrap();
+    assert_eq!(
+        bad.validate_xdf_metadata(),
+        [MetadataIssue::MissingChannels]
+    );
+    let mut channels = bad.desc_mut().append_child("channels");
+    channels
+        .append_child("channel")
+        .append_child_value("label", "C3")
+        .append_child_value("units", "uV");
+    channels
+        .append_child("channel")
+        .append_child_value("label", "C3")
+        .append_child_value("unit", "uV");
+    let issues = bad.validate_xdf_metadata();
+    assert!(issues.contains(&MetadataIssue::ChannelCountMismatch {
+        declared: 2,
+        expected: 3
+    }));
+    assert!(issues.contains(&MetadataIssue::MisnamedElement {
+        path: "desc/channels/channel[0]/units".to_string(),
+        expected: "unit"
+    }));
+    assert!(issues.contains(&MetadataIssue::MissingUnit { channel: 0 }));
+    assert!(issues.contains(&MetadataIssue::DuplicateLabel {
+        label: "C3".to_string()
+    }));
+    assert!(issues.contains(&MetadataIssue::AbbreviatedUnit {
+        channel: 1,
+        unit: "uV".to_string(),
+        expected: "microvolts"
+    }));
+}
+
+#[test]
+fn bids_entities_roundtrip() {
+    use lsl::bids::BidsInfo;
+    let mut info = lsl::StreamInfo::template_markers("Experiment", "").unwrap();
+    let bids = BidsInfo {
+        task: "rest".to_string(),
+        subject: "01".to_string(),
+        run: Some(3),
+        ..Default::default()
+    };
+    bids.describe(&mut info).unwrap();
+    let mut parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    assert_eq!(BidsInfo::from_info(&parsed), bids);
+    assert_eq!(parsed.subject().id(), "01");
+    assert_eq!(bids.file_prefix(), "sub-01_task-rest_run-3");
+    // describing again removes the entities that are now empty
+    let rerun = BidsInfo {
+        task: "rest".to_string(),
+        ..Default::default()
+    };
+    rerun.describe(&mut info).unwrap();
+    assert_eq!(BidsInfo::from_info(&info), rerun);
+    let invalid = BidsInfo {
+        task: "n-back".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(invalid.describe(&mut info), Err(lsl::Error::BadArgument));
+}
+
+#[test]
+fn sensor_locations_and_fiducials() {
+    use lsl::channels::Fiducial;
+    let mut info = lsl::StreamInfo::template_eeg("Amp", &["Fz", "Cz"], 256.0, "").unwrap();
+    assert_eq!(lsl::channels::locations(&info), [None, None]);
+    assert!(lsl::channels::set_locations(&mut info, &[[0.0, 0.6, 0.8]]).is_err());
+    lsl::channels::set_locations(&mut info, &[[0.0, 0.6, 0.8], [0.0, 0.0, 1.0]]).unwrap();
+    let nasion = Fiducial {
+        label: "Nasion".to_string(),
+        location: [0.0, 1.0, 0.0],
+    };
+    lsl::channels::set_fiducials(&mut info, std::slice::from_ref(&nasion));
+    let parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    assert_eq!(
+        lsl::channels::locations(&parsed),
+        [Some([0.0, 0.6, 0.8]), Some([0.0, 0.0, 1.0])]
+    );
+    assert_eq!(parsed.channels()[0].label, "Fz");
+    assert_eq!(lsl::channels::fiducials(&parsed), [nasion]);
+}
+
+#[test]
+fn acquisition_info_roundtrip() {
+    use lsl::meta::AcquisitionInfo;
+    let mut info =
+        lsl::StreamInfo::new("Amp", "EEG", 8, 512.0, lsl::ChannelFormat::Float32, "").unwrap();
+    assert_eq!(
+        AcquisitionInfo::from_info(&info),
+        AcquisitionInfo::default()
+    );
+    info.acquisition().set_manufacturer("BioSemi");
+    let acquisition = AcquisitionInfo {
+        model: "ActiveTwo".to_string(),
+        precision: Some(24),
+        compensated_lag: Some(0.008),
+        ..Default::default()
+    };
+    acquisition.describe(&mut info);
+    let parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    let parsed = AcquisitionInfo::from_info(&parsed);
+    assert_eq!(
+        parsed,
+        AcquisitionInfo {
+            manufacturer: "BioSemi".to_string(),
+            ..acquisition
+        }
+    );
+}
+
+#[test]
+fn subject_and_session_info_roundtrip() {
+    use lsl::meta::{SessionInfo, SubjectInfo};
+    let mut info = lsl::StreamInfo::template_markers("Experiment", "").unwrap();
+    let subject = SubjectInfo {
+        id: "P07".to_string(),
+        group: "control".to_string(),
+        age: Some(31),
+        handedness: "left".to_string(),
+        ..Default::default()
+    };
+    let session = SessionInfo {
+        id: "pre".to_string(),
+        date: "2024-03-14".to_string(),
+        ..Default::default()
+    };
+    subject.describe(&mut info);
+    session.describe(&mut info);
+    let parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    assert_eq!(SubjectInfo::from_info(&parsed), subject);
+    assert_eq!(SessionInfo::from_info(&parsed), session);
+    assert_eq!(lsl::bids::BidsInfo::from_info(&parsed).session, "pre");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn stream_info_serde_roundtrip() {
+    let info = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "amp-1").unwrap();
+    let json = serde_json::to_string(&info).unwrap();
+    let parsed: lsl::StreamInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        (parsed.stream_name(), parsed.source_id()),
+        (info.stream_name(), info.source_id())
+    );
+    assert_eq!(parsed.uid(), info.uid());
+    assert_eq!(parsed.channels(), info.channels());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn desc_json_roundtrip() {
+    use serde_json::json;
+    let mut info =
+        lsl::StreamInfo::new("Meta", "EEG", 2, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let desc = json!({
+        "acquisition": {"manufacturer": "BioSemi", "precision": 24},
+        "channels": {"channel": [{"label": "C3"}, {"label": "C4"}]},
+        "notes": null,
+    });
+    info.desc_mut().from_json(&desc).unwrap();
+    let parsed = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    let converted = parsed.desc().to_json();
+    assert_eq!(converted["acquisition"]["precision"], "24");
+    assert_eq!(converted["channels"], desc["channels"]);
+    assert_eq!(converted["notes"], "");
+    assert_eq!(
+        info.desc_mut().from_json(&json!(["C3"])),
+        Err(lsl::Error::BadArgument)
+    );
+}
+
+#[test]
+fn stream_info_core_setters() {
+    let mut info =
+        lsl::StreamInfo::new("Amp", "", 2, 100.0, lsl::ChannelFormat::Float32, "amp-1").unwrap();
+    info.desc_mut()
+        .append_child_value("name", "not the stream name");
+    let uid = info.uid();
+    info.set_name("Amp <relayed>").unwrap();
+    info.set_type("EEG").unwrap();
+    info.set_nominal_srate(50.0).unwrap();
+    info.set_source_id("relay-1").unwrap();
+    assert_eq!(
+        (info.stream_name(), info.stream_type()),
+        ("Amp <relayed>".to_string(), "EEG".to_string())
+    );
+    assert_eq!(
+        (info.nominal_srate(), info.source_id()),
+        (50.0, "relay-1".to_string())
+    );
+    assert_eq!((info.uid(), info.channel_count()), (uid, 2));
+    assert_eq!(info.desc().child_value_named("name"), "not the stream name");
+    assert_eq!(info.set_name(""), Err(lsl::Error::BadArgument));
+    assert_eq!(info.set_nominal_srate(-1.0), Err(lsl::Error::BadArgument));
+}
+
+#[test]
+fn stream_info_equality() {
+    let mut info = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "amp-1").unwrap();
+    let copy = lsl::StreamInfo::from_xml(&info.to_xml().unwrap()).unwrap();
+    let other = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "amp-1").unwrap();
+    assert!(info == copy && info == other);
+    assert_eq!(info.fingerprint(), other.fingerprint());
+    info.subject().set_id("P07");
+    assert!(info != other);
+    assert_ne!(info.fingerprint(), other.fingerprint());
+}
+
+#[test]
+fn stream_info_save_and_load() {
+    let path = std::env::temp_dir().join(format!("lsl-test-saved-info-{}.xml", std::process::id()));
+    let mut info = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "amp-1").unwrap();
+    info.cap().set_name("EasyCap");
+    info.save_xml(&path).unwrap();
+    let loaded = lsl::StreamInfo::load_xml(&path);
+    let newer = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("format version 1", "format version 2");
+    std::fs::write(&path, newer).unwrap();
+    let result = lsl::StreamInfo::load_xml(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(loaded.unwrap() == info);
+    assert!(matches!(result, Err(lsl::Error::Parse { line: 2, .. })));
+}
+
+#[test]
+fn stream_info_from_invalid_xml() {
+    let parse = |xml: &str| match lsl::StreamInfo::from_xml(xml) {
+        Err(lsl::Error::Parse { line, reason }) => Some((line, reason)),
+        _ => None,
+    };
+    assert_eq!(
+        parse("<info>\n<name>A</name>\n<type>EEG</name>\n</info>"),
+        Some((3, "mismatched end tag"))
+    );
+    assert_eq!(
+        parse("<info>\n<name>A</name>\n"),
+        Some((1, "unclosed element"))
+    );
+    assert_eq!(
+        parse("<stream></stream>"),
+        Some((1, "missing <info> element"))
+    );
+    let info = "<info>\n<name>A</name>\n<channel_count>-2</channel_count>\n\
+        <nominal_srate>100</nominal_srate>\n<channel_format>float32</channel_format>\n</info>";
+    assert_eq!(parse(info), Some((3, "invalid channel count")));
+    assert!(lsl::StreamInfo::from_xml(&info.replace("-2", "2")).is_ok());
+}
+
+#[test]
+fn stream_info_pretty_xml() {
+    let mut info = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "").unwrap();
+    info.desc_mut().append_child("notes");
+    let xml = info.to_xml_pretty(2).unwrap();
+    assert!(xml.contains("\n  <name>Amp</name>\n"));
+    assert!(xml.contains("\n      <channel>\n        <label>C3</label>\n"));
+    assert!(xml.contains("\n    <notes />\n") || xml.contains("\n    <notes/>\n"));
+    assert!(lsl::StreamInfo::from_xml(&xml).unwrap() == info);
+}
+
+#[test]
+fn stream_info_diff_and_merge() {
+    use lsl::meta::{Difference, MergePolicy};
+    let mut template = lsl::StreamInfo::template_eeg("Amp", &["C3", "C4"], 256.0, "").unwrap();
+    template.acquisition().set_manufacturer("BioSemi");
+    let mut runtime = lsl::StreamInfo::template_eeg("Amp", &["C3", "Cz"], 512.0, "").unwrap();
+    runtime.acquisition().set_model("ActiveTwo");
+    let differences = template.diff(&runtime);
+    assert!(differences.contains(&Difference {
+        path: "nominal_srate".to_string(),
+        left: Some("256".to_string()),
+        right: Some("512".to_string())
+    }));
+    assert!(differences.contains(&Difference {
+        path: "desc/channels/channel[1]/label".to_string(),
+        left: Some("C4".to_string()),
+        right: Some("Cz".to_string())
+    }));
+    assert!(differences.contains(&Difference {
+        path: "desc/acquisition/model".to_string(),
+        left: None,
+        right: Some("ActiveTwo".to_string())
+    }));
+    let mut merged = runtime.clone();
+    merged.merge_desc(&template, MergePolicy::KeepExisting);
+    assert_eq!(lsl::channels::labels(&merged), ["C3", "Cz"]);
+    assert_eq!(
+        (
+            merged.acquisition().manufacturer(),
+            merged.acquisition().model()
+        ),
+        ("BioSemi".to_string(), "ActiveTwo".to_string())
+    );
+    runtime.merge_desc(&template, MergePolicy::Overwrite);
+    assert_eq!(lsl::channels::labels(&runtime), ["C3", "C4"]);
+}
+
+#[test]
+fn merge_overwrite_keeps_order() {
+    let mut target =
+        lsl::StreamInfo::new("Amp", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    target.desc_mut().append_child("reference");
+    target.desc_mut().append_child_value("filter", "none");
+    let mut source = target.clone();
+    source
+        .desc_mut()
+        .child("reference")
+        .append_child_value("label", "Cz");
+    target.merge_desc(&source, lsl::meta::MergePolicy::Overwrite);
+    let first = target.desc().first_child();
+    assert_eq!(
+        (first.name(), first.child_value_named("label")),
+        ("reference".to_string(), "Cz".to_string())
+    );
+    assert_eq!(first.next_sibling().name(), "filter");
+}
+
+#[test]
+fn bandwidth_and_buffer_estimates() {
+    let eeg =
+        lsl::StreamInfo::new("Amp", "EEG", 32, 500.0, lsl::ChannelFormat::Float32, "").unwrap();
+    assert_eq!(eeg.estimated_bandwidth(), Some(500.0 * (32.0 * 4.0 + 8.0)));
+    assert_eq!(eeg.buflen_for_duration(2.5, 0.0), 3);
+    assert_eq!(eeg.estimated_buffer_bytes(10), Some(5000 * (32 * 4 + 8)));
+    let events = lsl::StreamInfo::new(
+        "Events",
+        "Events",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    assert_eq!(events.estimated_bandwidth(), None);
+    assert_eq!(events.buflen_for_duration(60.0, 10.0), 6);
+    assert_eq!(events.estimated_buffer_bytes(6), Some(600 * 12));
+    let markers = lsl::StreamInfo::template_markers("Markers", "").unwrap();
+    assert_eq!(markers.estimated_buffer_bytes(1), None);
+}
+
+#[test]
+fn xml_attributes() {
+    let xml = "<?xml version=\"1.0\"?>\n<info><name>Amp</name><type>EEG</type>\
+        <channel_count>2</channel_count><nominal_srate>100</nominal_srate>\
+        <channel_format>float32</channel_format><desc><channels>\
+        <channel id=\"1\" range='x > 0'><label>C3</label></channel>\
+        <channel id=\"2\" ref='A &amp; B'><label>C4</label></channel>\
+        </channels></desc></info>";
+    let mut info = lsl::StreamInfo::from_xml(xml).unwrap();
+    let second = info
+        .desc()
+        .child("channels")
+        .child("channel")
+        .next_sibling_named("channel");
+    assert_eq!(second.attribute("ref"), Some("A & B".to_string()));
+    assert_eq!(
+        second.attributes(),
+        [
+            ("id".to_string(), "2".to_string()),
+            ("ref".to_string(), "A & B".to_string())
+        ]
+    );
+    assert_eq!(second.child("label").attribute("id"), None);
+    let first = info.desc().child("channels").child("channel");
+    assert_eq!(first.attribute("range"), Some("x > 0".to_string()));
+    let second = info.set_attribute(&second, "ref", "Cz").unwrap();
+    assert_eq!(second.attribute("ref"), Some("Cz".to_string()));
+    assert_eq!(second.attribute("id"), Some("2".to_string()));
+    assert!(matches!(
+        info.set_attribute(&second, "a b", ""),
+        Err(lsl::Error::BadArgument)
+    ));
+}
+
+#[test]
+fn typed_xml_values() {
+    let mut info =
+        lsl::StreamInfo::new("Amp", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let mut settings = info.desc_mut().append_child("settings");
+    settings
+        .set_child_value_as("gain", 12.5)
+        .set_child_value_as("enabled", true);
+    settings.set_child_value_as("gain", 24.0);
+    settings.append_child_value("mode", "fast");
+    let settings = info.desc().child("settings");
+    assert_eq!(settings.child_value_as::<f64>("gain"), Ok(Some(24.0)));
+    assert_eq!(settings.child_value_as::<bool>("enabled"), Ok(Some(true)));
+    assert_eq!(settings.child_value_as::<u32>("channels"), Ok(None));
+    assert_eq!(
+        settings.child_value_as::<i32>("mode"),
+        Err(lsl::Error::InvalidValue {
+            field: "mode".to_string(),
+            text: "fast".to_string()
+        })
+    );
+}
+
+#[test]
+fn find_by_path() {
+    let mut info =
+        lsl::StreamInfo::new("Amp", "EEG", 3, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let mut channels = info.desc_mut().append_child("channels");
+    for (label, gain) in [("C3", 1.0), ("Cz", 2.0), ("C4", 4.0)].iter() {
+        channels
+            .append_child("channel")
+            .append_child_value("label", label)
+            .set_child_value_as("gain", *gain);
+    }
+    let desc = info.desc();
+    assert_eq!(
+        desc.find("channels/channel/label").unwrap().child_value(),
+        "C3"
+    );
+    assert_eq!(
+        desc.find("channels/channel[3]/label")
+            .unwrap()
+            .child_value(),
+        "C4"
+    );
+    assert_eq!(
+        desc.find_value_as::<f64>("channels/channel[2]/gain"),
+        Ok(Some(2.0))
+    );
+    assert!(desc.find("channels/channel[4]").is_none());
+    assert!(desc.find("channels/channel[0]").is_none());
+    assert!(desc.find("channels/channel[x]").is_none());
+    assert_eq!(
+        desc.find_value_as::<f64>("channels/channel[2]/unit"),
+        Ok(None)
+    );
+}
+
+#[test]
+fn fallible_xml_modification() {
+    let mut info =
+        lsl::StreamInfo::new("Amp", "EEG", 1, 100.0, lsl::ChannelFormat::Float32, "").unwrap();
+    let mut settings = info.desc_mut().try_append_child("settings").unwrap();
+    settings.try_append_child_value("mode", "fast").unwrap();
+    assert_eq!(
+        settings.try_append_child_value("mode", "a\0b").err(),
+        Some(lsl::Error::BadArgument)
+    );
+    assert_eq!(settings.try_set_child_value("mode", "slow"), Ok(()));
+    assert_eq!(
+        settings.try_set_child_value("gain", "2"),
+        Err(lsl::Error::InvalidElement)
+    );
+    assert_eq!(
+        settings.try_remove_child_named("gain"),
+        Err(lsl::Error::InvalidElement)
+    );
+    let mut missing = info.desc_mut().child("missing");
+    assert_eq!(
+        missing.try_append_child("x").err(),
+        Some(lsl::Error::InvalidElement)
+    );
+    assert_eq!(missing.try_set_name("y"), Err(lsl::Error::InvalidElement));
+    assert_eq!(
+        info.desc().child("settings").child_value_named("mode"),
+        "slow"
+    );
+}
+
+#[test]
+fn buffer_spec_units() {
+    use lsl::BufferSpec;
+    use std::time::Duration;
+    assert_eq!(
+        BufferSpec::Duration(Duration::from_secs(30)).to_buflen(500.0),
+        30
+    );
+    assert_eq!(
+        BufferSpec::Duration(Duration::from_millis(1500)).to_buflen(500.0),
+        2
+    );
+    assert_eq!(BufferSpec::Samples(1000).to_buflen(500.0), 2);
+    assert_eq!(BufferSpec::Samples(1001).to_buflen(500.0), 3);
+    assert_eq!(BufferSpec::Samples(250).to_buflen(lsl::IRREGULAR_RATE), 3);
+    assert_eq!(BufferSpec::Samples(0).to_buflen(100.0), 1);
+    // durations of irregular streams are converted as by `buflen_for_duration()` at 100 Hz
+    let events = lsl::StreamInfo::new(
+        "Events",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    let spec = BufferSpec::Duration(Duration::from_secs(90));
+    assert_eq!(
+        spec.to_buflen(lsl::IRREGULAR_RATE),
+        events.buflen_for_duration(90.0, 100.0)
+    );
+}
+
+#[test]
+fn wall_clock_conversion() {
+    let now = lsl::local_clock();
+    let system = lsl::time::lsl_to_system(now).unwrap();
+    let elapsed = match std::time::SystemTime::now().duration_since(system) {
+        Ok(elapsed) => elapsed,
+        Err(e) => e.duration(),
+    };
+    assert!(elapsed.as_secs_f64() < 0.1);
+    assert!((lsl::time::system_to_lsl(system) - now).abs() < 0.01);
+    assert_eq!(lsl::time::lsl_to_system(f64::NAN), None);
+    assert_eq!(lsl::time::lsl_to_system(f64::INFINITY), None);
+    assert_eq!(lsl::time::lsl_to_system(1e300), None);
+}
+
+#[test]
+fn unix_offset_estimate() {
+    use std::time::Duration;
+    let estimate = lsl::time::estimate_unix_offset(5, Duration::from_millis(20)).unwrap();
+    assert_eq!(estimate.samples, 5);
+    assert!(estimate.lower <= estimate.offset && estimate.offset <= estimate.upper);
+    assert!((estimate.offset - lsl::time::unix_offset()).abs() < 0.01);
+    assert_eq!(
+        lsl::time::estimate_unix_offset(0, Duration::from_secs(1)),
+        Err(lsl::Error::BadArgument)
+    );
+}
+
+#[test]
+fn clock_drift_model() {
+    use lsl::clocksync::ClockSync;
+    use lsl::TimeCorrection;
+    let mut sync = ClockSync::new(100);
+    assert_eq!(sync.map_remote_to_local(10.0), None);
+    // an offset of 2 s at remote time 0, drifting by 50 ppm, measured every 5 s
+    for k in 0..20 {
+        let remote_time = 5.0 * k as f64;
+        let noise = if k % 2 == 0 { 1e-5 } else { -1e-5 };
+        let offset = 2.0 + 50e-6 * remote_time + noise;
+        sync.add(TimeCorrection {
+            remote_time,
+            offset,
+            rtt: 2e-4,
+        });
+    }
+    // a measurement that was delayed on the network
+    sync.add(TimeCorrection {
+        remote_time: 102.0,
+        offset: 2.5,
+        rtt: 0.1,
+    });
+    let model = sync.model().unwrap();
+    assert_eq!(model.measurements, 20);
+    assert!((model.drift - 50e-6).abs() < 1e-6);
+    assert!((sync.map_remote_to_local(200.0).unwrap() - (200.0 + 2.0 + 0.01)).abs() < 1e-4);
+    sync.reset();
+    assert!(sync.measurements().is_empty());
+}
+
+#[test]
+fn channel_bridge_forwards_pull_errors() {
+    let info = lsl::StreamInfo::new(
+        "Bridged",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::String,
+        "bridged",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop("source_id", "bridged", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    // pulling numbers from a string-formatted stream fails
+    let samples = inlet.into_channel::<f32>(10);
+    assert!(matches!(
+        samples.recv(),
+        Ok(Err(lsl::Error::FormatMismatch))
+    ));
+    assert!(samples.recv().is_err());
+}
+
+#[test]
+fn channel_bridge_stops_when_dropped() {
+    let info = lsl::StreamInfo::new(
+        "Idle",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "idle",
+    )
+    .unwrap();
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop("source_id", "idle", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    let samples = inlet.into_channel::<i32>(10);
+    assert!(outlet.wait_for_consumers(5.0));
+    // no sample is ever pushed, so the reader must notice the drop while waiting for data
+    drop(samples);
+    let deadline = lsl::local_clock() + 5.0;
+    while outlet.have_consumers() && lsl::local_clock() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(!outlet.have_consumers());
+}
+
+#[test]
+fn inlet_records_time_correction_history() {
+    let info = lsl::StreamInfo::new(
+        "Corrected",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "corrected",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop("source_id", "corrected", 1, 5.0).unwrap();
+    let mut inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    assert!(inlet.time_correction_history().is_empty());
+    inlet.open_stream(5.0).unwrap();
+    inlet.enable_time_correction_history(10, 0.1);
+    let start = lsl::local_clock();
+    let history = loop {
+        let history = inlet.time_correction_history();
+        if !history.is_empty() {
+            break history;
+        }
+        assert!(lsl::local_clock() - start < 10.0, "no measurement");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    // (the stream is local, so both clocks are the same)
+    assert!(history[0].offset.abs() < 0.01 && history[0].rtt >= 0.0);
+    inlet.disable_time_correction_history();
+    assert!(inlet.time_correction_history().is_empty());
+}
+
+// A waker that unparks the current thread, for driving futures and streams without a runtime.
+#[cfg(any(feature = "futures", feature = "async-std", feature = "smol"))]
+fn thread_waker() -> std::task::Waker {
+    struct Unpark(std::thread::Thread);
+    impl std::task::Wake for Unpark {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    std::sync::Arc::new(Unpark(std::thread::current())).into()
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn inlet_into_stream_yields_samples() {
+    use futures_core::Stream;
+    use lsl::Pushable;
+    use std::task::{Context, Poll};
+    let info = lsl::StreamInfo::new(
+        "Streamed",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "streamed",
+    )
+    .unwrap();
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop("source_id", "streamed", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    inlet.open_stream(5.0).unwrap();
+    let mut samples = inlet.into_stream::<i32>();
+    outlet.push_sample(&vec![7]).unwrap();
+    let waker = thread_waker();
+    let mut cx = Context::from_waker(&waker);
+    let sample = loop {
+        match std::pin::Pin::new(&mut samples).poll_next(&mut cx) {
+            Poll::Ready(sample) => break sample,
+            Poll::Pending => std::thread::park_timeout(std::time::Duration::from_secs(5)),
+        }
+    };
+    assert_eq!(sample.unwrap().unwrap().data, vec![7]);
+}
+
+// Run a future to completion on the current thread.
+#[cfg(any(feature = "async-std", feature = "smol"))]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let waker = thread_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park_timeout(std::time::Duration::from_secs(5));
+    }
+}
+
+#[cfg(any(feature = "async-std", feature = "smol"))]
+#[test]
+fn async_inlet_without_runtime() {
+    use lsl::aio::{AsyncStreamInlet, AsyncStreamOutlet};
+    let info = lsl::StreamInfo::new(
+        "Awaited",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "awaited",
+    )
+    .unwrap();
+    let outlet = AsyncStreamOutlet::new(lsl::StreamOutlet::new(&info, 0, 360).unwrap());
+    let res = lsl::resolve_byprop("source_id", "awaited", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    inlet.open_stream(5.0).unwrap();
+    let inlet = AsyncStreamInlet::new(inlet);
+    outlet.push_sample(&vec![7]).unwrap();
+    // (the blocking calls run on the thread pool of the `blocking` crate, so no runtime is needed)
+    let (sample, ts) = block_on(inlet.pull_sample::<i32>(5.0)).unwrap();
+    assert_eq!((sample, ts != 0.0), (vec![7], true));
+}
+
+#[cfg(any(feature = "async-std", feature = "smol"))]
+#[test]
+fn async_inlet_opens_and_corrects() {
+    let info = lsl::StreamInfo::new(
+        "Opened",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "opened",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let res = lsl::resolve_byprop("source_id", "opened", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    let inlet = lsl::aio::AsyncStreamInlet::new(inlet);
+    block_on(inlet.open_stream(5.0)).unwrap();
+    // (the stream is local, so both clocks are the same)
+    let offset = block_on(inlet.time_correction(5.0)).unwrap();
+    assert!(offset.abs() < 0.01);
+}
+
+#[test]
+fn presence_follows_stream() {
+    let presence = lsl::presence("name='Present'").unwrap();
+    assert!(!presence.is_present().unwrap());
+    let info = lsl::StreamInfo::new(
+        "Present",
+        "Markers",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::Int32,
+        "",
+    )
+    .unwrap();
+    let _outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let start = lsl::local_clock();
+    while !presence.changed().unwrap() {
+        assert!(lsl::local_clock() - start < 10.0, "not found");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(presence.is_present().unwrap());
+    assert_eq!(presence.streams().unwrap()[0].stream_name(), "Present");
+    assert!(!presence.changed().unwrap());
+}
+
+#[test]
+fn fragmented_blobs_roundtrip() {
+    use lsl::fragment::{Assembler, Fragmenter};
+    let mut info = lsl::StreamInfo::new(
+        "Fragmented",
+        "Video",
+        1,
+        lsl::IRREGULAR_RATE,
+        lsl::ChannelFormat::String,
+        "fragmented",
+    )
+    .unwrap();
+    lsl::fragment::declare(&mut info, 16);
+    let outlet = lsl::StreamOutlet::new(&info, 0, 360).unwrap();
+    let mut fragmenter = Fragmenter::new(outlet, 16).unwrap();
+    let res = lsl::resolve_byprop("source_id", "fragmented", 1, 5.0).unwrap();
+    let inlet = lsl::StreamInlet::new(&res[0], 360, 0, true).unwrap();
+    assert!(lsl::fragment::is_declared(&inlet.info(5.0).unwrap()));
+    inlet.open_stream(5.0).unwrap();
+    let mut assembler = Assembler::new(inlet);
+    let blob: Vec<u8> = (0..50).collect();
+    fragmenter.push(&blob, 1.0, true).unwrap();
+    fragmenter.push(&[], 2.0, true).unwrap();
+    assert_eq!(assembler.pull(5.0).unwrap(), Some((blob, 1.0)));
+    assert_eq!(assembler.pull(5.0).unwrap(), Some((vec![], 2.0)));
+    assert_eq!(assembler.pull(0.0).unwrap(), None);
+}